@@ -0,0 +1,123 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wait_human::{AskOptions, ConfirmationId, WaitHuman, WaitHumanConfig};
+
+fn client_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("client_construction");
+
+    group.bench_function("shared_pool", |b| {
+        b.iter(|| {
+            WaitHuman::new(WaitHumanConfig::new("bench-key").with_shared_http_client()).unwrap()
+        })
+    });
+
+    group.bench_function("own_pool", |b| {
+        b.iter(|| WaitHuman::new_from_key("bench-key").unwrap())
+    });
+
+    group.finish();
+}
+
+/// Cost of gzip-compressing request bodies of various sizes, which justifies sending
+/// bodies up to 1 KiB uncompressed with the `compression` feature
+fn request_body(c: &mut Criterion) {
+    let mut group = c.benchmark_group("request_body");
+
+    for size in [128, 1024, 64 * 1024] {
+        let body = serde_json::to_vec(&serde_json::json!({ "body": "x".repeat(size) })).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("gzip", size), &body, |b, body| {
+            b.iter(|| {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body).unwrap();
+                encoder.finish().unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Polls of each wait of the `poll_loop` benchmark
+const POLLS_PER_WAIT: u64 = 100;
+
+const PENDING: &[u8] = b"HTTP/1.1 204 No Content\r\n\r\n";
+
+const ANSWERED_BODY: &str = r#"{"maybe_answer":{"answer":{"answer_content":{"type":"free_text","text":"ok"}},"answered_at":"2024-05-01T12:00:00Z"}}"#;
+
+/// Serves polls on a local port, answering every `POLLS_PER_WAIT`th one and reporting
+/// the others pending, over kept-alive connections
+async fn poll_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let uri = format!("http://{}", listener.local_addr().unwrap());
+    let polls = Arc::new(AtomicU64::new(0));
+    let answered = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+        ANSWERED_BODY.len(),
+        ANSWERED_BODY
+    );
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let polls = polls.clone();
+            let answered = answered.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                while let Ok(read @ 1..) = stream.read(&mut buf).await {
+                    request.extend_from_slice(&buf[..read]);
+                    // Polls are GETs without a body, so each ends with the blank line
+                    while let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        request.drain(..end + 4);
+                        let poll = polls.fetch_add(1, Ordering::Relaxed) + 1;
+                        let response = if poll.is_multiple_of(POLLS_PER_WAIT) {
+                            answered.as_bytes()
+                        } else {
+                            PENDING
+                        };
+                        if stream.write_all(response).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    uri
+}
+
+/// A wait of `POLLS_PER_WAIT` polls a millisecond apart against a local server, so the
+/// cost of each poll on the client's side shows next to the loopback round trip and
+/// the timer
+fn poll_loop(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let uri = runtime.block_on(poll_server());
+    let client =
+        WaitHuman::new(WaitHumanConfig::new("bench-key").with_endpoint(uri.as_str())).unwrap();
+    let confirmation_id: ConfirmationId = "conf_bench".parse().unwrap();
+    let options = AskOptions::new()
+        .with_poll_interval(Duration::from_millis(1))
+        .with_poll_jitter(0.0);
+
+    let mut group = c.benchmark_group("poll_loop");
+    group.throughput(Throughput::Elements(POLLS_PER_WAIT));
+    group.bench_function("wait_for_answer", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(client.wait_for_answer(&confirmation_id, Some(options.clone())))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, client_construction, request_body, poll_loop);
+criterion_main!(benches);
@@ -26,6 +26,57 @@ fn main() {
     }
 }
 
+/// Fields and variants the client reads ahead of the backend's shared types, added at
+/// the end of the item whose declaration starts with the first string
+///
+/// Each is dropped from here once the backend declares it, which otherwise fails the
+/// build with a duplicate.
+const CLIENT_ADDITIONS: &[(&str, &str)] = &[
+    (
+        "pub struct ConfirmationAnswer {",
+        r#"    /// Comment the human gave with the answer, e.g. the reason for a rejection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+"#,
+    ),
+    (
+        "pub struct ConfirmationAnswerWithDate {",
+        r#"    /// Who was notified, as the server resolved `AskOptions::recipient`, if it reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    /// Index in `AskOptions::auto_answer_rules` of the rule that answered, if one did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_answer_rule: Option<usize>,
+"#,
+    ),
+    (
+        "pub enum AnswerFormat {",
+        r#"    /// A single choice the human searches for, among choices the client supplies for
+    /// each query they type
+    Search,
+    /// A file the human provides, such as a signed PDF or a photo
+    Binary {
+        /// Content types accepted, e.g. `application/pdf` or `image/*`; any if empty
+        #[serde(default)]
+        content_types: Vec<String>,
+    },
+"#,
+    ),
+    (
+        "pub enum AnswerContent {",
+        r#"    /// The choice selected among search results
+    Search { key: String, label: String },
+    /// Text typed in the "other" field of an options question, instead of a choice
+    Other { text: String },
+    /// A file the human provided, base64-encoded on the wire
+    Binary {
+        content_type: String,
+        data: Base64Bytes,
+    },
+"#,
+    ),
+];
+
 fn transform_shared_types(content: String) -> String {
     use regex::Regex;
 
@@ -38,8 +89,91 @@ fn transform_shared_types(content: String) -> String {
     let content =
         re_derive.replace_all(&content, "#[derive(Serialize, Deserialize, Clone, Debug)]");
 
+    let content = add_client_additions(&content);
+    let content = add_camel_case_aliases(&content);
+
     // Add header comment and imports
     let header = "// This file is auto-generated by build.rs from ../app/backend/src/shared_types.rs\n// Do not edit manually - changes will be overwritten\n\n";
-    let imports = "use serde::{Deserialize, Serialize};\nuse chrono::{DateTime, Utc};\n\n";
+    // Client-side types the mirrored ones refer to
+    let imports = "use crate::types::Base64Bytes;\nuse serde::{Deserialize, Serialize};\nuse chrono::{DateTime, Utc};\n\n";
     format!("{}{}{}", header, imports, content)
 }
+
+/// Inserts [`CLIENT_ADDITIONS`] before the closing brace of their items
+fn add_client_additions(content: &str) -> String {
+    let mut content = content.to_string();
+    for (item, addition) in CLIENT_ADDITIONS {
+        let start = content
+            .find(item)
+            .unwrap_or_else(|| panic!("`{}` not found in the shared types", item));
+        let end = start
+            + content[start..]
+                .find("\n}")
+                .unwrap_or_else(|| panic!("`{}` isn't closed", item))
+            + 1;
+        content.insert_str(end, addition);
+    }
+    content
+}
+
+/// Lets the mirrored types read camelCase JSON too, as some API gateways rewrite field
+/// names: every snake_case field, and every multi-word variant of a snake_case enum,
+/// gets a `#[serde(alias = "...")]` with its camelCase name
+fn add_camel_case_aliases(content: &str) -> String {
+    use regex::Regex;
+
+    let re_field = Regex::new(r"^(\s+)(?:pub )?([a-z][a-z0-9]*(?:_[a-z0-9]+)+):").unwrap();
+    let re_variant = Regex::new(r"^(\s+)([A-Z][a-z0-9]*(?:[A-Z][a-z0-9]*)+)\b").unwrap();
+
+    let mut lines = Vec::new();
+    let mut snake_case_enum = false;
+    let mut attributes = String::new();
+    for line in content.lines() {
+        if line.starts_with("#[") {
+            attributes.push_str(line);
+        } else if line.starts_with("pub enum ") {
+            snake_case_enum = attributes.contains(r#"rename_all = "snake_case""#);
+        } else if line.starts_with('}') {
+            snake_case_enum = false;
+        }
+        if !line.starts_with("#[") {
+            attributes.clear();
+        }
+
+        if let Some(field) = re_field.captures(line) {
+            lines.push(alias(&field[1], &snake_to_camel(&field[2])));
+        } else if let Some(variant) = re_variant.captures(line).filter(|_| snake_case_enum) {
+            lines.push(alias(&variant[1], &pascal_to_camel(&variant[2])));
+        }
+        lines.push(line.to_string());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn alias(indent: &str, name: &str) -> String {
+    format!("{}#[serde(alias = \"{}\")]", indent, name)
+}
+
+/// `answer_content` to `answerContent`
+fn snake_to_camel(name: &str) -> String {
+    let mut words = name.split('_');
+    let mut camel = words.next().unwrap_or_default().to_string();
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
+/// `FreeText` to `freeText`
+fn pascal_to_camel(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
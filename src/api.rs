@@ -0,0 +1,172 @@
+//! Object-safe interface to the client, for code depending on an abstraction
+
+use crate::client::WaitHuman;
+use crate::error::{Result, WaitHumanError};
+use crate::types::{
+    AnswerFormat, AskOptions, ConfirmationAnswerWithDate, ConfirmationQuestion, QuestionMethod,
+};
+use crate::validation::{choice_index, FreeTextChecks};
+use futures_util::future::BoxFuture;
+
+/// The core operations of [`WaitHuman`], as a trait usable behind `Box<dyn WaitHumanApi>`
+///
+/// Lets application code take any implementation, such as a mock in tests, a proxy
+/// recording the questions asked or a router spreading them over several backends,
+/// instead of the concrete client. The methods take owned arguments and return boxed
+/// futures, so the trait stays dyn-compatible where the generic methods of `WaitHuman`
+/// aren't.
+///
+/// Only [`ask`](Self::ask) is required: the typed methods default to implementations
+/// built on it. `WaitHuman` implements them with its own typed methods when the
+/// `convenience` feature is enabled.
+///
+/// Not available on wasm targets, where the client's futures aren't `Send`.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::future::BoxFuture;
+/// use wait_human::{
+///     AnswerContent, AnswerSource, AskOptions, ConfirmationAnswer, ConfirmationAnswerWithDate,
+///     ConfirmationQuestion, WaitHumanApi,
+/// };
+///
+/// /// Approves everything, for tests
+/// struct AlwaysYes;
+///
+/// impl WaitHumanApi for AlwaysYes {
+///     fn ask(
+///         &self,
+///         _question: ConfirmationQuestion,
+///         _options: Option<AskOptions>,
+///     ) -> BoxFuture<'_, wait_human::Result<ConfirmationAnswerWithDate>> {
+///         Box::pin(async {
+///             Ok(ConfirmationAnswerWithDate {
+///                 answer: ConfirmationAnswer {
+///                     answer_content: AnswerContent::FreeText { text: "yes".to_string() },
+///                     attachments: Vec::new(),
+///                     signature: None,
+///                     confidence: None,
+///                     comment: None,
+///                 },
+///                 answered_at: chrono::Utc::now(),
+///                 metadata: None,
+///                 answered_by: AnswerSource::Automation,
+///                 locale: None,
+///                 tags: Vec::new(),
+///                 question: None,
+///                 due_at: None,
+///                 recipient: None,
+///                 auto_answer_rule: None,
+///             })
+///         })
+///     }
+/// }
+///
+/// async fn deploy(approvals: &dyn WaitHumanApi) -> wait_human::Result<bool> {
+///     let answer = approvals
+///         .ask_free_text("Deploy?".to_string(), None, None)
+///         .await?;
+///     Ok(answer == "yes")
+/// }
+///
+/// # futures_executor::block_on(async {
+/// assert!(deploy(&AlwaysYes).await?);
+/// # Ok::<(), wait_human::WaitHumanError>(())
+/// # }).unwrap();
+/// ```
+pub trait WaitHumanApi: Send + Sync {
+    /// Asks a question and waits for its answer. See [`WaitHuman::ask`]
+    fn ask(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<ConfirmationAnswerWithDate>>;
+
+    /// Asks a free-text question and returns the text. See [`WaitHuman::ask_free_text`]
+    fn ask_free_text(
+        &self,
+        subject: String,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<String>> {
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject,
+            body,
+            answer_format: AnswerFormat::FreeText,
+        };
+
+        Box::pin(async move {
+            let checks = FreeTextChecks::new(options.as_ref());
+            let answer = self.ask(question, options).await?;
+            checks.text_of(answer)
+        })
+    }
+
+    /// Asks a single-choice question and returns the selected choice. See
+    /// [`WaitHuman::ask_multiple_choice`]
+    fn ask_multiple_choice(
+        &self,
+        subject: String,
+        choices: Vec<String>,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<String>> {
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject,
+            body,
+            answer_format: AnswerFormat::Options {
+                options: choices.clone(),
+                multiple: false,
+            },
+        };
+
+        Box::pin(async move {
+            let answer = self.ask(question.clone(), options).await?;
+            answer.check_format(&question)?;
+            let Some(&[index]) = answer.selected_indexes() else {
+                return Err(WaitHumanError::UnexpectedAnswerType {
+                    expected: "options".to_string(),
+                    actual: format!("{:?}", answer.content()),
+                });
+            };
+            let mut choices = choices;
+            Ok(choices.swap_remove(choice_index(index, choices.len())?))
+        })
+    }
+}
+
+impl WaitHumanApi for WaitHuman {
+    fn ask(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<ConfirmationAnswerWithDate>> {
+        Box::pin(WaitHuman::ask(self, question, options))
+    }
+
+    #[cfg(feature = "convenience")]
+    fn ask_free_text(
+        &self,
+        subject: String,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<String>> {
+        Box::pin(WaitHuman::ask_free_text(self, subject, body, options))
+    }
+
+    #[cfg(feature = "convenience")]
+    fn ask_multiple_choice(
+        &self,
+        subject: String,
+        choices: Vec<String>,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, Result<String>> {
+        Box::pin(WaitHuman::ask_multiple_choice(
+            self, subject, choices, body, options,
+        ))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::types::{redact_api_key, WaitHumanConfig};
+use std::fmt;
+
+#[cfg(feature = "secrecy")]
+type Secret = secrecy::SecretString;
+#[cfg(not(feature = "secrecy"))]
+type Secret = String;
+
+/// The client's API key, zeroized on drop with the `secrecy` feature
+///
+/// Only [`ApiKey::expose`] reads it, when setting the `Authorization` header; the
+/// `Debug` output is redacted.
+#[derive(Clone)]
+pub(crate) struct ApiKey(Secret);
+
+impl ApiKey {
+    /// Takes the API key out of `config`, preferring its `secret_api_key`
+    pub(crate) fn take(config: &mut WaitHumanConfig) -> Self {
+        let api_key = std::mem::take(&mut config.api_key);
+        #[cfg(feature = "secrecy")]
+        if let Some(secret) = config.secret_api_key.take() {
+            return Self(secret);
+        }
+        #[cfg(feature = "secrecy")]
+        let api_key = Secret::from(api_key);
+        Self(api_key)
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        #[cfg(feature = "secrecy")]
+        return secrecy::ExposeSecret::expose_secret(&self.0);
+        #[cfg(not(feature = "secrecy"))]
+        return &self.0;
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.expose().is_empty()
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&redact_api_key(self.expose()))
+    }
+}
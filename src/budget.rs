@@ -0,0 +1,51 @@
+//! Time budget of a call, shared by its requests, retries and sleeps
+
+use crate::error::WaitHumanError;
+use crate::runtime::Instant;
+use crate::types::AskOptions;
+use std::time::Duration;
+
+/// What's left of the timeout or deadline of a call
+///
+/// Every request, retry and sleep of the call draws from the same budget, measured
+/// against one deadline, so together they can't run past the timeout the caller set.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Budget {
+    start: Instant,
+    deadline: Option<Instant>,
+}
+
+impl Budget {
+    /// The budget of a call started at `start`, under the timeout or deadline of `options`
+    pub(crate) fn new(options: &AskOptions, start: Instant) -> Self {
+        Self {
+            start,
+            deadline: options.deadline_from(start),
+        }
+    }
+
+    /// The point in time the budget runs out at, if the call has a timeout or deadline
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Time left before the deadline, or `None` without one
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Whether a wait of `wait` starting now still ends by the deadline
+    pub(crate) fn fits(&self, wait: Duration) -> bool {
+        self.remaining().is_none_or(|remaining| wait <= remaining)
+    }
+
+    /// The `Timeout` error of a call that ran out of budget
+    pub(crate) fn timeout_error(&self) -> WaitHumanError {
+        WaitHumanError::Timeout {
+            elapsed_seconds: self.start.elapsed().as_secs_f64(),
+            confirmation_id: None,
+            subject: None,
+        }
+    }
+}
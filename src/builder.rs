@@ -0,0 +1,239 @@
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::client::WaitHuman;
+use crate::error::Result;
+use crate::metrics::Metrics;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::runtime::Sleeper;
+#[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+use crate::store::ConfirmationStore;
+use crate::types::{RedirectPolicy, WaitHumanConfig};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builder for a [`WaitHuman`] client, created with `WaitHuman::builder`
+///
+/// Every setting of [`WaitHumanConfig`] has a method here. Nothing is validated until
+/// [`build`](Self::build), which reports the first invalid setting.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use wait_human::WaitHuman;
+///
+/// let client = WaitHuman::builder()
+///     .api_key("your-api-key")
+///     .endpoint("https://gw.example.com/waithuman")
+///     .user_agent("my-app/1.2")
+///     .rate_limit_backoff(Duration::from_secs(10))
+///     .build()?;
+/// # Ok::<(), wait_human::WaitHumanError>(())
+/// ```
+///
+/// To start from a configuration read from the environment or a key file, convert it:
+///
+/// ```no_run
+/// use wait_human::{WaitHumanBuilder, WaitHumanConfig};
+///
+/// let client = WaitHumanBuilder::from(WaitHumanConfig::from_env()?)
+///     .in_flight_tracking()
+///     .build()?;
+/// # Ok::<(), wait_human::WaitHumanError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaitHumanBuilder {
+    config: WaitHumanConfig,
+}
+
+impl WaitHumanBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            config: WaitHumanConfig::new(""),
+        }
+    }
+
+    /// Sets the API key (mandatory)
+    pub fn api_key<S: Into<String>>(mut self, api_key: S) -> Self {
+        self.config.api_key = api_key.into();
+        #[cfg(feature = "secrecy")]
+        {
+            self.config.secret_api_key = None;
+        }
+        self
+    }
+
+    /// Sets the API key from a `SecretString`. See `WaitHumanConfig::with_secret_api_key`
+    #[cfg(feature = "secrecy")]
+    pub fn secret_api_key(mut self, api_key: secrecy::SecretString) -> Self {
+        self.config = self.config.with_secret_api_key(api_key);
+        self
+    }
+
+    /// Sets the endpoint URL. See `WaitHumanConfig::endpoint`
+    pub fn endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.config = self.config.with_endpoint(endpoint);
+        self
+    }
+
+    /// Keeps a trailing slash on the endpoint. See `WaitHumanConfig::preserve_endpoint`
+    pub fn preserve_endpoint(mut self) -> Self {
+        self.config = self.config.with_preserve_endpoint();
+        self
+    }
+
+    /// Adds an endpoint to fail over to. See `WaitHumanConfig::fallback_endpoints`
+    pub fn fallback_endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.config = self.config.with_fallback_endpoint(endpoint);
+        self
+    }
+
+    /// Declares the backends replicated. See `WaitHumanConfig::replicated_endpoints`
+    pub fn replicated_endpoints(mut self) -> Self {
+        self.config = self.config.with_replicated_endpoints();
+        self
+    }
+
+    /// Sets the base URL of the web UI used to build confirmation links
+    pub fn app_url<S: Into<String>>(mut self, app_url: S) -> Self {
+        self.config = self.config.with_app_url(app_url);
+        self
+    }
+
+    /// Sets the fallback backoff used for rate-limited requests without `Retry-After`
+    pub fn rate_limit_backoff(mut self, backoff: Duration) -> Self {
+        self.config = self.config.with_rate_limit_backoff(backoff);
+        self
+    }
+
+    /// Sets the HTTP client used to send requests
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.config = self.config.with_http_client(client);
+        self
+    }
+
+    /// Customizes the HTTP client the client builds. See
+    /// `WaitHumanConfig::with_http_client_builder`
+    pub fn http_client_builder<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(reqwest::ClientBuilder) -> reqwest::ClientBuilder + Send + Sync + 'static,
+    {
+        self.config = self.config.with_http_client_builder(configure);
+        self
+    }
+
+    /// Shares one HTTP client across the process. See
+    /// `WaitHumanConfig::shared_http_client`
+    pub fn shared_http_client(mut self) -> Self {
+        self.config = self.config.with_shared_http_client();
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.config = self.config.with_user_agent(user_agent);
+        self
+    }
+
+    /// Pins the API version the client speaks
+    pub fn api_version<S: Into<String>>(mut self, api_version: S) -> Self {
+        self.config = self.config.with_api_version(api_version);
+        self
+    }
+
+    /// Sets the proxy URL all requests are sent through
+    pub fn proxy<S: Into<String>>(mut self, proxy: S) -> Self {
+        self.config = self.config.with_proxy(proxy);
+        self
+    }
+
+    /// Sets how long establishing a connection may take
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.config = self.config.with_connect_timeout(connect_timeout);
+        self
+    }
+
+    /// Sets how long a single long poll may hold its connection open
+    pub fn max_poll_hold(mut self, max_poll_hold: Duration) -> Self {
+        self.config = self.config.with_max_poll_hold(max_poll_hold);
+        self
+    }
+
+    /// Sets which redirects the client follows
+    pub fn redirect_policy(mut self, redirect_policy: RedirectPolicy) -> Self {
+        self.config = self.config.with_redirect_policy(redirect_policy);
+        self
+    }
+
+    /// Rejects plain-HTTP endpoints other than loopback hosts
+    pub fn require_https(mut self) -> Self {
+        self.config = self.config.with_require_https();
+        self
+    }
+
+    /// Sets the hook receiving metrics about the client's activity
+    pub fn metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.config = self.config.with_metrics(metrics);
+        self
+    }
+
+    /// Sets the timer the client sleeps with, to run on executors other than tokio
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.config = self.config.with_sleeper(sleeper);
+        self
+    }
+
+    /// Sets the store pending confirmations are saved to, so waits can resume after a
+    /// restart
+    #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+    pub fn store(mut self, store: Arc<dyn ConfirmationStore>) -> Self {
+        self.config = self.config.with_store(store);
+        self
+    }
+
+    /// Enables the circuit breaker around create and poll requests
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.config = self.config.with_circuit_breaker(circuit_breaker);
+        self
+    }
+
+    /// Tracks the asks in flight, so they can be drained with `WaitHuman::shutdown`
+    pub fn in_flight_tracking(mut self) -> Self {
+        self.config = self.config.with_in_flight_tracking();
+        self
+    }
+
+    /// Sets the secret webhook requests are signed with
+    pub fn webhook_secret<S: Into<String>>(mut self, secret: S) -> Self {
+        self.config = self.config.with_webhook_secret(secret);
+        self
+    }
+
+    /// Sets the secret every request to the API is signed with
+    pub fn request_signing_secret<S: Into<String>>(mut self, secret: S) -> Self {
+        self.config = self.config.with_request_signing_secret(secret);
+        self
+    }
+
+    /// Enables gzip compression of request bodies larger than 1 KiB
+    #[cfg(feature = "compression")]
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.config = self.config.with_compression(compression);
+        self
+    }
+
+    /// Validates the settings and creates the client
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `WaitHuman::new`, e.g. if no API key was set
+    pub fn build(self) -> Result<WaitHuman> {
+        WaitHuman::new(self.config)
+    }
+}
+
+impl From<WaitHumanConfig> for WaitHumanBuilder {
+    fn from(config: WaitHumanConfig) -> Self {
+        Self { config }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::error::{Result, WaitHumanError};
+use futures_util::future::BoxFuture;
+use std::collections::BTreeMap;
+use std::future::Future;
+
+/// A fixed set of choices that can be asked with `WaitHuman::ask_choice`
+///
+/// Usually implemented for a fieldless enum with the [`impl_choices!`](crate::impl_choices)
+/// macro, which fails to compile when a variant has no label. It can also be
+/// implemented by hand, keeping `labels` and `from_index` in sync:
+///
+/// ```
+/// use wait_human::Choices;
+///
+/// enum Approval {
+///     Approve,
+///     Reject,
+/// }
+///
+/// impl Choices for Approval {
+///     fn labels() -> Vec<String> {
+///         vec!["Approve".to_string(), "Reject".to_string()]
+///     }
+///
+///     fn from_index(index: usize) -> Option<Self> {
+///         match index {
+///             0 => Some(Approval::Approve),
+///             1 => Some(Approval::Reject),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Choices: Sized {
+    /// Labels shown to the human, in order
+    fn labels() -> Vec<String>;
+
+    /// Maps the index of a selected label back to its value
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// Checks that `labels` and `from_index` agree: every label maps back to a value,
+    /// and no index past the last label does
+    ///
+    /// `WaitHuman::ask_choice` runs this check before asking. A mismatch, e.g. a
+    /// variant added without its label, panics in debug builds and fails the ask with
+    /// `InvalidQuestion` in release builds. Call it from a unit test to catch it
+    /// before anything runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidQuestion` describing the first mismatch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wait_human::Choices;
+    ///
+    /// enum Size {
+    ///     Small,
+    ///     Large,
+    /// }
+    ///
+    /// impl Choices for Size {
+    ///     // "Large" was forgotten
+    ///     fn labels() -> Vec<String> {
+    ///         vec!["Small".to_string()]
+    ///     }
+    ///
+    ///     fn from_index(index: usize) -> Option<Self> {
+    ///         [Size::Small, Size::Large].into_iter().nth(index)
+    ///     }
+    /// }
+    ///
+    /// assert!(Size::check_labels().is_err());
+    /// ```
+    fn check_labels() -> Result<()> {
+        let count = Self::labels().len();
+        if count == 0 {
+            return Err(WaitHumanError::InvalidQuestion(
+                "Choices::labels returned no labels".to_string(),
+            ));
+        }
+        if let Some(index) = (0..count).find(|&index| Self::from_index(index).is_none()) {
+            return Err(WaitHumanError::InvalidQuestion(format!(
+                "Choices::from_index returns no value for label {} of {}",
+                index, count
+            )));
+        }
+        if Self::from_index(count).is_some() {
+            return Err(WaitHumanError::InvalidQuestion(format!(
+                "Choices::from_index returns a value for index {}, past the {} labels",
+                count, count
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A choice asked with `WaitHuman::ask_choice_value`: the human sees `label`, and the
+/// ask returns `value`
+///
+/// A choice can be shown greyed out with [`disabled`](Self::disabled), so the human
+/// sees it but can't pick it, and explained with [`with_help`](Self::with_help).
+/// Converting from a `(label, value)` pair gives an enabled choice without help:
+///
+/// ```
+/// use wait_human::Choice;
+///
+/// let choice: Choice<u32> = ("Three replicas", 3).into();
+/// assert_eq!(choice.label, "Three replicas");
+/// assert_eq!(choice.value, 3);
+/// assert!(!choice.disabled);
+///
+/// let choice = Choice::new("Five replicas", 5)
+///     .disabled()
+///     .with_help("Needs a larger quota");
+/// assert!(choice.disabled);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Choice<T> {
+    /// Label shown to the human
+    pub label: String,
+    /// Value returned when the label is selected
+    pub value: T,
+    /// Whether the choice is shown but can't be selected
+    pub disabled: bool,
+    /// Help text shown under the label
+    pub help: Option<String>,
+}
+
+impl<T> Choice<T> {
+    /// Creates an enabled choice showing `label` and returning `value`
+    pub fn new<L: Into<String>>(label: L, value: T) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            disabled: false,
+            help: None,
+        }
+    }
+
+    /// Shows the choice greyed out, so it can't be selected
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    /// Shows `help` under the label
+    pub fn with_help<S: Into<String>>(mut self, help: S) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+}
+
+impl<L: Into<String>, T> From<(L, T)> for Choice<T> {
+    fn from((label, value): (L, T)) -> Self {
+        Self::new(label, value)
+    }
+}
+
+/// Choices asked with `WaitHuman::ask_multiple_choice_map`, as `(key, label)` pairs in a
+/// stable order
+///
+/// The answer only carries the index of the selected label, so the labels must be
+/// sent in the same order they are mapped back in, on every run. That is why this is
+/// implemented for `BTreeMap`s, `Vec`s and arrays, but not for `HashMap`, whose
+/// iteration order changes from one run to the next. Other ordered maps, such as an
+/// `IndexMap`, can implement it, or be collected into a `Vec` first.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use wait_human::OrderedChoices;
+///
+/// let regions = BTreeMap::from([("us", "United States"), ("eu", "Europe")]);
+/// assert_eq!(
+///     regions.into_choices(),
+///     [("eu", "Europe".to_string()), ("us", "United States".to_string())]
+/// );
+/// ```
+pub trait OrderedChoices<K> {
+    /// Returns the keys and labels, in the order the labels are shown
+    fn into_choices(self) -> Vec<(K, String)>;
+}
+
+impl<K, L: Into<String>> OrderedChoices<K> for BTreeMap<K, L> {
+    fn into_choices(self) -> Vec<(K, String)> {
+        self.into_iter()
+            .map(|(key, label)| (key, label.into()))
+            .collect()
+    }
+}
+
+impl<K: Clone, L: AsRef<str>> OrderedChoices<K> for &BTreeMap<K, L> {
+    fn into_choices(self) -> Vec<(K, String)> {
+        self.iter()
+            .map(|(key, label)| (key.clone(), label.as_ref().to_string()))
+            .collect()
+    }
+}
+
+impl<K, L: Into<String>> OrderedChoices<K> for Vec<(K, L)> {
+    fn into_choices(self) -> Vec<(K, String)> {
+        self.into_iter()
+            .map(|(key, label)| (key, label.into()))
+            .collect()
+    }
+}
+
+impl<K, L: Into<String>, const N: usize> OrderedChoices<K> for [(K, L); N] {
+    fn into_choices(self) -> Vec<(K, String)> {
+        self.into_iter()
+            .map(|(key, label)| (key, label.into()))
+            .collect()
+    }
+}
+
+/// A choice offered by a [`ChoiceProvider`] in answer to a search
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SearchChoice {
+    /// Identifier of the choice, returned when it is selected, e.g. a customer id
+    pub key: String,
+    /// Label shown to the human
+    pub label: String,
+}
+
+impl SearchChoice {
+    /// Creates a choice with the given key and label
+    pub fn new<K: Into<String>, L: Into<String>>(key: K, label: L) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+        }
+    }
+}
+
+/// Source of the choices of a searchable question, asked with
+/// `WaitHuman::ask_searchable_choice`
+///
+/// The human types a query, which the server relays to the client on its next poll;
+/// the client looks up matching choices here and sends them back. The query is empty
+/// when the question is first shown. An error message is shown to the human, who can
+/// search again.
+///
+/// Implemented for async closures taking the query:
+///
+/// ```
+/// use wait_human::{AskOptions, SearchChoice};
+///
+/// let options = AskOptions::new().with_choice_provider(|query: String| async move {
+///     let customers = [("cus_1", "Acme"), ("cus_2", "Globex")];
+///     Ok(customers
+///         .iter()
+///         .filter(|(_, name)| name.to_lowercase().contains(&query.to_lowercase()))
+///         .map(|(id, name)| SearchChoice::new(*id, *name))
+///         .collect())
+/// });
+/// ```
+pub trait ChoiceProvider: Send + Sync {
+    /// Returns the choices matching `query`, or a message explaining why there are none
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> BoxFuture<'a, std::result::Result<Vec<SearchChoice>, String>>;
+}
+
+impl<F, Fut> ChoiceProvider for F
+where
+    F: Fn(String) -> Fut + Send + Sync,
+    Fut: Future<Output = std::result::Result<Vec<SearchChoice>, String>> + Send + 'static,
+{
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> BoxFuture<'a, std::result::Result<Vec<SearchChoice>, String>> {
+        Box::pin(self(query.to_string()))
+    }
+}
+
+/// Implements [`Choices`] for a fieldless enum, mapping each variant to its label
+///
+/// Variants are presented to the human in the order they are listed. Every variant of
+/// the enum must be listed, so adding a variant without a label fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use wait_human::{impl_choices, Choices};
+///
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// impl_choices!(Color {
+///     Red => "Red",
+///     Green => "Green",
+/// });
+///
+/// assert_eq!(Color::labels(), ["Red", "Green"]);
+/// assert!(matches!(Color::from_index(1), Some(Color::Green)));
+/// assert!(Color::from_index(2).is_none());
+/// ```
+///
+/// Leaving out a variant is a compile error:
+///
+/// ```compile_fail
+/// use wait_human::impl_choices;
+///
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// impl_choices!(Color {
+///     Red => "Red",
+///     Green => "Green",
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_choices {
+    ($ty:ident { $($variant:ident => $label:expr),+ $(,)? }) => {
+        impl $crate::Choices for $ty {
+            fn labels() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![$(::std::string::String::from($label)),+]
+            }
+
+            fn from_index(index: usize) -> ::std::option::Option<Self> {
+                [$($ty::$variant),+].into_iter().nth(index)
+            }
+        }
+
+        const _: () = {
+            // Fails to compile when a variant of the enum has no label
+            #[allow(dead_code)]
+            fn every_variant_has_a_label(value: &$ty) {
+                match value {
+                    $($ty::$variant => {})+
+                }
+            }
+        };
+    };
+}
@@ -0,0 +1,127 @@
+use crate::error::{Result, WaitHumanError};
+use crate::runtime::Instant;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Thresholds of the circuit breaker around create and poll requests
+///
+/// After `failure_threshold` consecutive failures (network errors or 5xx responses)
+/// within `window`, requests fail right away with `CircuitOpen` for `cooldown`. Then a
+/// single request is let through to test recovery: if it succeeds the circuit closes,
+/// otherwise it opens for another cooldown.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// Window the consecutive failures must fall within
+    pub window: Duration,
+    /// How long the circuit stays open before testing recovery
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Circuit breaker state, shared by all clones of a client
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        failures: u32,
+        window_start: Instant,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A probe request is testing recovery. If it never reports back (e.g. its future
+    /// was dropped), another probe is allowed after a cooldown
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::Closed {
+                failures: 0,
+                window_start: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns `CircuitOpen` if requests should be short-circuited
+    pub(crate) fn check(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } if now < until => Err(WaitHumanError::CircuitOpen {
+                retry_after: until - now,
+            }),
+            State::HalfOpen { since } if now < since + self.config.cooldown => {
+                Err(WaitHumanError::CircuitOpen {
+                    retry_after: since + self.config.cooldown - now,
+                })
+            }
+            // The cooldown is over: let this request through as the probe
+            State::Open { .. } | State::HalfOpen { .. } => {
+                *state = State::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    /// Records the outcome of a request that `check` let through
+    pub(crate) fn record(&self, success: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        *state = match *state {
+            _ if success => State::Closed {
+                failures: 0,
+                window_start: now,
+            },
+            State::Closed {
+                failures,
+                window_start,
+            } => {
+                let (failures, window_start) =
+                    if failures == 0 || now - window_start > self.config.window {
+                        (1, now)
+                    } else {
+                        (failures + 1, window_start)
+                    };
+
+                if failures >= self.config.failure_threshold {
+                    State::Open {
+                        until: now + self.config.cooldown,
+                    }
+                } else {
+                    State::Closed {
+                        failures,
+                        window_start,
+                    }
+                }
+            }
+            State::HalfOpen { .. } => State::Open {
+                until: now + self.config.cooldown,
+            },
+            State::Open { until } => State::Open { until },
+        };
+    }
+}
@@ -1,21 +1,153 @@
+use crate::api_key::ApiKey;
+use crate::budget::Budget;
+use crate::builder::WaitHumanBuilder;
+use crate::choices::ChoiceProvider;
+#[cfg(feature = "convenience")]
+use crate::choices::{Choice, Choices, OrderedChoices, SearchChoice};
+use crate::circuit_breaker::CircuitBreaker;
+#[cfg(feature = "debug-http")]
+use crate::debug_http;
 use crate::error::{Result, WaitHumanError};
+use crate::failover::Endpoints;
+use crate::in_flight::{InFlight, InFlightGuard};
+use crate::metrics::Metrics;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::runtime::Sleeper;
+use crate::runtime::{race_timer, sleep, spawn, BoxFuture, Instant};
+use crate::signing::{sign_request, REQUEST_SIGNATURE_HEADER, REQUEST_TIMESTAMP_HEADER};
+use crate::single_flight::SingleFlight;
+#[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+use crate::store::{ConfirmationStore, StoredConfirmation};
 use crate::types::*;
-use reqwest::Client;
-use std::time::Instant;
-use tokio::time::{sleep, Duration};
+use crate::validation::{
+    check_attachment_sizes, check_selection, validate_answer, validate_auto_answer_rules,
+    validate_choice_provider, validate_choice_states, validate_default_answer,
+    validate_length_bounds, validate_locale, validate_question, validate_recipient,
+    validate_required_approvals, validate_tags, validate_timeout,
+};
+#[cfg(feature = "convenience")]
+use crate::validation::{
+    choice_index, content_for, phrase_matches, single_selection, validate_phrase, FreeTextChecks,
+};
+use crate::webhook::{self, WebhookAnswer};
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use futures_util::stream::{self, Stream};
+use reqwest::header::{HeaderValue, AUTHORIZATION, LOCATION, RETRY_AFTER, USER_AGENT};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 const DEFAULT_ENDPOINT: &str = "https://api.waithuman.com";
+const DEFAULT_APP_URL: &str = "https://waithuman.com";
 const POLL_INTERVAL_MS: u64 = 3000;
+const RATE_LIMIT_BACKOFF_MS: u64 = 5000;
+/// Characters of an unexpected response body included in the error
+const JSON_ERROR_SNIPPET_CHARS: usize = 200;
+const API_VERSION_HEADER: &str = "WaitHuman-Api-Version";
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const IDEMPOTENT_CREATE_ATTEMPTS: u32 = 3;
+/// How often `shutdown` checks whether the asks in flight are done
+const SHUTDOWN_CHECK_MS: u64 = 100;
+/// Wait before looking up a key whose original create is still being processed
+const IN_FLIGHT_RETRY_MS: u64 = 1000;
+/// Consecutive long polls returning pending well before their hold after which an
+/// adaptive ask concludes the server doesn't support long polling
+const LONG_POLL_EARLY_RETURNS: u32 = 2;
+/// Long polls dropped in a row by an intermediary that are sent again before the poll
+/// fails, so a server closing every connection doesn't loop forever
+const MAX_DROPPED_HOLDS: u32 = 3;
+/// Polls in a row answered with a truncated or garbled body that are sent again before
+/// the poll fails with `InvalidResponse`
+const MAX_GARBLED_POLLS: u32 = 3;
+/// Shortest wait before the next poll a server can ask for, so a `poll_interval_ms` of
+/// 0 or a `Retry-After: 0` can't make the client poll in a tight loop
+const MIN_SERVER_POLL_WAIT: Duration = Duration::from_millis(250);
+/// Shortest wait before retrying a request answered with a 503, so a `Retry-After: 0`
+/// or an `ends_at` in the past can't make the client retry in a tight loop
+const MIN_MAINTENANCE_WAIT: Duration = Duration::from_secs(1);
+/// Maintenance waits of a request without a timeout or deadline after which it fails
+/// with `ServiceUnavailable`, so a server that stays down doesn't keep the call forever
+const MAX_MAINTENANCE_RETRIES: u32 = 5;
+/// Bulk polls of `ask_many` failing in a row after which the questions still pending
+/// fail with the poll's error
+const MAX_FAILED_BATCH_POLLS: u32 = 3;
+/// Interval of the TCP keepalive probes of the HTTP clients the client builds, so
+/// connections held by long polls aren't taken as dead by NATs and firewalls
+#[cfg(not(target_arch = "wasm32"))]
+const TCP_KEEPALIVE: Duration = Duration::from_secs(15);
+/// Request bodies up to this size are sent uncompressed, as compressing them costs
+/// more than it saves (see the `request_body` benchmark)
+#[cfg(feature = "compression")]
+const COMPRESSION_THRESHOLD_BYTES: usize = 1024;
 
 /// Main WaitHuman client for making requests
-#[derive(Debug, Clone)]
+///
+/// Cloning is cheap and clones share the same connection pool, so a single client
+/// can be created at startup and cloned into each task or request handler.
+///
+/// The `Debug` output redacts the API key.
+#[derive(Clone)]
 pub struct WaitHuman {
-    api_key: String,
-    endpoint: String,
+    api_key: ApiKey,
+    endpoint: Url,
+    /// Whether API paths end with a slash, as the endpoint does, from
+    /// `WaitHumanConfig::preserve_endpoint`
+    trailing_slash: bool,
+    preserve_endpoint: bool,
+    /// The endpoint and its fallbacks, when `WaitHumanConfig::fallback_endpoints` is set
+    endpoints: Option<Arc<Endpoints>>,
+    app_url: Url,
+    rate_limit_backoff: Duration,
+    /// Poll interval used when `AskOptions::poll_interval` is unset
+    poll_interval: Duration,
+    /// Longest a single long poll may hold its connection open
+    max_poll_hold: Duration,
+    user_agent: String,
+    api_version: String,
+    require_https: bool,
+    metrics: Option<Arc<dyn Metrics>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    sleeper: Option<Arc<dyn Sleeper>>,
+    #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+    store: Option<Arc<dyn ConfirmationStore>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    in_flight: Option<Arc<InFlight>>,
+    webhook_secret: Option<String>,
+    request_signing_secret: Option<String>,
+    #[cfg(feature = "compression")]
+    compression: bool,
     client: Client,
+    /// Poll loops in progress, so concurrent waits on one confirmation share a loop
+    polls: SingleFlight<ConfirmationId, Result<(ConfirmationAnswerWithDate, AskStats)>>,
+    /// Set once the server turned out not to offer the bulk poll endpoint
+    bulk_unsupported: Arc<AtomicBool>,
+    /// Answer formats the server supports, once `supported_answer_formats` learned them
+    answer_formats: Arc<OnceLock<Vec<AnswerFormatKind>>>,
+    /// Answers returned by the next asks instead of asking the server, from
+    /// `with_answer_injection`
+    #[cfg(feature = "test-util")]
+    injected_answers: Arc<std::sync::Mutex<std::collections::VecDeque<AnswerContent>>>,
 }
 
 impl WaitHuman {
+    /// Returns a builder to configure a client setting by setting
+    ///
+    /// See [`WaitHumanBuilder`] for an example.
+    pub fn builder() -> WaitHumanBuilder {
+        WaitHumanBuilder::new()
+    }
+
     /// Creates a new WaitHuman client from just an API key
     ///
     /// This is a convenience wrapper around `WaitHuman::new()` that uses the default endpoint.
@@ -40,6 +172,38 @@ impl WaitHuman {
         Self::new(WaitHumanConfig::new(api_key))
     }
 
+    /// Creates a new WaitHuman client from environment variables
+    ///
+    /// Reads the API key from `WAITHUMAN_API_KEY` and, if set, a custom endpoint from
+    /// `WAITHUMAN_ENDPOINT`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `WAITHUMAN_API_KEY` is not set or empty
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::WaitHuman;
+    ///
+    /// let client = WaitHuman::from_env()?;
+    /// # Ok::<(), wait_human::WaitHumanError>(())
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        Self::new(WaitHumanConfig::from_env()?)
+    }
+
+    /// Creates a new WaitHuman client with the API key read from a file
+    ///
+    /// This is a convenience wrapper around `WaitHumanConfig::from_key_file`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or contains no key
+    pub fn from_key_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(WaitHumanConfig::from_key_file(path)?)
+    }
+
     /// Creates a new WaitHuman client
     ///
     /// # Arguments
@@ -48,30 +212,183 @@ impl WaitHuman {
     ///
     /// # Errors
     ///
-    /// Returns an error if the API key is empty
-    pub fn new(config: WaitHumanConfig) -> Result<Self> {
-        if config.api_key.is_empty() {
-            return Err(WaitHumanError::InvalidResponse(
-                "api_key is mandatory".to_string(),
-            ));
+    /// Returns an error if the API key is empty, the endpoint or app URL isn't a valid
+    /// URL, or the proxy configuration is invalid
+    ///
+    /// ```
+    /// use wait_human::{WaitHuman, WaitHumanConfig, WaitHumanError};
+    ///
+    /// let result = WaitHuman::new(WaitHumanConfig::new("your-api-key").with_endpoint("api.example.com"));
+    /// assert!(matches!(result, Err(WaitHumanError::InvalidConfig { field: "endpoint", .. })));
+    /// ```
+    pub fn new(mut config: WaitHumanConfig) -> Result<Self> {
+        let api_key = ApiKey::take(&mut config);
+        if api_key.is_empty() {
+            return Err(WaitHumanError::InvalidConfig {
+                field: "api_key",
+                message: "api_key is mandatory".to_string(),
+            });
+        }
+
+        let api_version = config
+            .api_version
+            .unwrap_or_else(|| API_VERSION.to_string());
+        if !SUPPORTED_API_VERSIONS.contains(&api_version.as_str()) {
+            return Err(WaitHumanError::InvalidConfig {
+                field: "api_version",
+                message: format!(
+                    "unsupported API version {:?}, expected one of {:?}",
+                    api_version, SUPPORTED_API_VERSIONS
+                ),
+            });
         }
 
-        let mut endpoint = config
-            .endpoint
-            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        // Explicit configuration wins over the environment, which wins over the defaults
+        let endpoint = config.endpoint.or_else(|| env_var(ENDPOINT_ENV));
+        let poll_interval = match env_var(POLL_INTERVAL_ENV) {
+            // 0 would poll in a tight loop
+            Some(ms) => match ms.parse() {
+                Ok(ms) if ms > 0 => Duration::from_millis(ms),
+                _ => {
+                    return Err(WaitHumanError::InvalidConfig {
+                        field: POLL_INTERVAL_ENV,
+                        message: format!(
+                            "{} must be a positive number of milliseconds, got {:?}",
+                            POLL_INTERVAL_ENV, ms
+                        ),
+                    })
+                }
+            },
+            None => Duration::from_millis(POLL_INTERVAL_MS),
+        };
 
-        // Remove trailing slash
-        if endpoint.ends_with('/') {
-            endpoint.pop();
+        // The answer UI lives on the API host for custom endpoints, unless configured
+        let app_url = match (&config.app_url, &endpoint) {
+            (Some(app_url), _) => app_url.as_str(),
+            (None, Some(endpoint)) => endpoint.as_str(),
+            (None, None) => DEFAULT_APP_URL,
+        };
+        // Parsed first, so an invalid endpoint is reported as such rather than as the
+        // app URL that defaults to it
+        let endpoint = endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        let trailing_slash = config.preserve_endpoint && endpoint.ends_with('/');
+        let endpoint = base_url("endpoint", endpoint)?;
+        let app_url = base_url("app_url", app_url)?;
+        if config.require_https {
+            check_https("endpoint", &endpoint)?;
+        }
+        let mut fallbacks = Vec::with_capacity(config.fallback_endpoints.len());
+        for fallback in &config.fallback_endpoints {
+            let fallback = base_url("fallback_endpoints", fallback)?;
+            if config.require_https {
+                check_https("fallback_endpoints", &fallback)?;
+            }
+            fallbacks.push(fallback);
+        }
+        let endpoints = (!fallbacks.is_empty()).then(|| {
+            let urls = std::iter::once(endpoint.clone()).chain(fallbacks).collect();
+            Arc::new(Endpoints::new(urls, config.replicated_endpoints))
+        });
+        // The server takes the hold in whole seconds
+        let max_poll_hold = config.max_poll_hold.unwrap_or(DEFAULT_MAX_POLL_HOLD);
+        if max_poll_hold < Duration::from_secs(1) {
+            return Err(WaitHumanError::InvalidConfig {
+                field: "max_poll_hold",
+                message: "max_poll_hold must be at least a second".to_string(),
+            });
         }
 
         Ok(Self {
-            api_key: config.api_key,
+            api_key,
             endpoint,
-            client: Client::new(),
+            trailing_slash,
+            preserve_endpoint: config.preserve_endpoint,
+            endpoints,
+            app_url,
+            rate_limit_backoff: config
+                .rate_limit_backoff
+                .unwrap_or(Duration::from_millis(RATE_LIMIT_BACKOFF_MS)),
+            poll_interval,
+            max_poll_hold,
+            user_agent: config
+                .user_agent
+                .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            api_version,
+            require_https: config.require_https,
+            metrics: config.metrics,
+            #[cfg(not(target_arch = "wasm32"))]
+            sleeper: config.sleeper,
+            #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+            store: config.store,
+            circuit_breaker: config
+                .circuit_breaker
+                .map(|config| Arc::new(CircuitBreaker::new(config))),
+            in_flight: config.track_in_flight.then(Arc::default),
+            webhook_secret: config.webhook_secret,
+            request_signing_secret: config.request_signing_secret,
+            #[cfg(feature = "compression")]
+            compression: config.compression,
+            client: build_client(
+                config.http_client,
+                config.http_client_builder,
+                config.proxy,
+                config.connect_timeout,
+                config.redirect_policy,
+                config.require_https,
+                config.shared_http_client,
+            )?,
+            polls: SingleFlight::new(),
+            bulk_unsupported: Arc::new(AtomicBool::new(false)),
+            answer_formats: Default::default(),
+            #[cfg(feature = "test-util")]
+            injected_answers: Default::default(),
         })
     }
 
+    /// Queues `answer` as the answer to the next question asked, **for tests only**
+    ///
+    /// Compiled in with the `test-util` feature. The next `ask`, or any method built on
+    /// it, returns the injected answer right away as if a human gave it: no confirmation
+    /// is created, nothing is polled and nothing sleeps. Everything else runs as usual,
+    /// from the checks of the question and options to those of the answer, the
+    /// validator's and the typed methods' parsing, so tests cover the real code path
+    /// without a server. Call it repeatedly to queue answers for several asks, e.g. one
+    /// the validator rejects and the one given when asked again; each is used once, in
+    /// order, and asks beyond them go to the server. Clones of the client share the
+    /// queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wait_human::{AnswerContent, AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run() -> wait_human::Result<()> {
+    /// let client = WaitHuman::new_from_key("test-key")?.with_answer_injection(
+    ///     AnswerContent::FreeText {
+    ///         text: "ship it".to_string(),
+    ///     },
+    /// );
+    ///
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Deploy?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    /// let answer = client.ask(question, None).await?;
+    /// assert_eq!(answer.as_free_text(), Some("ship it"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn with_answer_injection(self, answer: AnswerContent) -> Self {
+        self.injected_answers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(answer);
+        self
+    }
+
     /// General method to ask a question and wait for an answer
     ///
     /// # Arguments
@@ -86,14 +403,607 @@ impl WaitHuman {
     /// - Network errors occur
     /// - The request times out
     /// - Polling fails
+    /// - A free-text answer violates `AskOptions::min_length` or `max_length`, or is
+    ///   blank and `AskOptions::reject_empty` is set
     pub async fn ask(
         &self,
         question: ConfirmationQuestion,
         options: Option<AskOptions>,
     ) -> Result<ConfirmationAnswerWithDate> {
-        let confirmation_id = self.create_confirmation(question).await?;
-        let timeout_seconds = options.and_then(|o| o.timeout_seconds);
-        self.poll_for_answer(confirmation_id, timeout_seconds).await
+        self.ask_with_stats(question, options)
+            .await
+            .map(|(answer, _)| answer)
+    }
+
+    /// Like [`ask`](Self::ask), but also returns statistics about the wait
+    ///
+    /// Useful for performance tuning and SLA reporting, e.g. how long the human took
+    /// and how many polls it needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask`](Self::ask)
+    pub async fn ask_with_stats(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        self.ask_tracked(question, options.unwrap_or_default(), &OnceLock::new())
+            .await
+    }
+
+    /// Runs [`ask_with_stats`](Self::ask_with_stats), setting `created_id` once the
+    /// first confirmation is created
+    ///
+    /// With `AskOptions::validator`, a rejected answer is asked again as a follow-up
+    /// showing the rejection message, all attempts sharing the timeout or deadline.
+    async fn ask_tracked(
+        &self,
+        mut question: ConfirmationQuestion,
+        mut options: AskOptions,
+        created_id: &OnceLock<ConfirmationId>,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        let Some(validator) = options.validator.clone() else {
+            return self.ask_once(question, options, created_id).await;
+        };
+        let start = Instant::now();
+        validate_timeout(&options)?;
+        options.pin_deadline(start);
+        let max_attempts = options
+            .max_answer_attempts
+            .unwrap_or(DEFAULT_ANSWER_ATTEMPTS)
+            .max(1);
+        let idempotency_key = options.idempotency_key.clone();
+        let body = question.body.clone();
+
+        let mut attempt = 1;
+        let mut followup_id = OnceLock::new();
+        loop {
+            let asked_id = if attempt == 1 {
+                created_id
+            } else {
+                &followup_id
+            };
+            let (answer, stats) = self
+                .ask_once(question.clone(), options.clone(), asked_id)
+                .await?;
+
+            // Answers filled in by the client aren't the human's to correct
+            let checked = match answer.answered_by {
+                AnswerSource::Automation => Ok(()),
+                _ => validator.check(answer.content()),
+            };
+            let reason = match checked {
+                Ok(()) => {
+                    let stats = AskStats {
+                        total_wait: start.elapsed(),
+                        ..stats
+                    };
+                    return Ok((answer, stats));
+                }
+                Err(reason) => reason,
+            };
+
+            let confirmation_id = asked_id.get().cloned();
+            if attempt >= max_attempts {
+                return Err(WaitHumanError::AnswerRejected {
+                    confirmation_id,
+                    reason,
+                    attempts: attempt,
+                });
+            }
+
+            attempt += 1;
+            options.parent_id = confirmation_id;
+            // A reused key would return the confirmation that was just answered
+            options.idempotency_key = idempotency_key
+                .as_ref()
+                .map(|key| format!("{}-{}", key, attempt));
+            question.body = Some(match &body {
+                Some(body) => format!("{}\n\n{}", reason, body),
+                None => reason,
+            });
+            followup_id = OnceLock::new();
+        }
+    }
+
+    /// Asks a question once, setting `created_id` once the confirmation is created
+    async fn ask_once(
+        &self,
+        question: ConfirmationQuestion,
+        options: AskOptions,
+        created_id: &OnceLock<ConfirmationId>,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        let start = Instant::now();
+        validate_timeout(&options)?;
+        validate_default_answer(&question, &options)?;
+        if options.dry_run {
+            let answer = dry_run_answer(&question, &options);
+            self.validate_confirmation(question, &options, start)
+                .await?;
+            return Ok((answer, AskStats::default()));
+        }
+        #[cfg(feature = "test-util")]
+        if let Some(injected) = self.take_injected_answer(&question, &options)? {
+            let answer = local_answer(&question, &options, injected, AnswerSource::Human);
+            check_selection(&answer, &options)?;
+            return Ok((answer, AskStats::default()));
+        }
+        let in_flight = self
+            .in_flight
+            .as_ref()
+            .map(InFlight::register)
+            .transpose()?;
+        let subject = question.subject.clone();
+        let asked = options.include_question.then(|| question.clone());
+
+        // created_id is set once created, so a timeout can report the confirmation
+        let ask = async {
+            let created = self.create_confirmation(question, &options, start).await?;
+            let _ = created_id.set(created.id.clone());
+            self.wait_created(created, &options, start, in_flight.as_ref())
+                .await
+        };
+
+        let (mut answer, stats) = self
+            .run_wait(ask, &options, start, created_id, Some(subject))
+            .await?;
+        check_selection(&answer, &options)?;
+        if let Some(asked) = asked {
+            answer.question.get_or_insert(asked);
+        }
+        Ok((answer, stats))
+    }
+
+    /// Asks a question, making sure the confirmation doesn't outlive the ask
+    ///
+    /// The recommended entry point when the answer is only useful to the caller: the
+    /// ask gives up after `timeout`, which replaces any timeout or deadline in
+    /// `options`, and the confirmation is cleaned up on every way out:
+    ///
+    /// - answered: nothing left to clean up
+    /// - timed out or failed after the confirmation was created (a network error while
+    ///   polling, `NoChannel`, ...): the confirmation is cancelled before the error is
+    ///   returned, on a best-effort basis
+    /// - the confirmation expired, was declined or cancelled: nothing left to clean up
+    /// - the future is dropped: the confirmation is cancelled in the background, as with
+    ///   `AskOptions::cancel_on_drop`. This includes a drop while the cancellation of a
+    ///   failed ask is in flight, which starts it again in the background
+    ///
+    /// A drop while the create request itself is in flight can't be cleaned up, as the
+    /// id of the confirmation isn't known yet; the server expires it with its usual
+    /// expiry. The granular methods remain for finer control over each step.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask`](Self::ask)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use wait_human::{AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    /// # async fn example(client: WaitHuman) -> wait_human::Result<()> {
+    /// let answer = client
+    ///     .ask_scoped(
+    ///         ConfirmationQuestion {
+    ///             method: QuestionMethod::Push,
+    ///             subject: "What should the release notes highlight?".to_string(),
+    ///             body: None,
+    ///             answer_format: AnswerFormat::FreeText,
+    ///         },
+    ///         Duration::from_secs(600),
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ask_scoped(
+        &self,
+        question: ConfirmationQuestion,
+        timeout: Duration,
+        options: Option<AskOptions>,
+    ) -> Result<ConfirmationAnswerWithDate> {
+        let mut options = options.unwrap_or_default().with_timeout(timeout);
+        options.deadline = None;
+        // The scope guard below takes over from the ask's own guard
+        options.cancel_on_drop = false;
+        let route = self.route_for(&options)?;
+
+        let created_id = OnceLock::new();
+        let guard = ScopeGuard {
+            client: self.clone(),
+            route,
+            created_id: &created_id,
+            armed: true,
+        };
+        let result = self
+            .ask_tracked(question, options, &created_id)
+            .await
+            .map(|(answer, _)| answer);
+
+        if let Err(e) = &result {
+            let confirmation_id = match e {
+                WaitHumanError::NoChannel { confirmation_id } => Some(confirmation_id),
+                WaitHumanError::Expired { .. }
+                | WaitHumanError::Declined { .. }
+                | WaitHumanError::Cancelled { .. }
+                | WaitHumanError::Abstained { .. } => None,
+                _ => created_id.get(),
+            };
+            if let Some(confirmation_id) = confirmation_id {
+                // Best effort: the ask's own error is the one to report
+                let _ = self.cancel_at(&guard.route, confirmation_id).await;
+            }
+        }
+        guard.disarm();
+        result
+    }
+
+    /// Waits for the answer to a confirmation created elsewhere, e.g. by another process
+    /// that handed over its id
+    ///
+    /// Runs the same wait as [`ask`](Self::ask) without creating a confirmation, and
+    /// honors the same `AskOptions`: the timeout or deadline, poll interval, long
+    /// polling, nudging and `cancel_on_drop`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask`](Self::ask)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use wait_human::{AskOptions, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, stored_id: String) -> wait_human::Result<()> {
+    /// let confirmation_id = stored_id.parse()?;
+    /// let options = AskOptions::new().with_timeout(Duration::from_secs(600));
+    ///
+    /// let answer = client.wait_for_answer(&confirmation_id, Some(options)).await?;
+    /// println!("{:?}", answer.as_free_text());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_answer(
+        &self,
+        confirmation_id: &ConfirmationId,
+        options: Option<AskOptions>,
+    ) -> Result<ConfirmationAnswerWithDate> {
+        let start = Instant::now();
+        let options = options.unwrap_or_default();
+        validate_timeout(&options)?;
+        let in_flight = self
+            .in_flight
+            .as_ref()
+            .map(InFlight::register)
+            .transpose()?;
+        let created_id = OnceLock::from(confirmation_id.clone());
+
+        let created = CreatedConfirmation {
+            id: confirmation_id.clone(),
+            expires_at: None,
+        };
+        let wait = self.wait_created(created, &options, start, in_flight.as_ref());
+
+        let (answer, _) = self
+            .run_wait(wait, &options, start, &created_id, None)
+            .await?;
+        check_selection(&answer, &options)?;
+        Ok(answer)
+    }
+
+    /// Asks a follow-up question to an earlier confirmation, in the same thread
+    ///
+    /// The confirmation is created with `parent_id` as its parent (replacing
+    /// `AskOptions::parent_id`), so the server groups them and shows the human the earlier
+    /// question and answer as context. Every follow-up of a conversation can reference its
+    /// first confirmation, whose id comes from [`create`](Self::create).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask`](Self::ask)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let created = client
+    ///     .create(
+    ///         ConfirmationQuestion {
+    ///             method: QuestionMethod::Push,
+    ///             subject: "What should the release notes highlight?".to_string(),
+    ///             body: None,
+    ///             answer_format: AnswerFormat::FreeText,
+    ///         },
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// let highlights = client.wait_for_answer(&created.id, None).await?;
+    ///
+    /// let followup = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Publish the notes now?".to_string(),
+    ///     body: highlights.as_free_text().map(str::to_string),
+    ///     answer_format: AnswerFormat::Options {
+    ///         options: vec!["Publish".to_string(), "Hold".to_string()],
+    ///         multiple: false,
+    ///     },
+    /// };
+    /// let decision = client.ask_followup(&created.id, followup, None).await?;
+    /// println!("{:?}", decision.selected_indexes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ask_followup(
+        &self,
+        parent_id: &ConfirmationId,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<ConfirmationAnswerWithDate> {
+        let options = AskOptions {
+            parent_id: Some(parent_id.clone()),
+            ..options.unwrap_or_default()
+        };
+
+        self.ask(question, Some(options)).await
+    }
+
+    /// Asks several questions at once, collecting as many answers as arrive before the
+    /// timeout or deadline of `options`, which the whole batch shares
+    ///
+    /// Returns one result per question, in order. Once no poll fits before the deadline,
+    /// the answers received so far are returned and the others are `Timeout` errors carrying their
+    /// confirmation id. A question that can't be created, or whose confirmation ends
+    /// without an answer (e.g. `Declined`), only fails its own entry. Pending
+    /// confirmations are polled together, as with [`get_answers`](Self::get_answers).
+    ///
+    /// A failed poll is retried at the next interval, keeping the answers collected so
+    /// far. Only once polls fail several times in a row do the questions still pending
+    /// fail, with the poll's error.
+    ///
+    /// With `AskOptions::cancel_on_drop`, confirmations still pending at the deadline,
+    /// or when polls keep failing, are cancelled, so nobody answers a question that no
+    /// longer matters.
+    ///
+    /// # Errors
+    ///
+    /// Fails as a whole only if the options are invalid or the client is shutting down
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use wait_human::{AnswerFormat, AskOptions, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let questions = ["billing", "storage", "network"].map(|team| ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: format!("Does the {} team approve the release?", team),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::Options {
+    ///         options: vec!["Approve".to_string(), "Reject".to_string()],
+    ///         multiple: false,
+    ///     },
+    /// });
+    /// let options = AskOptions::new()
+    ///     .with_timeout(Duration::from_secs(3600))
+    ///     .with_cancel_on_drop();
+    ///
+    /// let results = client.ask_many(questions, Some(options)).await?;
+    /// let approvals = results
+    ///     .iter()
+    ///     .filter(|result| matches!(result, Ok(answer) if answer.selected_indexes() == Some(&[0])))
+    ///     .count();
+    /// println!("{} of {} teams approved in time", approvals, results.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ask_many<I>(
+        &self,
+        questions: I,
+        options: Option<AskOptions>,
+    ) -> Result<Vec<Result<ConfirmationAnswerWithDate>>>
+    where
+        I: IntoIterator<Item = ConfirmationQuestion>,
+    {
+        let start = Instant::now();
+        let options = options.unwrap_or_default();
+        validate_timeout(&options)?;
+        let in_flight = self
+            .in_flight
+            .as_ref()
+            .map(InFlight::register)
+            .transpose()?;
+        let route = self.route_for(&options)?;
+        let budget = Budget::new(&options, start);
+        let timeout = |confirmation_id, subject| WaitHumanError::Timeout {
+            elapsed_seconds: start.elapsed().as_secs_f64(),
+            confirmation_id,
+            subject: Some(subject),
+        };
+
+        let questions: Vec<ConfirmationQuestion> = questions.into_iter().collect();
+        let subjects: Vec<String> = questions.iter().map(|q| q.subject.clone()).collect();
+        let (shared_options, budget, timeout) = (&options, &budget, &timeout);
+        let creates = questions
+            .into_iter()
+            .enumerate()
+            .map(|(position, question)| async move {
+                // A key shared by the batch would have the server deduplicate it into a
+                // single confirmation
+                let options = match &shared_options.idempotency_key {
+                    Some(key) => Cow::Owned(AskOptions {
+                        idempotency_key: Some(format!("{}-{}", key, position)),
+                        ..shared_options.clone()
+                    }),
+                    None => Cow::Borrowed(shared_options),
+                };
+                let subject = question.subject.clone();
+                let create = self.create_confirmation(question, &options, start);
+                self.within(budget, create).await.map_err(|e| match e {
+                    WaitHumanError::Timeout { .. } => timeout(None, subject),
+                    e => e,
+                })
+            });
+
+        let mut results = Vec::new();
+        let mut pending = Vec::new();
+        for (position, created) in join_all(creates).await.into_iter().enumerate() {
+            match created {
+                Ok(created) => {
+                    if let Some(in_flight) = &in_flight {
+                        in_flight.created(route.clone(), created.id.clone());
+                    }
+                    let guard = options.cancel_on_drop.then(|| CancelGuard {
+                        client: self.clone(),
+                        route: route.clone(),
+                        confirmation_id: Some(created.id.clone()),
+                    });
+                    pending.push((position, created.id, guard));
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        let mut failed_polls = 0;
+        while !pending.is_empty() {
+            let ids: Vec<ConfirmationId> = pending.iter().map(|(_, id, _)| id.clone()).collect();
+            let poll = self.fetch_answer_states(&ids, &options, start);
+            let polled = match budget.deadline() {
+                Some(deadline) => match self.timeout_at(deadline, poll).await {
+                    Some(polled) => polled,
+                    None => break,
+                },
+                None => poll.await,
+            };
+            let states = match polled {
+                Ok(states) => {
+                    failed_polls = 0;
+                    states
+                }
+                // Keep the answers collected so far: poll again, and only fail the
+                // questions still pending once the polls keep failing
+                Err(e) => {
+                    failed_polls += 1;
+                    if failed_polls >= MAX_FAILED_BATCH_POLLS {
+                        for (position, _, _guard) in pending.drain(..) {
+                            results[position] = Some(Err(e.clone()));
+                        }
+                        break;
+                    }
+                    pending.iter().map(|_| Ok(None)).collect()
+                }
+            };
+
+            let mut still_pending = Vec::new();
+            for ((position, confirmation_id, guard), state) in pending.into_iter().zip(states) {
+                let result = match state {
+                    Ok(None) => {
+                        still_pending.push((position, confirmation_id, guard));
+                        continue;
+                    }
+                    Ok(Some(answer)) => check_selection(&answer, &options).map(|()| answer),
+                    Err(e) => Err(e),
+                };
+                if let Some(guard) = guard {
+                    guard.disarm();
+                }
+                if let Some(in_flight) = &in_flight {
+                    in_flight.finished(&confirmation_id);
+                }
+                results[position] = Some(result);
+            }
+            pending = still_pending;
+            if pending.is_empty() {
+                break;
+            }
+
+            let wait = poll_interval(&options, self.poll_interval);
+            match budget.remaining() {
+                // No poll fits before the deadline: waiting it out couldn't collect any
+                // more answers
+                Some(remaining) if wait >= remaining => break,
+                _ => self.sleep(wait).await,
+            }
+        }
+
+        // Dropping the guards of confirmations still pending cancels them
+        for (position, confirmation_id, _guard) in pending {
+            let subject = subjects[position].clone();
+            results[position] = Some(Err(timeout(Some(confirmation_id), subject)));
+        }
+
+        // Every entry is resolved by now
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Like [`ask`](Self::ask), with strongly-typed metadata that round-trips through the
+    /// confirmation
+    ///
+    /// `metadata` is serialized into the confirmation, replacing `AskOptions::metadata`,
+    /// and the copy echoed back on the answer is deserialized into `M`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask`](Self::ask), and `InvalidResponse` if the echoed
+    /// metadata is missing or doesn't deserialize into `M`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use serde::{Deserialize, Serialize};
+    /// use wait_human::{AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Order {
+    ///     id: u64,
+    /// }
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Ship order?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    ///
+    /// let (answer, order) = client
+    ///     .ask_with_metadata(question, Order { id: 1234 }, None)
+    ///     .await?;
+    /// println!("order {}: {:?}", order.id, answer.as_free_text());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ask_with_metadata<M>(
+        &self,
+        question: ConfirmationQuestion,
+        metadata: M,
+        options: Option<AskOptions>,
+    ) -> Result<(ConfirmationAnswerWithDate, M)>
+    where
+        M: Serialize + DeserializeOwned,
+    {
+        let metadata = serde_json::to_value(&metadata).map_err(|e| {
+            WaitHumanError::InvalidQuestion(format!("metadata could not be serialized: {}", e))
+        })?;
+
+        let options = AskOptions {
+            metadata: Some(metadata),
+            ..options.unwrap_or_default()
+        };
+
+        let answer = self.ask(question, Some(options)).await?;
+        let metadata = answer.metadata_as()?.ok_or_else(|| {
+            WaitHumanError::InvalidResponse("answer is missing the metadata".to_string())
+        })?;
+
+        Ok((answer, metadata))
     }
 
     /// Convenience method for free-text questions
@@ -109,6 +1019,9 @@ impl WaitHuman {
     /// Returns an error if:
     /// - The request fails or times out
     /// - The answer type doesn't match (not free text)
+    /// - The answer violates `AskOptions::min_length` or `max_length`
+    /// - The answer is blank and `AskOptions::reject_empty` is set
+    #[cfg(feature = "convenience")]
     pub async fn ask_free_text<S, B>(
         &self,
         subject: S,
@@ -126,23 +1039,19 @@ impl WaitHuman {
             answer_format: AnswerFormat::FreeText,
         };
 
-        let answer = self.ask(question, options).await?;
-
-        match answer.answer.answer_content {
-            AnswerContent::FreeText { text } => Ok(text),
-            other => Err(WaitHumanError::UnexpectedAnswerType {
-                expected: "free_text".to_string(),
-                actual: format!("{:?}", other),
-            }),
-        }
+        let checks = FreeTextChecks::new(options.as_ref());
+        self.ask_typed(question, options, |answer| checks.text_of(answer))
+            .await
     }
 
-    /// Convenience method for multiple-choice questions (single selection)
+    /// Convenience method for free-text questions whose answer is JSON
+    ///
+    /// Asks a free-text question and deserializes the answer into `T`, which is handy
+    /// when the human (or an upstream tool) pastes structured data.
     ///
     /// # Arguments
     ///
     /// * `subject` - The question subject/title
-    /// * `choices` - Available choices for the user to select from
     /// * `body` - Optional detailed question body
     /// * `options` - Optional settings like timeout
     ///
@@ -150,121 +1059,4599 @@ impl WaitHuman {
     ///
     /// Returns an error if:
     /// - The request fails or times out
-    /// - The answer type doesn't match (not options)
-    /// - The selected index is invalid
-    pub async fn ask_multiple_choice<S, B, C>(
+    /// - The answer type doesn't match (not free text)
+    /// - The answer is not valid JSON for `T`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use serde::Deserialize;
+    /// use wait_human::WaitHuman;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Discount {
+    ///     percent: u8,
+    /// }
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let discount: Discount = client
+    ///     .ask_free_text_as("Which discount?", Some(r#"Reply like {"percent": 10}"#), None)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_free_text_as<T, S, B>(
         &self,
         subject: S,
-        choices: C,
         body: Option<B>,
         options: Option<AskOptions>,
-    ) -> Result<String>
+    ) -> Result<T>
     where
+        T: DeserializeOwned,
         S: Into<String>,
         B: Into<String>,
-        C: IntoIterator,
-        C::Item: Into<String>,
     {
-        let choices_vec: Vec<String> = choices.into_iter().map(|c| c.into()).collect();
-
-        let question = ConfirmationQuestion {
-            method: QuestionMethod::Push,
-            subject: subject.into(),
-            body: body.map(|b| b.into()),
-            answer_format: AnswerFormat::Options {
-                options: choices_vec.clone(),
-                multiple: false,
-            },
-        };
-
-        let answer = self.ask(question, options).await?;
-
-        match answer.answer.answer_content {
-            AnswerContent::Options { selected_indexes } => {
-                let index = selected_indexes.first().ok_or_else(|| {
-                    WaitHumanError::InvalidResponse("No selection received".to_string())
-                })?;
-
-                let index_usize = *index as usize;
+        let text = self.ask_free_text(subject, body, options).await?;
 
-                choices_vec
-                    .get(index_usize)
-                    .cloned()
-                    .ok_or_else(|| WaitHumanError::InvalidSelectedIndex { index: *index })
-            }
-            other => Err(WaitHumanError::UnexpectedAnswerType {
-                expected: "options".to_string(),
-                actual: format!("{:?}", other),
-            }),
-        }
+        serde_json::from_str(&text).map_err(|e| WaitHumanError::AnswerParseFailed {
+            message: e.to_string(),
+        })
     }
 
-    // Private helper methods
-
-    async fn create_confirmation(&self, question: ConfirmationQuestion) -> Result<String> {
-        let url = format!("{}/confirmations/create", self.endpoint);
-        let request_body = CreateConfirmationRequest { question };
+    /// Convenience method for small forms: asks for several fields at once and returns
+    /// their values by field name
+    ///
+    /// Fields the human left empty are missing from the map, or null.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `fields` - The fields of the form, in display order
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The form has no fields, or fields with blank or repeated names
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not a form)
+    /// - A required field is empty (`MissingFormField`), or a value doesn't fit its field
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{FormField, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let values = client
+    ///     .ask_form(
+    ///         "Scale the cluster?",
+    ///         vec![
+    ///             FormField::number("replicas", "Replicas").required(),
+    ///             FormField::text("reason", "Reason"),
+    ///         ],
+    ///         None::<&str>,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// println!("scaling to {}", values["replicas"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_form<S, B>(
+        &self,
+        subject: S,
+        fields: Vec<FormField>,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<serde_json::Map<String, serde_json::Value>>
+    where
+        S: Into<String>,
+        B: Into<String>,
+    {
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject: subject.into(),
+            body: body.map(|b| b.into()),
+            answer_format: AnswerFormat::Form { fields },
+        };
+        let answer_format = question.answer_format.clone();
+
+        self.ask_typed(question, options, |answer| {
+            answer.content().check_format(&answer_format)?;
+            let AnswerContent::Form { values } = answer.answer.answer_content else {
+                unreachable!("checked by check_format");
+            };
+            Ok(values)
+        })
+        .await
+    }
+
+    /// Like [`ask_form`](Self::ask_form), but deserializes the values into `T`, whose
+    /// fields are named like the form's
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ask_form`](Self::ask_form), and `AnswerParseFailed`
+    /// if the values don't deserialize into `T`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use serde::Deserialize;
+    /// use wait_human::{FormField, WaitHuman};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Scaling {
+    ///     replicas: u32,
+    ///     reason: Option<String>,
+    /// }
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let scaling: Scaling = client
+    ///     .ask_form_as(
+    ///         "Scale the cluster?",
+    ///         vec![
+    ///             FormField::number("replicas", "Replicas").required(),
+    ///             FormField::text("reason", "Reason"),
+    ///         ],
+    ///         None::<&str>,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_form_as<T, S, B>(
+        &self,
+        subject: S,
+        fields: Vec<FormField>,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+        S: Into<String>,
+        B: Into<String>,
+    {
+        let values = self.ask_form(subject, fields, body, options).await?;
+
+        serde_json::from_value(serde_json::Value::Object(values)).map_err(|e| {
+            WaitHumanError::AnswerParseFailed {
+                message: e.to_string(),
+            }
+        })
+    }
+
+    /// Convenience method for "I have read this" gates: returns once the human
+    /// acknowledges the question
+    ///
+    /// The question is sent as a single-option question with the option
+    /// [`ACKNOWLEDGE_LABEL`](crate::ACKNOWLEDGE_LABEL), so there is nothing to choose and
+    /// no answer to inspect.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `body` - Optional detailed question body, e.g. the text to acknowledge
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::WaitHuman;
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// client
+    ///     .ask_acknowledge(
+    ///         "Maintenance window starts at 22:00",
+    ///         Some("Production will be read-only for 30 minutes."),
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_acknowledge<S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<()>
+    where
+        S: Into<String>,
+        B: Into<String>,
+    {
+        self.ask_choice_index(
+            subject.into(),
+            vec![ACKNOWLEDGE_LABEL.to_string()],
+            body.map(|b| b.into()),
+            options,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Convenience method for confirming a destructive action by having the human type
+    /// a phrase, like typing a repository's name to delete it
+    ///
+    /// Asks a free-text question and returns whether the answer matches
+    /// `expected_phrase`. The match is exact unless `AskOptions::phrase_ignore_case` or
+    /// `phrase_ignore_whitespace` is set. The phrase isn't added to the question, so say
+    /// in the subject or body what to type.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `expected_phrase` - The phrase the human must type to confirm
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `expected_phrase` is blank
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not free text)
+    ///
+    /// A mismatch is not an error: it returns `false`, so callers can ask again or abort.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AskOptions, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let confirmed = client
+    ///     .ask_confirm_phrase(
+    ///         "Type the database name to drop it",
+    ///         "orders-prod",
+    ///         Some("This deletes orders-prod and all of its backups."),
+    ///         Some(AskOptions::new().with_phrase_ignore_whitespace()),
+    ///     )
+    ///     .await?;
+    /// if !confirmed {
+    ///     println!("aborting");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_confirm_phrase<S, P, B>(
+        &self,
+        subject: S,
+        expected_phrase: P,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<bool>
+    where
+        S: Into<String>,
+        P: AsRef<str>,
+        B: Into<String>,
+    {
+        let expected_phrase = expected_phrase.as_ref();
+        validate_phrase(expected_phrase)?;
+
+        let (ignore_case, ignore_whitespace) = options.as_ref().map_or((false, false), |o| {
+            (o.phrase_ignore_case, o.phrase_ignore_whitespace)
+        });
+
+        let text = self.ask_free_text(subject, body, options).await?;
+        Ok(phrase_matches(
+            &text,
+            expected_phrase,
+            ignore_case,
+            ignore_whitespace,
+        ))
+    }
+
+    /// Convenience method for multiple-choice questions (single selection)
+    ///
+    /// Returns the selected label. Choices must be given in a stable order: building
+    /// them from a `HashMap` shuffles them between runs. For choices kept in a map, use
+    /// [`ask_multiple_choice_map`](Self::ask_multiple_choice_map).
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `choices` - Available choices for the user to select from
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    /// - The selected index is invalid
+    #[cfg(feature = "convenience")]
+    pub async fn ask_multiple_choice<S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<String>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        let mut choices_vec: Vec<String> = choices.into_iter().map(|c| c.into()).collect();
+
+        let index = self
+            .ask_choice_index(
+                subject.into(),
+                choices_vec.clone(),
+                body.map(|b| b.into()),
+                options,
+            )
+            .await?;
+
+        Ok(choices_vec.swap_remove(choice_index(index, choices_vec.len())?))
+    }
+
+    /// Convenience method for multiple-choice questions over a typed set of choices
+    ///
+    /// The labels shown to the human come from [`Choices::labels`], and the selected
+    /// index is mapped back to a value with [`Choices::from_index`].
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    /// - The selected index is invalid
+    /// - `T`'s labels and `from_index` disagree (see [`Choices::check_labels`]), which
+    ///   panics instead in debug builds
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{impl_choices, WaitHuman};
+    ///
+    /// enum Deploy {
+    ///     Now,
+    ///     Tomorrow,
+    ///     Never,
+    /// }
+    ///
+    /// impl_choices!(Deploy {
+    ///     Now => "Deploy now",
+    ///     Tomorrow => "Wait until tomorrow",
+    ///     Never => "Don't deploy",
+    /// });
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// match client.ask_choice::<Deploy, _, &str>("Deploy v2?", None, None).await? {
+    ///     Deploy::Now => println!("deploying"),
+    ///     Deploy::Tomorrow => println!("scheduled"),
+    ///     Deploy::Never => println!("cancelled"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_choice<T, S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<T>
+    where
+        T: Choices,
+        S: Into<String>,
+        B: Into<String>,
+    {
+        if let Err(e) = T::check_labels() {
+            if cfg!(debug_assertions) {
+                panic!(
+                    "inconsistent Choices impl for {}: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+            }
+            return Err(e);
+        }
+
+        let labels = T::labels();
+        let count = labels.len();
+        let index = self
+            .ask_choice_index(subject.into(), labels, body.map(|b| b.into()), options)
+            .await?;
+
+        T::from_index(choice_index(index, count)?)
+            .ok_or(WaitHumanError::InvalidSelectedIndex { index })
+    }
+
+    /// Convenience method for multiple-choice questions whose labels differ from the
+    /// values the caller wants back
+    ///
+    /// The human sees each choice's label, and the value of the selected choice is
+    /// returned, so the caller never has to match on label text. Choices can be given
+    /// as [`Choice`]s or `(label, value)` pairs.
+    ///
+    /// Disabled choices and choice help are added to `AskOptions::disabled_choices` and
+    /// `AskOptions::choice_help`.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `choices` - Choices to present, in order
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    /// - The selected index is invalid, or a disabled choice
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use wait_human::{Choice, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let delay = client
+    ///     .ask_choice_value(
+    ///         "When should the migration run?",
+    ///         [
+    ///             Choice::new("Right away", Duration::ZERO),
+    ///             Choice::new("In an hour", Duration::from_secs(3600)),
+    ///             Choice::new("Tonight", Duration::from_secs(8 * 3600))
+    ///                 .disabled()
+    ///                 .with_help("The night window is already booked"),
+    ///         ],
+    ///         None::<&str>,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_choice_value<T, S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<T>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<Choice<T>>,
+    {
+        let mut options = options.unwrap_or_default();
+        let mut labels = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        for (index, choice) in (0u32..).zip(choices) {
+            let choice = choice.into();
+            if choice.disabled {
+                options.disabled_choices.push(index);
+            }
+            if let Some(help) = choice.help {
+                options.choice_help.push((index, help));
+            }
+            labels.push(choice.label);
+            values.push(choice.value);
+        }
+
+        let index = self
+            .ask_choice_index(
+                subject.into(),
+                labels,
+                body.map(|b| b.into()),
+                Some(options),
+            )
+            .await?;
+
+        Ok(values.swap_remove(choice_index(index, values.len())?))
+    }
+
+    /// Convenience method for multiple-choice questions whose choices are keyed, e.g.
+    /// by id, returning the key of the selected label
+    ///
+    /// Choices are taken from a `BTreeMap`, or `(key, label)` pairs in a `Vec` or array,
+    /// so they are sent in the same order on every run and the selected index always
+    /// maps back to the right key. A `HashMap` isn't accepted, as its order isn't
+    /// stable; see [`OrderedChoices`].
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `choices` - Keys and the labels shown for them, in order
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    /// - The selected index is invalid
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::collections::BTreeMap;
+    /// use wait_human::WaitHuman;
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let environments = BTreeMap::from([
+    ///     (17, "Staging"),
+    ///     (42, "Production"),
+    /// ]);
+    ///
+    /// let environment_id = client
+    ///     .ask_multiple_choice_map("Deploy to?", &environments, None::<&str>, None)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_multiple_choice_map<K, S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<K>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: OrderedChoices<K>,
+    {
+        let choices = choices
+            .into_choices()
+            .into_iter()
+            .map(|(key, label)| Choice::new(label, key));
+        self.ask_choice_value(subject, choices, body, options).await
+    }
+
+    /// Convenience method for multiple-choice questions (single selection) where the
+    /// human can explain their choice
+    ///
+    /// Returns the selected label and index along with the comment, if any. Choices in
+    /// `AskOptions::require_comment_on` can only be selected with a comment, e.g. so a
+    /// rejection always comes with its reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `choices` - Available choices for the user to select from
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not options)
+    /// - The selected index is invalid
+    /// - A choice requiring a comment was selected without one (`MissingComment`)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AskOptions, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let review = client
+    ///     .ask_multiple_choice_with_comment(
+    ///         "Merge the release branch?",
+    ///         ["Approve", "Reject"],
+    ///         None::<&str>,
+    ///         Some(AskOptions::new().with_comment_required_on(1)),
+    ///     )
+    ///     .await?;
+    /// if let Some(reason) = review.comment {
+    ///     println!("{}: {reason}", review.choice);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_multiple_choice_with_comment<S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<ChoiceWithComment>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        let mut choices_vec: Vec<String> = choices.into_iter().map(|c| c.into()).collect();
+        let options = options.unwrap_or_default().with_allow_comment();
+
+        let (index, comment) = self
+            .ask_choice_commented(
+                subject.into(),
+                choices_vec.clone(),
+                body.map(|b| b.into()),
+                Some(options),
+            )
+            .await?;
+
+        let index = choice_index(index, choices_vec.len())?;
+        Ok(ChoiceWithComment {
+            choice: choices_vec.swap_remove(index),
+            index,
+            comment,
+        })
+    }
+
+    /// Convenience method for a multiple-choice question with an "other (please
+    /// specify)" field, where the human may type their own answer instead
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `choices` - Available choices for the user to select from
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout. `allow_other` is set for you
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (neither options nor "other")
+    /// - The selected index is invalid
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{ChoiceOrOther, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let reason = client
+    ///     .ask_choice_or_other(
+    ///         "Why roll back?",
+    ///         ["Failing health checks", "Error rate"],
+    ///         None::<&str>,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// match reason {
+    ///     ChoiceOrOther::Predefined(index) => println!("reason #{index}"),
+    ///     ChoiceOrOther::Other(reason) => println!("other reason: {reason}"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_choice_or_other<S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<ChoiceOrOther>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        let choices: Vec<String> = choices.into_iter().map(|c| c.into()).collect();
+        let choice_count = choices.len();
+        let options = options.unwrap_or_default().with_allow_other();
+        let coerce = options.coerce_answers;
+
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject: subject.into(),
+            body: body.map(|b| b.into()),
+            answer_format: AnswerFormat::Options {
+                options: choices,
+                multiple: false,
+            },
+        };
+        let answer_format = question.answer_format.clone();
+
+        self.ask_typed(question, Some(options), |answer| {
+            match content_for(answer, &answer_format, coerce)? {
+                AnswerContent::Other { text } => Ok(ChoiceOrOther::Other(text)),
+                content => {
+                    // A single in-range index, as checked by content_for
+                    let index = single_selection(content.selected_indexes().unwrap_or_default())?;
+                    Ok(ChoiceOrOther::Predefined(choice_index(
+                        index,
+                        choice_count,
+                    )?))
+                }
+            }
+        })
+        .await
+    }
+
+    /// Convenience method for choosing among too many choices to list up front, such
+    /// as customers or repositories
+    ///
+    /// The human searches, and `provider` supplies the matching choices for each query:
+    /// the server relays the query on the next poll, and the client posts the results
+    /// back before polling again. Searches are only answered while the client is
+    /// polling, so long polling keeps them snappy.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `provider` - Source of the choices matching a query
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not a search result)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AskOptions, SearchChoice, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let customer = client
+    ///     .ask_searchable_choice(
+    ///         "Which customer gets the refund?",
+    ///         |query: String| async move {
+    ///             // Look the customers up in the CRM
+    ///             Ok(vec![SearchChoice::new("cus_1", format!("Acme ({query})"))])
+    ///         },
+    ///         None::<&str>,
+    ///         Some(AskOptions::new().with_long_poll()),
+    ///     )
+    ///     .await?;
+    /// println!("Refunding {}", customer.key);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_searchable_choice<S, B, P>(
+        &self,
+        subject: S,
+        provider: P,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<SearchChoice>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        P: ChoiceProvider + 'static,
+    {
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject: subject.into(),
+            body: body.map(|b| b.into()),
+            answer_format: AnswerFormat::Search,
+        };
+        let options = options.unwrap_or_default().with_choice_provider(provider);
+
+        self.ask_typed(question, Some(options), |answer| {
+            let AnswerContent::Search { key, label } =
+                content_for(answer, &AnswerFormat::Search, false)?
+            else {
+                unreachable!("checked by content_for");
+            };
+            Ok(SearchChoice { key, label })
+        })
+        .await
+    }
+
+    /// Convenience method for questions answered with a file, such as a signed PDF or
+    /// a photo the human takes
+    ///
+    /// The file comes base64-encoded in the answer and is returned decoded, with its
+    /// content type. Files larger than `AskOptions::max_binary_bytes` (10 MiB by
+    /// default) are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The question subject/title
+    /// * `content_types` - Content types accepted, e.g. `application/pdf` or `image/*`;
+    ///   any if empty
+    /// * `body` - Optional detailed question body
+    /// * `options` - Optional settings like timeout
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request fails or times out
+    /// - The answer type doesn't match (not binary), or the file isn't of an accepted
+    ///   content type
+    /// - The file is too large (`BinaryAnswerTooLarge`)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::WaitHuman;
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let contract = client
+    ///     .ask_binary(
+    ///         "Please upload the signed contract",
+    ///         ["application/pdf"],
+    ///         None::<&str>,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// std::fs::write("contract.pdf", &contract.data).expect("writable");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_binary<S, B, C>(
+        &self,
+        subject: S,
+        content_types: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<BinaryAnswer>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        let answer_format = AnswerFormat::Binary {
+            content_types: content_types.into_iter().map(|c| c.into()).collect(),
+        };
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject: subject.into(),
+            body: body.map(|b| b.into()),
+            answer_format: answer_format.clone(),
+        };
+
+        self.ask_typed(question, options, |answer| {
+            let AnswerContent::Binary { content_type, data } =
+                content_for(answer, &answer_format, false)?
+            else {
+                unreachable!("checked by content_for");
+            };
+            Ok(BinaryAnswer {
+                content_type,
+                data: data.into_inner(),
+            })
+        })
+        .await
+    }
+
+    /// Like [`ask`](Self::ask), but returns `Ok(None)` instead of a `Timeout` error
+    ///
+    /// Useful when nobody answering in time is a normal branch rather than an error.
+    /// All other errors are still returned as `Err`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use wait_human::{AnswerFormat, AskOptions, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Anything to add before the release?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    /// let options = AskOptions {
+    ///     timeout: Some(Duration::from_secs(60)),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// match client.try_ask(question, Some(options)).await? {
+    ///     Some(answer) => println!("{:?}", answer.as_free_text()),
+    ///     None => println!("nobody answered, releasing anyway"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn try_ask(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<Option<ConfirmationAnswerWithDate>> {
+        none_on_timeout(self.ask(question, options).await)
+    }
+
+    /// Like [`ask_free_text`](Self::ask_free_text), but returns `Ok(None)` instead of a
+    /// `Timeout` error
+    #[cfg(feature = "convenience")]
+    pub async fn try_ask_free_text<S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<String>>
+    where
+        S: Into<String>,
+        B: Into<String>,
+    {
+        none_on_timeout(self.ask_free_text(subject, body, options).await)
+    }
+
+    /// Like [`ask_multiple_choice`](Self::ask_multiple_choice), but returns `Ok(None)`
+    /// instead of a `Timeout` error
+    #[cfg(feature = "convenience")]
+    pub async fn try_ask_multiple_choice<S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<String>>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        none_on_timeout(
+            self.ask_multiple_choice(subject, choices, body, options)
+                .await,
+        )
+    }
+
+    /// Like [`ask_choice`](Self::ask_choice), but returns `Ok(None)` instead of a
+    /// `Timeout` error
+    #[cfg(feature = "convenience")]
+    pub async fn try_ask_choice<T, S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<T>>
+    where
+        T: Choices,
+        S: Into<String>,
+        B: Into<String>,
+    {
+        none_on_timeout(self.ask_choice(subject, body, options).await)
+    }
+
+    /// Like [`ask_free_text`](Self::ask_free_text), but lets the human abstain, which
+    /// returns `Ok(None)`
+    ///
+    /// Timing out is still a `Timeout` error, so answered, abstained and timed out are
+    /// three distinct outcomes. See `AskOptions::allow_abstain`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{WaitHuman, WaitHumanError};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// match client
+    ///     .ask_free_text_or_abstain("Any concerns with this design?", None::<&str>, None)
+    ///     .await
+    /// {
+    ///     Ok(Some(concerns)) => println!("concerns: {}", concerns),
+    ///     Ok(None) => println!("the reviewer abstained"),
+    ///     Err(WaitHumanError::Timeout { .. }) => println!("nobody reviewed it in time"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "convenience")]
+    pub async fn ask_free_text_or_abstain<S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<String>>
+    where
+        S: Into<String>,
+        B: Into<String>,
+    {
+        let options = options.unwrap_or_default().with_allow_abstain();
+        none_on_abstain(self.ask_free_text(subject, body, Some(options)).await)
+    }
+
+    /// Like [`ask_multiple_choice`](Self::ask_multiple_choice), but lets the human
+    /// abstain, which returns `Ok(None)`
+    #[cfg(feature = "convenience")]
+    pub async fn ask_multiple_choice_or_abstain<S, B, C>(
+        &self,
+        subject: S,
+        choices: C,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<String>>
+    where
+        S: Into<String>,
+        B: Into<String>,
+        C: IntoIterator,
+        C::Item: Into<String>,
+    {
+        let options = options.unwrap_or_default().with_allow_abstain();
+        none_on_abstain(
+            self.ask_multiple_choice(subject, choices, body, Some(options))
+                .await,
+        )
+    }
+
+    /// Like [`ask_choice`](Self::ask_choice), but lets the human abstain, which returns
+    /// `Ok(None)`
+    #[cfg(feature = "convenience")]
+    pub async fn ask_choice_or_abstain<T, S, B>(
+        &self,
+        subject: S,
+        body: Option<B>,
+        options: Option<AskOptions>,
+    ) -> Result<Option<T>>
+    where
+        T: Choices,
+        S: Into<String>,
+        B: Into<String>,
+    {
+        let options = options.unwrap_or_default().with_allow_abstain();
+        none_on_abstain(self.ask_choice(subject, body, Some(options)).await)
+    }
+
+    /// Checks that a question is well-formed and the client is configured correctly,
+    /// without creating a confirmation: no human is contacted
+    ///
+    /// Runs every client-side check an ask would, then sends the question to the
+    /// server's validation endpoint, which also verifies the API key. Servers without
+    /// that endpoint are skipped, leaving only the client-side checks. Handy for CI smoke
+    /// tests; see also `AskOptions::dry_run`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error creating the confirmation would have returned, e.g.
+    /// `InvalidQuestion`, `Unauthorized` or `CreateFailed`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Deploy to production?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::Options {
+    ///         options: vec!["Yes".to_string(), "No".to_string()],
+    ///         multiple: false,
+    ///     },
+    /// };
+    ///
+    /// client.validate(question, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn validate(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<()> {
+        let options = options.unwrap_or_default();
+        validate_timeout(&options)?;
+        validate_default_answer(&question, &options)?;
+        self.validate_confirmation(question, &options, Instant::now())
+            .await
+    }
+
+    /// Creates a confirmation without waiting for its answer
+    ///
+    /// Returns its id along with when the server will expire it, if it does, so callers
+    /// can display or plan around the deadline. Wait for the answer with
+    /// [`wait_for_answer`](Self::wait_for_answer) or [`subscribe`](Self::subscribe).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the question is invalid or the confirmation cannot be created
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerFormat, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Approve the release?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    ///
+    /// let created = client.create(question, None).await?;
+    /// if let Some(expires_at) = created.expires_at {
+    ///     println!("{} expires at {}", created.id, expires_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<CreatedConfirmation> {
+        let options = options.unwrap_or_default();
+        self.create_confirmation(question, &options, Instant::now())
+            .await
+    }
+
+    /// Creates a confirmation exactly once, even across network failures, without waiting
+    /// for its answer
+    ///
+    /// The create is sent with `AskOptions::idempotency_key` (a random key if unset).
+    /// When it fails without a response, or the server reports the same key is still
+    /// being processed, the client looks the key up and adopts the existing confirmation
+    /// instead of creating a second one. Only if none exists is the create retried, up
+    /// to 3 attempts in total. With a timeout or deadline on `options`, the attempts, the
+    /// lookups and the waits between them all share it.
+    ///
+    /// Wait for the answer with [`wait_for_answer`](Self::wait_for_answer) or
+    /// [`subscribe`](Self::subscribe).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the question is invalid, or the create still fails after
+    /// the last attempt
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerFormat, AskOptions, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Wire $10,000 to ACME?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    /// let options = AskOptions::default().with_idempotency_key("payment-8812");
+    ///
+    /// let confirmation_id = client.create_idempotent(question, Some(options)).await?;
+    /// println!("{}", client.confirmation_url(&confirmation_id));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_idempotent(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> Result<ConfirmationId> {
+        let start = Instant::now();
+        let mut options = options.unwrap_or_default();
+        validate_timeout(&options)?;
+        let key = options
+            .idempotency_key
+            .get_or_insert_with(random_idempotency_key)
+            .clone();
+        let budget = Budget::new(&options, start);
+        let route = self.route_for(&options)?;
+
+        let mut attempt = 1;
+        loop {
+            let create = self.create_confirmation(question.clone(), &options, start);
+            let error = match self.within(&budget, create).await {
+                Ok(created) => return Ok(created.id),
+                Err(error) => error,
+            };
+
+            // Without a response, or with the original create still in flight, the
+            // confirmation may exist already
+            let in_flight = error.status() == Some(StatusCode::CONFLICT.as_u16());
+            let no_response = matches!(error, WaitHumanError::NetworkError { status: None, .. });
+            if !in_flight && !no_response {
+                return Err(error);
+            }
+
+            if in_flight {
+                self.backoff(&budget, Duration::from_millis(IN_FLIGHT_RETRY_MS))
+                    .await?;
+            }
+            let lookup = self.find_by_idempotency_key(&route, &key);
+            if let Some(confirmation_id) = self.within(&budget, lookup).await? {
+                return Ok(confirmation_id);
+            }
+
+            if attempt == IDEMPOTENT_CREATE_ATTEMPTS {
+                return Err(error);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Returns the confirmation created with an idempotency key, if any
+    async fn find_by_idempotency_key(
+        &self,
+        route: &Route,
+        key: &str,
+    ) -> Result<Option<ConfirmationId>> {
+        let url = route.url(&["confirmations", "by-idempotency-key"]);
+
+        let request = self
+            .request_on(route, Method::GET, &url)
+            .query(&[("key", key)]);
+        let response = self.send(request).await?;
+
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        let data: CreateConfirmationResponse = read_json(response).await?;
+        Ok(Some(data.confirmation_request_id))
+    }
+
+    /// Fetches the answer of a confirmation once, without waiting
+    ///
+    /// Returns `None` while the confirmation is pending. Useful to reconcile an answer
+    /// that arrived after an ask timed out, using the id carried by the `Timeout` error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or `Expired`, `Declined` or `Cancelled` if
+    /// the confirmation ended without an answer
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{WaitHuman, WaitHumanError};
+    ///
+    /// # async fn run(client: WaitHuman, question: wait_human::ConfirmationQuestion) -> wait_human::Result<()> {
+    /// match client.ask(question, None).await {
+    ///     Err(WaitHumanError::Timeout {
+    ///         confirmation_id: Some(confirmation_id),
+    ///         ..
+    ///     }) => {
+    ///         // Later, e.g. from a reconciliation job
+    ///         if let Some(answer) = client.get_answer(&confirmation_id).await? {
+    ///             println!("answered late: {:?}", answer.as_free_text());
+    ///         }
+    ///     }
+    ///     result => println!("{:?}", result),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_answer(
+        &self,
+        confirmation_id: &ConfirmationId,
+    ) -> Result<Option<ConfirmationAnswerWithDate>> {
+        self.fetch_answer(confirmation_id, &AskOptions::default(), Instant::now())
+            .await
+    }
+
+    /// Fetches where a confirmation stands, in a single request
+    ///
+    /// Tells apart a pending confirmation from one that expired, was declined or was
+    /// cancelled, which `get_answer` reports as errors. Servers that don't report the
+    /// state of unanswered confirmations always appear `Pending` until answered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response can't be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{ConfirmationId, ConfirmationStatus, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, id: ConfirmationId) -> wait_human::Result<()> {
+    /// match client.status(&id).await? {
+    ///     ConfirmationStatus::Pending => println!("waiting"),
+    ///     ConfirmationStatus::Answered(answer) => println!("answered at {}", answer.answered_at),
+    ///     other => println!("ended without an answer: {:?}", other),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&self, confirmation_id: &ConfirmationId) -> Result<ConfirmationStatus> {
+        let options = AskOptions::default();
+        let target = self.poll_target(confirmation_id, &options)?;
+        let (status, _) = self.poll_status(&target, &options, Instant::now()).await?;
+        Ok(status)
+    }
+
+    /// Fetches the answers of several confirmations in a single request
+    ///
+    /// Returns the answers in the order of `confirmation_ids`, with `None` for those
+    /// still pending. Much cheaper than polling each confirmation when waiting on many.
+    /// Servers without the bulk endpoint are queried once per confirmation instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or `Expired`, `Declined` or `Cancelled`
+    /// (carrying its id) if one of the confirmations ended without an answer
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{ConfirmationId, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, ids: Vec<ConfirmationId>) -> wait_human::Result<()> {
+    /// let answers = client.get_answers(&ids).await?;
+    /// let answered = answers.iter().filter(|answer| answer.is_some()).count();
+    /// println!("{}/{} answered", answered, ids.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_answers(
+        &self,
+        confirmation_ids: &[ConfirmationId],
+    ) -> Result<Vec<Option<ConfirmationAnswerWithDate>>> {
+        self.fetch_answers(confirmation_ids, &AskOptions::default(), Instant::now())
+            .await
+    }
+
+    /// Returns the web URL where a human can answer the given confirmation
+    ///
+    /// Useful for out-of-band notification flows, e.g. posting the link to Slack yourself.
+    /// The base URL is `WaitHumanConfig::app_url` if set, otherwise the custom endpoint,
+    /// otherwise the WaitHuman web app.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wait_human::{WaitHuman, WaitHumanConfig};
+    ///
+    /// let client = WaitHuman::new(
+    ///     WaitHumanConfig::new("your-api-key").with_app_url("https://approve.example.com/"),
+    /// )?;
+    /// assert_eq!(
+    ///     client.confirmation_url(&"abc123".parse()?),
+    ///     "https://approve.example.com/confirmations/abc123"
+    /// );
+    /// # Ok::<(), wait_human::WaitHumanError>(())
+    /// ```
+    ///
+    /// Paths are appended to any path of the base URL, with or without a trailing slash:
+    ///
+    /// ```
+    /// use wait_human::{WaitHuman, WaitHumanConfig};
+    ///
+    /// for endpoint in ["https://gw.example.com/waithuman", "https://gw.example.com/waithuman/"] {
+    ///     let client = WaitHuman::new(WaitHumanConfig::new("your-api-key").with_endpoint(endpoint))?;
+    ///     assert_eq!(
+    ///         client.confirmation_url(&"abc123".parse()?),
+    ///         "https://gw.example.com/waithuman/confirmations/abc123"
+    ///     );
+    /// }
+    /// # Ok::<(), wait_human::WaitHumanError>(())
+    /// ```
+    pub fn confirmation_url(&self, confirmation_id: &ConfirmationId) -> String {
+        join_url(
+            &self.app_url,
+            &["confirmations", confirmation_id.as_str()],
+            false,
+        )
+        .into()
+    }
+
+    /// Verifies that the API key and endpoint are valid
+    ///
+    /// Sends an authenticated no-op request, so misconfiguration can be detected at
+    /// startup rather than when the first question is asked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The API key is rejected (`Unauthorized`)
+    /// - The endpoint is unreachable or returns another error status
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::WaitHuman;
+    ///
+    /// # async fn run() -> wait_human::Result<()> {
+    /// let client = WaitHuman::from_env()?;
+    /// client.verify_credentials().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify_credentials(&self) -> Result<()> {
+        let url = self.api_url(&["auth", "verify"]);
+
+        let response = self.send(self.request(Method::GET, &url)).await?;
+
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the kinds of answer format both this client and the server support
+    ///
+    /// Asks the server's capabilities endpoint the first time, and caches its answer
+    /// for the life of the client and its clones. Servers without the endpoint are
+    /// taken to support every format this client knows ([`AnswerFormatKind::ALL`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capabilities request fails other than by the endpoint
+    /// not existing; the next call asks again.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerFormatKind, WaitHuman};
+    ///
+    /// # async fn run() -> wait_human::Result<()> {
+    /// let client = WaitHuman::from_env()?;
+    /// if client
+    ///     .supported_answer_formats()
+    ///     .await?
+    ///     .contains(&AnswerFormatKind::Form)
+    /// {
+    ///     // Ask all the fields at once
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn supported_answer_formats(&self) -> Result<Vec<AnswerFormatKind>> {
+        if let Some(formats) = self.answer_formats.get() {
+            return Ok(formats.clone());
+        }
+
+        let url = self.api_url(&["capabilities"]);
+        let response = self.send(self.request(Method::GET, &url)).await?;
+        let response = check_response(response).await?;
+
+        let formats = match response.status() {
+            // Older servers: every format the client knows
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+                AnswerFormatKind::ALL.to_vec()
+            }
+            status if status.is_success() => {
+                let capabilities: CapabilitiesResponse = read_json(response).await?;
+                capabilities
+                    .answer_formats
+                    .into_iter()
+                    .filter_map(|format| serde_json::from_value(format).ok())
+                    .collect()
+            }
+            status => {
+                return Err(WaitHumanError::RequestFailed {
+                    status: status.as_u16(),
+                    status_text: status.to_string(),
+                })
+            }
+        };
+
+        Ok(self.answer_formats.get_or_init(|| formats).clone())
+    }
+
+    /// Re-sends the notification of a pending confirmation to the human
+    ///
+    /// Nudging a confirmation that is no longer pending (e.g. already answered) is a
+    /// no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails
+    pub async fn nudge_confirmation(&self, confirmation_id: &ConfirmationId) -> Result<()> {
+        self.nudge_at(&self.default_route(), confirmation_id).await
+    }
+
+    async fn nudge_at(&self, route: &Route, confirmation_id: &ConfirmationId) -> Result<()> {
+        let url = route.url(&["confirmations", "nudge", confirmation_id.as_str()]);
+
+        let response = self
+            .send(self.request_on(route, Method::POST, &url))
+            .await?;
+
+        let response = check_response(response).await?;
+
+        // The server answers 409 when the confirmation is no longer pending
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::CONFLICT {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the question of a pending confirmation, e.g. when the price it quotes
+    /// changed, keeping the notification the human already received
+    ///
+    /// The new question is validated like a created one. The human sees the new version
+    /// from then on; the returned [`ConfirmationUpdate`] tells whether they had seen the
+    /// old one, if the server reports it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The question is invalid (`InvalidQuestion`)
+    /// - The confirmation was answered first (`AlreadyAnswered`, carrying the answer),
+    ///   including when the human answers while the update is in flight
+    /// - The confirmation expired, was declined or cancelled (`Expired`, `Declined`,
+    ///   `Cancelled`)
+    /// - The request fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{
+    ///     AnswerFormat, ConfirmationId, ConfirmationQuestion, QuestionMethod, WaitHuman,
+    ///     WaitHumanError,
+    /// };
+    ///
+    /// # async fn run(client: WaitHuman, confirmation_id: ConfirmationId) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Buy 100 shares at $12.40?".to_string(),
+    ///     body: Some("The price moved from $12.10.".to_string()),
+    ///     answer_format: AnswerFormat::Options {
+    ///         options: vec!["Buy".to_string(), "Skip".to_string()],
+    ///         multiple: false,
+    ///     },
+    /// };
+    ///
+    /// match client.update_confirmation(&confirmation_id, question).await {
+    ///     Ok(update) if update.previously_seen == Some(true) => {
+    ///         println!("the human saw the old price");
+    ///     }
+    ///     Ok(_) => {}
+    ///     Err(WaitHumanError::AlreadyAnswered { answer, .. }) => {
+    ///         println!("answered at the old price: {:?}", answer.selected_indexes());
+    ///     }
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_confirmation(
+        &self,
+        confirmation_id: &ConfirmationId,
+        question: ConfirmationQuestion,
+    ) -> Result<ConfirmationUpdate> {
+        validate_question(&question)?;
+
+        let url = self.api_url(&["confirmations", "update", confirmation_id.as_str()]);
+        let request = self.request(Method::PATCH, &url).json_body(
+            &UpdateConfirmationRequest {
+                question: &question,
+            },
+            self,
+        );
+
+        let response = self.send(request).await?;
+
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if status == StatusCode::BAD_REQUEST || status == StatusCode::UNPROCESSABLE_ENTITY {
+            let message = response_text(response).await.unwrap_or_default();
+            return Err(WaitHumanError::InvalidQuestion(message));
+        }
+
+        // The server answers 409 when the confirmation is no longer pending, which may
+        // be an answer that won the race with this update
+        if status == StatusCode::CONFLICT {
+            let options = AskOptions::default();
+            let target = self.poll_target(confirmation_id, &options)?;
+            let (state, _) = self.poll_status(&target, &options, Instant::now()).await?;
+            return Err(match state.into_answer(confirmation_id) {
+                Ok(Some(answer)) => WaitHumanError::AlreadyAnswered {
+                    confirmation_id: confirmation_id.clone(),
+                    answer: Box::new(answer),
+                },
+                Ok(None) => WaitHumanError::RequestFailed {
+                    status: status.as_u16(),
+                    status_text: status.to_string(),
+                },
+                Err(e) => e,
+            });
+        }
+
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        let update = match status {
+            StatusCode::NO_CONTENT => ConfirmationUpdate::default(),
+            _ => read_json(response).await?,
+        };
+        self.remember_question(confirmation_id, question).await;
+        Ok(update)
+    }
+
+    /// Cancels a pending confirmation, so it is no longer shown to the human
+    ///
+    /// Cancelling a confirmation that is no longer pending (e.g. already answered) is a
+    /// no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails
+    pub async fn cancel_confirmation(&self, confirmation_id: &ConfirmationId) -> Result<()> {
+        self.cancel_at(&self.default_route(), confirmation_id).await
+    }
+
+    async fn cancel_at(&self, route: &Route, confirmation_id: &ConfirmationId) -> Result<()> {
+        let url = route.url(&["confirmations", "cancel", confirmation_id.as_str()]);
+
+        let response = self
+            .send(self.request_on(route, Method::POST, &url))
+            .await?;
+
+        let response = check_response(response).await?;
+
+        // The server answers 409 when the confirmation is no longer pending
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::CONFLICT {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        self.forget(confirmation_id).await;
+        Ok(())
+    }
+
+    /// Returns the confirmations saved to the configured store, oldest first: those
+    /// created by this client or an earlier run that are still waiting on an answer
+    ///
+    /// After a restart, resume waiting on each with
+    /// [`wait_for_answer`](Self::wait_for_answer). Confirmations that timed out stay in
+    /// the store, so they can be resumed too; those answered, expired, declined or
+    /// cancelled are removed as the client learns about it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidConfig` if no store is configured, or `StoreFailed` if the store
+    /// can't be read
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use wait_human::{JsonFileStore, WaitHuman, WaitHumanConfig};
+    ///
+    /// # async fn run() -> wait_human::Result<()> {
+    /// let client = WaitHuman::new(
+    ///     WaitHumanConfig::new("your-api-key")
+    ///         .with_store(Arc::new(JsonFileStore::new("pending-approvals.json"))),
+    /// )?;
+    ///
+    /// for stored in client.stored_confirmations().await? {
+    ///     let answer = client.wait_for_answer(&stored.id, None).await?;
+    ///     println!("{}: {:?}", stored.question.subject, answer.as_free_text());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+    pub async fn stored_confirmations(&self) -> Result<Vec<StoredConfirmation>> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| WaitHumanError::InvalidConfig {
+                field: "store",
+                message: "no confirmation store is configured".to_string(),
+            })?;
+        store.load().await
+    }
+
+    /// Returns the number of asks in flight
+    ///
+    /// Always 0 unless tracking is enabled with `WaitHumanConfig::with_in_flight_tracking`.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+            .as_ref()
+            .map_or(0, |in_flight| in_flight.len())
+    }
+
+    /// Drains the asks in flight, for a clean shutdown during deploys
+    ///
+    /// New asks on this client and its clones fail with `ShuttingDown` from now on.
+    /// Asks in flight get up to `timeout` to receive their answers; the confirmations
+    /// of those still waiting afterwards are cancelled, which ends them with
+    /// `Cancelled`. Returns the number of confirmations cancelled.
+    ///
+    /// Requires tracking enabled with `WaitHumanConfig::with_in_flight_tracking`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidConfig` if tracking is not enabled
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use wait_human::{WaitHuman, WaitHumanConfig};
+    ///
+    /// # async fn run() -> wait_human::Result<()> {
+    /// let client = WaitHuman::new(WaitHumanConfig::new("your-api-key").with_in_flight_tracking())?;
+    ///
+    /// // ... on SIGTERM:
+    /// let cancelled = client.shutdown(Duration::from_secs(30)).await?;
+    /// println!("cancelled {} pending approvals", cancelled);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> Result<usize> {
+        let in_flight = self
+            .in_flight
+            .as_ref()
+            .ok_or_else(|| WaitHumanError::InvalidConfig {
+                field: "track_in_flight",
+                message: "in-flight tracking is not enabled".to_string(),
+            })?;
+        in_flight.begin_shutdown();
+
+        let deadline = Instant::now() + timeout;
+        while in_flight.len() > 0 && Instant::now() < deadline {
+            self.sleep(Duration::from_millis(SHUTDOWN_CHECK_MS)).await;
+        }
+
+        // Best effort: a confirmation that can't be cancelled expires on its own
+        let cancelled = join_all(in_flight.pending_confirmations().into_iter().map(
+            |(route, confirmation_id)| async move {
+                self.cancel_at(&route, &confirmation_id).await.is_ok()
+            },
+        ))
+        .await;
+
+        Ok(cancelled.into_iter().filter(|cancelled| *cancelled).count())
+    }
+
+    /// Submits an answer to a confirmation on behalf of the human
+    ///
+    /// For building a custom answer UI on top of the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The answer is malformed, or the server rejects it, e.g. because it doesn't match
+    ///   the question's answer format (`InvalidAnswer`)
+    /// - The request fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerContent, ConfirmationId, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, confirmation_id: ConfirmationId) -> wait_human::Result<()> {
+    /// client
+    ///     .submit_answer(&confirmation_id, AnswerContent::Options { selected_indexes: vec![0] })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn submit_answer(
+        &self,
+        confirmation_id: &ConfirmationId,
+        answer_content: AnswerContent,
+    ) -> Result<()> {
+        self.submit_answer_with_attachments(confirmation_id, answer_content, Vec::new())
+            .await
+    }
+
+    /// Like [`submit_answer`](Self::submit_answer), with files attached to the answer,
+    /// e.g. a signed document or a revised design
+    ///
+    /// Inline attachments are uploaded as multipart form data, while URL attachments are
+    /// sent as references. The asker sees them in
+    /// [`ConfirmationAnswerWithDate::attachments`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`submit_answer`](Self::submit_answer), and
+    /// `AttachmentTooLarge` if an inline attachment exceeds [`MAX_ATTACHMENT_BYTES`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{AnswerContent, Attachment, ConfirmationId, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, confirmation_id: ConfirmationId) -> Result<(), Box<dyn std::error::Error>> {
+    /// let signed = std::fs::read("contract-signed.pdf")?;
+    ///
+    /// client
+    ///     .submit_answer_with_attachments(
+    ///         &confirmation_id,
+    ///         AnswerContent::FreeText { text: "Signed, see attached".to_string() },
+    ///         vec![Attachment::inline("contract-signed.pdf", "application/pdf", signed)],
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn submit_answer_with_attachments(
+        &self,
+        confirmation_id: &ConfirmationId,
+        answer_content: AnswerContent,
+        attachments: Vec<Attachment>,
+    ) -> Result<()> {
+        validate_answer(&answer_content)?;
+        check_attachment_sizes(&attachments)?;
+
+        let url = self.api_url(&["confirmations", "answer", confirmation_id.as_str()]);
+        let answer = ConfirmationAnswer {
+            answer_content,
+            attachments: Vec::new(),
+            signature: None,
+            confidence: None,
+            comment: None,
+        };
+
+        let request = self.request(Method::POST, &url);
+
+        let request = if attachments.is_empty() {
+            request.json_body(&answer, self)
+        } else {
+            request.multipart(answer_form(&answer, attachments)?)
+        };
+
+        let response = self.send(request).await?;
+
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if status == StatusCode::BAD_REQUEST || status == StatusCode::UNPROCESSABLE_ENTITY {
+            let message = response_text(response).await.unwrap_or_default();
+            return Err(WaitHumanError::InvalidAnswer(message));
+        }
+
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Lists recent confirmations, one page at a time
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Restricts which confirmations are listed
+    /// * `cursor` - `next_cursor` of the previous page, or `None` for the first page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The cursor is invalid or expired (`InvalidCursor`)
+    /// - The request fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{ConfirmationState, ListFilter, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let filter = ListFilter {
+    ///     status: Some(ConfirmationState::Pending),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut cursor = None;
+    /// loop {
+    ///     let page = client.list_confirmations(&filter, cursor.as_deref()).await?;
+    ///     for confirmation in &page.confirmations {
+    ///         println!("{}: {}", confirmation.id, confirmation.subject);
+    ///     }
+    ///     match page.next_cursor {
+    ///         Some(next) => cursor = Some(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_confirmations(
+        &self,
+        filter: &ListFilter,
+        cursor: Option<&str>,
+    ) -> Result<ConfirmationPage> {
+        let url = self.api_url(&["confirmations", "list"]);
+
+        let request = self
+            .request(Method::GET, &url)
+            .query(&ListConfirmationsQuery { filter, cursor });
+        let response = self.send(request).await?;
+
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if status == StatusCode::BAD_REQUEST {
+            if let Some(cursor) = cursor {
+                return Err(WaitHumanError::InvalidCursor {
+                    cursor: cursor.to_string(),
+                });
+            }
+        }
+
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        read_json(response).await
+    }
+
+    /// Fetches the audit trail of a confirmation: when it was created, notified, seen,
+    /// reminded and answered, and by whom
+    ///
+    /// Events are returned oldest first. A confirmation the server has no events for
+    /// yields an empty history.
+    ///
+    /// # Arguments
+    ///
+    /// * `confirmation_id` - Id of the confirmation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, e.g. with `RequestFailed` for an unknown
+    /// confirmation
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::{ConfirmationId, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, confirmation_id: ConfirmationId) -> wait_human::Result<()> {
+    /// for event in client.get_history(&confirmation_id).await? {
+    ///     println!("{} {:?} by {:?}", event.at, event.kind, event.actor);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_history(&self, confirmation_id: &ConfirmationId) -> Result<Vec<HistoryEvent>> {
+        let url = self.api_url(&["confirmations", "history", confirmation_id.as_str()]);
+
+        let response = self.send(self.request(Method::GET, &url)).await?;
+        let response = check_response(response).await?;
+
+        let status = response.status();
+        if status == StatusCode::NO_CONTENT {
+            return Ok(Vec::new());
+        }
+        if !status.is_success() {
+            return Err(WaitHumanError::RequestFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            });
+        }
+
+        let mut events = read_json::<GetHistoryResponse>(response).await?.events;
+        events.sort_by_key(|event| event.at);
+        Ok(events)
+    }
+
+    /// Subscribes to updates of an existing confirmation as a stream of events
+    ///
+    /// Yields [`ConfirmationEvent::Pending`] once while the confirmation awaits an answer,
+    /// [`ConfirmationEvent::Reassigned`] whenever it is handed over to another human,
+    /// [`ConfirmationEvent::PartialAnswer`] whenever the answer progresses without being
+    /// complete, then [`ConfirmationEvent::Answered`] as the final item. The API has no push channel,
+    /// so the stream polls under the hood; drop it to stop polling. Terminal states such as
+    /// expiry are yielded as an error, which also ends the stream.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use wait_human::{ConfirmationEvent, ConfirmationId, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman, confirmation_id: ConfirmationId) -> wait_human::Result<()> {
+    /// let mut events = std::pin::pin!(client.subscribe(confirmation_id));
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     match event? {
+    ///         ConfirmationEvent::Pending => println!("waiting for a human..."),
+    ///         ConfirmationEvent::Reassigned { to } => println!("handed over to {to}"),
+    ///         ConfirmationEvent::PartialAnswer(partial) => println!("so far: {:?}", partial),
+    ///         ConfirmationEvent::Answered(answer) => println!("answered at {}", answer.answered_at),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe(
+        &self,
+        confirmation_id: ConfirmationId,
+    ) -> impl Stream<Item = Result<ConfirmationEvent>> + '_ {
+        let options = AskOptions::default();
+        let start = Instant::now();
+
+        // The state is the wait before the next poll, which is None until Pending was
+        // yielded, and what the polls reported so far, or None once the stream ended
+        stream::unfold(
+            Some((None, Seen::default())),
+            move |state: Option<(Option<Duration>, Seen)>| {
+                let confirmation_id = confirmation_id.clone();
+                let options = options.clone();
+
+                async move {
+                    let (mut wait, mut seen) = state?;
+
+                    loop {
+                        if let Some(wait) = wait {
+                            self.sleep(wait).await;
+                        }
+
+                        let result = match self.poll_target(&confirmation_id, &options) {
+                            Ok(target) => self.poll_once(&target, &options, start).await,
+                            Err(e) => Err(e),
+                        };
+                        match result {
+                            Ok((Some(answer), _)) => {
+                                return Some((
+                                    Ok(ConfirmationEvent::Answered(Box::new(answer))),
+                                    None,
+                                ))
+                            }
+                            Ok((None, hints)) => {
+                                let next = hints
+                                    .wait
+                                    .unwrap_or_else(|| poll_interval(&options, self.poll_interval));
+                                // Changes found by the first poll are reported by the next
+                                // ones, after Pending, one per poll
+                                if wait.is_none() {
+                                    let state = Some((Some(next), seen));
+                                    return Some((Ok(ConfirmationEvent::Pending), state));
+                                }
+                                if let Some(to) = changed(&mut seen.assignee, hints.reassigned_to) {
+                                    let state = Some((Some(next), seen));
+                                    return Some((Ok(ConfirmationEvent::Reassigned { to }), state));
+                                }
+                                if let Some(partial) =
+                                    changed(&mut seen.partial_answer, hints.partial_answer)
+                                {
+                                    let event = ConfirmationEvent::PartialAnswer(partial);
+                                    return Some((Ok(event), Some((Some(next), seen))));
+                                }
+                                wait = Some(next);
+                            }
+                            Err(e) => return Some((Err(e), None)),
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    /// Asks a question and follows the wait as a stream of statuses
+    ///
+    /// Yields [`AskStatus::Pending`] after every poll that found no answer yet, or
+    /// [`AskStatus::Reassigned`] when the question was handed over to another human, or
+    /// [`AskStatus::PartialAnswer`] when the answer progressed without being complete, then
+    /// [`AskStatus::Answered`] as the final item, so UI code can render progress with a
+    /// simple loop. Errors, including a `Timeout` once the ask's deadline passes, are
+    /// yielded as the final item instead. Dropping the stream stops polling, and with
+    /// `AskOptions::cancel_on_drop` also cancels the confirmation.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use wait_human::{AnswerFormat, AskStatus, ConfirmationQuestion, QuestionMethod, WaitHuman};
+    ///
+    /// # async fn run(client: WaitHuman) -> wait_human::Result<()> {
+    /// let question = ConfirmationQuestion {
+    ///     method: QuestionMethod::Push,
+    ///     subject: "Approve the release?".to_string(),
+    ///     body: None,
+    ///     answer_format: AnswerFormat::FreeText,
+    /// };
+    /// let mut statuses = std::pin::pin!(client.ask_stream(question, None));
+    ///
+    /// while let Some(status) = statuses.next().await {
+    ///     match status? {
+    ///         AskStatus::Pending { elapsed, polls, .. } => {
+    ///             println!("waiting for {:?} ({} polls)", elapsed, polls)
+    ///         }
+    ///         AskStatus::Reassigned { to } => println!("handed over to {to}"),
+    ///         AskStatus::PartialAnswer(partial) => println!("so far: {:?}", partial),
+    ///         AskStatus::Answered(answer) => println!("answered: {:?}", answer.as_free_text()),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask_stream(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> impl Stream<Item = Result<AskStatus>> + '_ {
+        let options = options.unwrap_or_default();
+        let start = Instant::now();
+
+        stream::unfold(AskStreamState::Create(question), move |state| {
+            let options = options.clone();
+
+            async move {
+                if let AskStreamState::Done = state {
+                    return None;
+                }
+
+                let confirmation_id = state.confirmation_id();
+                let step = self.ask_stream_step(state, &options, start);
+                let result = self
+                    .within(&Budget::new(&options, start), step)
+                    .await
+                    .map_err(|e| match e {
+                        WaitHumanError::Timeout {
+                            elapsed_seconds, ..
+                        } => WaitHumanError::Timeout {
+                            elapsed_seconds,
+                            confirmation_id,
+                            subject: None,
+                        },
+                        e => e,
+                    });
+
+                match result {
+                    Ok((status, next)) => Some((Ok(status), next)),
+                    Err(e) => Some((Err(e), AskStreamState::Done)),
+                }
+            }
+        })
+    }
+
+    /// Verifies and parses a webhook request the server sent when a confirmation was
+    /// answered
+    ///
+    /// An alternative to polling for event-driven services: wire it into any HTTP
+    /// server, passing the raw request body and the values of the
+    /// [`WEBHOOK_SIGNATURE_HEADER`](crate::WEBHOOK_SIGNATURE_HEADER) and
+    /// [`WEBHOOK_TIMESTAMP_HEADER`](crate::WEBHOOK_TIMESTAMP_HEADER) headers. The
+    /// signature is checked against `WaitHumanConfig::webhook_secret`, and requests
+    /// signed more than [`WEBHOOK_TOLERANCE`](crate::WEBHOOK_TOLERANCE) away from now are rejected as possible
+    /// replays. Respond with a 4xx status when verification fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidWebhook` if the signature or timestamp is invalid or the body is
+    /// malformed, and `InvalidConfig` if no webhook secret is configured
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hmac::{Hmac, Mac};
+    /// use sha2::Sha256;
+    /// use wait_human::{WaitHuman, WaitHumanConfig, WaitHumanError};
+    ///
+    /// let client = WaitHuman::new(
+    ///     WaitHumanConfig::new("your-api-key").with_webhook_secret("whsec_test"),
+    /// )?;
+    ///
+    /// let body = br#"{
+    ///     "confirmation_request_id": "abc123",
+    ///     "answer": {
+    ///         "answer": { "answer_content": { "type": "free_text", "text": "yes" } },
+    ///         "answered_at": "2024-01-01T00:00:00Z"
+    ///     }
+    /// }"#;
+    /// let timestamp = chrono::Utc::now().timestamp().to_string();
+    ///
+    /// // As computed by the server
+    /// let mut mac = Hmac::<Sha256>::new_from_slice(b"whsec_test").unwrap();
+    /// mac.update(format!("{}.", timestamp).as_bytes());
+    /// mac.update(body);
+    /// let signature = format!("v1={}", hex::encode(mac.finalize().into_bytes()));
+    ///
+    /// let webhook = client.verify_webhook(body, &signature, &timestamp)?;
+    /// assert_eq!(webhook.confirmation_id.as_str(), "abc123");
+    /// assert_eq!(webhook.answer.as_free_text(), Some("yes"));
+    ///
+    /// // A tampered body, or a replay of an old request, is rejected
+    /// assert!(matches!(
+    ///     client.verify_webhook(b"{}", &signature, &timestamp),
+    ///     Err(WaitHumanError::InvalidWebhook(_))
+    /// ));
+    /// assert!(matches!(
+    ///     client.verify_webhook(body, &signature, "1700000000"),
+    ///     Err(WaitHumanError::InvalidWebhook(_))
+    /// ));
+    /// # Ok::<(), WaitHumanError>(())
+    /// ```
+    pub fn verify_webhook(
+        &self,
+        body: &[u8],
+        signature: &str,
+        timestamp: &str,
+    ) -> Result<WebhookAnswer> {
+        let secret =
+            self.webhook_secret
+                .as_deref()
+                .ok_or_else(|| WaitHumanError::InvalidConfig {
+                    field: "webhook_secret",
+                    message: "no webhook secret is configured".to_string(),
+                })?;
+        webhook::verify(secret, body, signature, timestamp)
+    }
+
+    /// Returns the underlying HTTP client, shared by every request of this client
+    ///
+    /// This is an advanced escape hatch: the client's configuration may change between
+    /// releases. Prefer [`raw_request`](Self::raw_request), which adds the headers the API
+    /// expects.
+    pub fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Starts an authorized request to an API endpoint the client doesn't wrap yet
+    ///
+    /// `path` is relative to the configured endpoint, e.g. `confirmations/export`, and
+    /// the request carries the same headers as the client's own: the API key, user agent
+    /// and API version. Sending it and handling the response is up to the caller, with
+    /// none of the client's retries, rate-limit handling, circuit breaker or request
+    /// signing.
+    ///
+    /// This is an advanced, unstable API meant to bridge the gap until the client
+    /// supports an endpoint. `reqwest` is re-exported so versions match.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wait_human::reqwest::Method;
+    /// use wait_human::WaitHuman;
+    ///
+    /// # async fn run(client: WaitHuman) -> Result<(), Box<dyn std::error::Error>> {
+    /// let response = client
+    ///     .raw_request(Method::GET, "confirmations/export")
+    ///     .query(&[("format", "csv")])
+    ///     .send()
+    ///     .await?;
+    /// println!("{}", response.text().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_request(&self, method: Method, path: &str) -> RequestBuilder {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.request(method, &self.api_url(&segments))
+    }
+
+    // Private helper methods
+
+    /// Returns the URL of an API path on the client's endpoint
+    fn api_url(&self, segments: &[&str]) -> Url {
+        join_url(&self.endpoint, segments, self.trailing_slash)
+    }
+
+    /// Sleeps for `duration` with the configured timer
+    async fn sleep(&self, duration: Duration) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(sleeper) = &self.sleeper {
+            return sleeper.sleep(duration).await;
+        }
+        sleep(duration).await
+    }
+
+    /// Runs `future` to completion, or returns `None` if `deadline` passes first
+    async fn timeout_at<F: Future>(&self, deadline: Instant, future: F) -> Option<F::Output> {
+        let timer = self.sleep(deadline.saturating_duration_since(Instant::now()));
+        race_timer(timer, future).await
+    }
+
+    /// Runs `future` out of `budget`, failing with a `Timeout` once the budget runs out
+    async fn within<F, T>(&self, budget: &Budget, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        match budget.deadline() {
+            Some(deadline) => self
+                .timeout_at(deadline, future)
+                .await
+                .unwrap_or_else(|| Err(budget.timeout_error())),
+            None => future.await,
+        }
+    }
+
+    /// Sleeps `wait` out of `budget` before a retry
+    ///
+    /// Returns a `Timeout` right away if the wait would run past the deadline, as the
+    /// retry couldn't complete in time anyway.
+    async fn backoff(&self, budget: &Budget, wait: Duration) -> Result<()> {
+        if !budget.fits(wait) {
+            return Err(budget.timeout_error());
+        }
+        self.sleep(wait).await;
+        Ok(())
+    }
+
+    /// Advances an `ask_stream` by one poll, creating the confirmation first if needed
+    async fn ask_stream_step(
+        &self,
+        state: AskStreamState,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<(AskStatus, AskStreamState)> {
+        let (confirmation_id, polls, mut seen, guard) = match state {
+            AskStreamState::Create(question) => {
+                validate_timeout(options)?;
+                let created = self.create_confirmation(question, options, start).await?;
+                let route = self.route_for(options)?;
+                let guard = options.cancel_on_drop.then(|| {
+                    Box::new(CancelGuard {
+                        client: self.clone(),
+                        route,
+                        confirmation_id: Some(created.id.clone()),
+                    })
+                });
+                (created.id, 0, Seen::default(), guard)
+            }
+            AskStreamState::Poll {
+                confirmation_id,
+                polls,
+                wait,
+                seen,
+                guard,
+            } => {
+                if let Some(wait) = wait {
+                    self.sleep(wait).await;
+                }
+                (confirmation_id, polls, seen, guard)
+            }
+            AskStreamState::Done => unreachable!("a finished ask_stream isn't polled"),
+        };
+
+        let polls = polls + 1;
+        let hold = long_poll_hold(options, options.deadline_from(start), self.max_poll_hold);
+        let sent = Instant::now();
+        let result = match self.poll_target(&confirmation_id, options) {
+            Ok(target) => self.poll_once(&target, options, start).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok((Some(answer), _)) => {
+                if let Some(guard) = guard {
+                    guard.disarm();
+                }
+                check_selection(&answer, options)?;
+                Ok((AskStatus::Answered(Box::new(answer)), AskStreamState::Done))
+            }
+            Ok((None, _))
+                if options
+                    .max_polls
+                    .is_some_and(|max_polls| polls >= max_polls) =>
+            {
+                Err(WaitHumanError::MaxPollsExceeded {
+                    polls,
+                    confirmation_id,
+                })
+            }
+            Ok((None, hints)) => {
+                let wait = next_poll_wait(
+                    options,
+                    self.poll_interval,
+                    self.max_poll_hold,
+                    options.deadline_from(start),
+                    hints.wait,
+                )
+                // A server ignoring the hold would otherwise be polled in a tight loop
+                .or_else(|| {
+                    returned_early(hold, sent, &hints)
+                        .then(|| poll_interval(options, self.poll_interval))
+                });
+                // One change is yielded per poll; the others are yielded by the next
+                let status = if let Some(to) = changed(&mut seen.assignee, hints.reassigned_to) {
+                    AskStatus::Reassigned { to }
+                } else if let Some(partial) =
+                    changed(&mut seen.partial_answer, hints.partial_answer)
+                {
+                    AskStatus::PartialAnswer(partial)
+                } else {
+                    AskStatus::Pending {
+                        elapsed: start.elapsed(),
+                        polls,
+                        queue_position: hints.queue_position,
+                        estimated_wait: hints.estimated_wait,
+                    }
+                };
+                Ok((
+                    status,
+                    AskStreamState::Poll {
+                        confirmation_id,
+                        polls,
+                        wait,
+                        seen,
+                        guard,
+                    },
+                ))
+            }
+            Err(e) => {
+                if let Some(guard) = guard {
+                    guard.disarm();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Asks a single-selection question and returns the selected index, checked to be in
+    /// range of `choices`
+    #[cfg(feature = "convenience")]
+    async fn ask_choice_index(
+        &self,
+        subject: String,
+        choices: Vec<String>,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> Result<u32> {
+        let (index, _) = self
+            .ask_choice_commented(subject, choices, body, options)
+            .await?;
+        Ok(index)
+    }
+
+    /// Like `ask_choice_index`, also returning the comment given with the answer, if not
+    /// blank
+    #[cfg(feature = "convenience")]
+    async fn ask_choice_commented(
+        &self,
+        subject: String,
+        choices: Vec<String>,
+        body: Option<String>,
+        options: Option<AskOptions>,
+    ) -> Result<(u32, Option<String>)> {
+        let question = ConfirmationQuestion {
+            method: QuestionMethod::Push,
+            subject,
+            body,
+            answer_format: AnswerFormat::Options {
+                options: choices,
+                multiple: false,
+            },
+        };
+        let answer_format = question.answer_format.clone();
+        let coerce = options.as_ref().is_some_and(|o| o.coerce_answers);
+
+        self.ask_typed(question, options, |answer| {
+            let comment = answer
+                .comment()
+                .map(str::trim)
+                .filter(|comment| !comment.is_empty())
+                .map(str::to_string);
+            let content = content_for(answer, &answer_format, coerce)?;
+
+            // A single in-range index, as checked by content_for
+            let index = single_selection(content.selected_indexes().unwrap_or_default())?;
+            Ok((index, comment))
+        })
+        .await
+    }
+
+    /// Asks `question` like [`ask`](Self::ask), then turns the answer into the result
+    /// of a typed ask with `typed`
+    ///
+    /// An `Abstained` error from `typed` gets the id of the confirmation asked.
+    #[cfg(feature = "convenience")]
+    async fn ask_typed<T>(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+        typed: impl FnOnce(ConfirmationAnswerWithDate) -> Result<T>,
+    ) -> Result<T> {
+        let created_id = OnceLock::new();
+        let (answer, _) = self
+            .ask_tracked(question, options.unwrap_or_default(), &created_id)
+            .await?;
+        typed(answer).map_err(|e| match e {
+            WaitHumanError::Abstained {
+                confirmation_id: None,
+            } => WaitHumanError::Abstained {
+                confirmation_id: created_id.into_inner(),
+            },
+            e => e,
+        })
+    }
+
+    /// Waits for the answer to a created confirmation, cancelling it if the wait is
+    /// dropped and `AskOptions::cancel_on_drop` is set
+    async fn wait_created(
+        &self,
+        created: CreatedConfirmation,
+        options: &AskOptions,
+        start: Instant,
+        in_flight: Option<&InFlightGuard>,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        let confirmation_id = created.id;
+
+        let route = self.route_for(options)?;
+        if let Some(in_flight) = in_flight {
+            in_flight.created(route.clone(), confirmation_id.clone());
+        }
+        let guard = options.cancel_on_drop.then(|| CancelGuard {
+            client: self.clone(),
+            route,
+            confirmation_id: Some(confirmation_id.clone()),
+        });
+
+        let poll = self.poll_for_answer(confirmation_id.clone(), options, start);
+        // Without a client timeout, stop waiting when the server expires the confirmation
+        let expires_at = created
+            .expires_at
+            .filter(|_| options.deadline_from(start).is_none());
+        let result = match expires_at {
+            Some(expires_at) => self
+                .timeout_at(instant_at(expires_at), poll)
+                .await
+                .unwrap_or(Err(WaitHumanError::Expired { confirmation_id })),
+            None => poll.await,
+        };
+        if let Some(guard) = guard {
+            guard.disarm();
+        }
+        result
+    }
+
+    /// Runs a wait for an answer under the deadline of `options`, then reports it to the
+    /// metrics and applies `AskOptions::default_on_timeout`
+    ///
+    /// A `Timeout` error is completed with `created_id`, if set by then, and `subject`.
+    async fn run_wait<F>(
+        &self,
+        wait: F,
+        options: &AskOptions,
+        start: Instant,
+        created_id: &OnceLock<ConfirmationId>,
+        subject: Option<String>,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)>
+    where
+        F: Future<Output = Result<(ConfirmationAnswerWithDate, AskStats)>>,
+    {
+        // Enforce the deadline on the whole wait, so a slow request or sleep can't overshoot it
+        let result = self.within(&Budget::new(options, start), wait).await;
+        let result = result.map_err(|e| match e {
+            WaitHumanError::Timeout {
+                elapsed_seconds, ..
+            } => WaitHumanError::Timeout {
+                elapsed_seconds,
+                confirmation_id: created_id.get().cloned(),
+                subject,
+            },
+            e => e,
+        });
+
+        if let Some(metrics) = &self.metrics {
+            match &result {
+                Ok((_, stats)) => metrics.confirmation_answered(stats),
+                Err(WaitHumanError::Timeout { .. }) => {
+                    metrics.confirmation_timed_out(start.elapsed())
+                }
+                Err(e) => metrics.ask_failed(e),
+            }
+        }
+
+        match (result, options.default_on_timeout.clone()) {
+            (Err(WaitHumanError::Timeout { .. }), Some(answer_content)) => {
+                let answer = ConfirmationAnswerWithDate {
+                    answer: ConfirmationAnswer {
+                        answer_content,
+                        attachments: Vec::new(),
+                        signature: None,
+                        confidence: None,
+                        comment: None,
+                    },
+                    answered_at: Utc::now(),
+                    metadata: options.metadata.clone(),
+                    answered_by: AnswerSource::Automation,
+                    locale: None,
+                    tags: options.tags.clone(),
+                    question: None,
+                    due_at: None,
+                    recipient: None,
+                    auto_answer_rule: None,
+                };
+                let stats = AskStats {
+                    total_wait: start.elapsed(),
+                    ..Default::default()
+                };
+                Ok((answer, stats))
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Returns the route of a call: `AskOptions::endpoint_override` and
+    /// `api_key_override` if set, otherwise the client's endpoint and API key
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidConfig` if the endpoint override isn't a valid base URL, or the
+    /// API key override is empty
+    fn route_for(&self, options: &AskOptions) -> Result<Route> {
+        let (endpoint, trailing_slash) = match &options.endpoint_override {
+            Some(endpoint) => {
+                let trailing_slash = self.preserve_endpoint && endpoint.ends_with('/');
+                let endpoint = base_url("endpoint_override", endpoint)?;
+                if self.require_https {
+                    check_https("endpoint_override", &endpoint)?;
+                }
+                (endpoint, trailing_slash)
+            }
+            None => (self.endpoint.clone(), self.trailing_slash),
+        };
+        if options
+            .api_key_override
+            .as_ref()
+            .is_some_and(|api_key| api_key.is_empty())
+        {
+            return Err(WaitHumanError::InvalidConfig {
+                field: "api_key_override",
+                message: "api_key_override must not be empty".to_string(),
+            });
+        }
+
+        Ok(Route {
+            endpoint,
+            trailing_slash,
+            api_key: options.api_key_override.clone(),
+        })
+    }
+
+    /// Returns the route of calls without options: the client's endpoint and API key
+    fn default_route(&self) -> Route {
+        Route {
+            endpoint: self.endpoint.clone(),
+            trailing_slash: self.trailing_slash,
+            api_key: None,
+        }
+    }
+
+    async fn create_confirmation(
+        &self,
+        question: ConfirmationQuestion,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<CreatedConfirmation> {
+        if options.dry_run {
+            return Err(WaitHumanError::InvalidConfig {
+                field: "dry_run",
+                message: "dry_run can't create a confirmation; use ask or validate".to_string(),
+            });
+        }
+
+        #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+        let stored_question = self.store.as_ref().map(|_| question.clone());
+
+        let response = self
+            .send_question(
+                &["confirmations", "create"],
+                question,
+                options,
+                options.idempotency_key.as_deref(),
+                start,
+            )
+            .await?;
+
+        let data: CreateConfirmationResponse = match response.status() {
+            StatusCode::OK | StatusCode::CREATED => read_json(response).await?,
+            StatusCode::ACCEPTED => read_accepted(response).await?,
+            status => {
+                return Err(WaitHumanError::CreateFailed {
+                    status: status.as_u16(),
+                    status_text: status.to_string(),
+                })
+            }
+        };
+        if let Some(metrics) = &self.metrics {
+            metrics.confirmation_created();
+        }
+
+        if options.require_channel && data.reachable_channels == Some(0) {
+            // Nobody will answer it; the cancel is best effort
+            let confirmation_id = data.confirmation_request_id;
+            let _ = self
+                .cancel_at(&self.route_for(options)?, &confirmation_id)
+                .await;
+            return Err(WaitHumanError::NoChannel { confirmation_id });
+        }
+
+        let created = CreatedConfirmation {
+            id: data.confirmation_request_id,
+            expires_at: data.expires_at,
+        };
+        #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+        if let Some(question) = stored_question {
+            if let Err(e) = self.remember(question, options, &created).await {
+                // Unsaved, it couldn't be resumed after a restart nor answered by
+                // anyone waiting; the cancel is best effort
+                let _ = self.cancel_at(&self.route_for(options)?, &created.id).await;
+                return Err(e);
+            }
+        }
+        Ok(created)
+    }
+
+    /// Saves a created confirmation to the store, if one is configured
+    #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+    async fn remember(
+        &self,
+        question: ConfirmationQuestion,
+        options: &AskOptions,
+        created: &CreatedConfirmation,
+    ) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        store
+            .save(StoredConfirmation {
+                id: created.id.clone(),
+                question,
+                metadata: options.metadata.clone(),
+                created_at: Utc::now(),
+                expires_at: created.expires_at,
+            })
+            .await
+    }
+
+    /// Takes the next answer queued with `with_answer_injection`, if any, once the
+    /// question and options passed the checks the server would have been asked after
+    ///
+    /// An invalid ask leaves the answer queued for the next one.
+    #[cfg(feature = "test-util")]
+    fn take_injected_answer(
+        &self,
+        question: &ConfirmationQuestion,
+        options: &AskOptions,
+    ) -> Result<Option<AnswerContent>> {
+        let mut answers = self
+            .injected_answers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if answers.is_empty() {
+            return Ok(None);
+        }
+        validate_ask(question, options)?;
+        check_attachment_sizes(&options.attachments)?;
+        Ok(answers.pop_front())
+    }
+
+    /// Removes a confirmation that no longer needs waiting on from the store, if one is
+    /// configured
+    ///
+    /// Best effort: a stale entry only makes a resumed wait end at its first poll.
+    #[cfg_attr(
+        not(all(feature = "store", not(target_arch = "wasm32"))),
+        allow(unused_variables)
+    )]
+    async fn forget(&self, confirmation_id: &ConfirmationId) {
+        #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+        if let Some(store) = &self.store {
+            let _ = store.remove(confirmation_id).await;
+        }
+    }
+
+    /// Replaces the question of a stored confirmation after an update, if a store is
+    /// configured
+    ///
+    /// Best effort: the stored question is informational.
+    #[cfg_attr(
+        not(all(feature = "store", not(target_arch = "wasm32"))),
+        allow(unused_variables)
+    )]
+    async fn remember_question(
+        &self,
+        confirmation_id: &ConfirmationId,
+        question: ConfirmationQuestion,
+    ) {
+        #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+        if let Some(store) = &self.store {
+            let Ok(stored) = store.load().await else {
+                return;
+            };
+            if let Some(mut stored) = stored.into_iter().find(|c| &c.id == confirmation_id) {
+                stored.question = question;
+                let _ = store.save(stored).await;
+            }
+        }
+    }
+
+    /// Has the server check a question like a create would, without creating anything
+    async fn validate_confirmation(
+        &self,
+        question: ConfirmationQuestion,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<()> {
+        let response = self
+            .send_question(
+                &["confirmations", "validate"],
+                question,
+                options,
+                None,
+                start,
+            )
+            .await?;
+
+        match response.status() {
+            // Older servers don't offer validation; the client-side checks passed
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            status if status.is_success() => Ok(()),
+            status => Err(WaitHumanError::CreateFailed {
+                status: status.as_u16(),
+                status_text: status.to_string(),
+            }),
+        }
+    }
+
+    /// Validates a question and posts it with its options to the endpoint at `segments`,
+    /// retrying while rate limited
+    async fn send_question(
+        &self,
+        segments: &[&str],
+        question: ConfirmationQuestion,
+        options: &AskOptions,
+        idempotency_key: Option<&str>,
+        start: Instant,
+    ) -> Result<Response> {
+        validate_ask(&question, options)?;
+
+        let route = self.route_for(options)?;
+        let url = route.url(segments);
+
+        // Reject oversized uploads before sending anything
+        check_attachment_sizes(&options.attachments)?;
+
+        let max_binary_bytes = matches!(question.answer_format, AnswerFormat::Binary { .. })
+            .then(|| options.binary_limit());
+        let request_body = CreateConfirmationRequest {
+            question,
+            metadata: options.metadata.clone(),
+            attachments: options.attachments.clone(),
+            min_length: options.min_length,
+            max_length: options.max_length,
+            body_format: options.body_format,
+            locale: options.locale.clone(),
+            priority: options.priority,
+            recipient: options.recipient.clone(),
+            rules: (!options.auto_answer_rules.is_empty())
+                .then(|| options.auto_answer_rules.clone()),
+            require_signature: options.require_signature,
+            tags: options.tags.clone(),
+            parent_id: options.parent_id.clone(),
+            allow_abstain: options.allow_abstain,
+            required_approvals: options.required_approvals,
+            disabled_options: options.disabled_choices.clone(),
+            option_help: options.choice_help.iter().cloned().collect(),
+            allow_comment: options.allow_comment || !options.require_comment_on.is_empty(),
+            allow_other: options.allow_other,
+            require_comment_on: options.require_comment_on.clone(),
+            max_binary_bytes,
+            due_at: server_due_in(options, start).map(due_at),
+        };
+
+        let mut maintenance_retries = 0;
+        let response = loop {
+            let mut request = self.request_on(&route, Method::POST, &url);
+            if let Some(key) = idempotency_key {
+                request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+            }
+
+            let response = self
+                .send_guarded(request.json_body(&request_body, self))
+                .await?;
+
+            let budget = Budget::new(options, start);
+            match response.status() {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    self.wait_for_rate_limit(&response, &budget).await?
+                }
+                StatusCode::SERVICE_UNAVAILABLE => {
+                    self.wait_for_maintenance(response, &budget, &mut maintenance_retries)
+                        .await?
+                }
+                _ => break response,
+            }
+        };
+
+        check_response(response).await
+    }
+
+    /// Waits for the answer to a confirmation
+    ///
+    /// Concurrent waits on the same confirmation (e.g. asks sharing an idempotency key)
+    /// share a single poll loop, driven by the options of the wait that started it.
+    /// Dropping one waiter leaves the loop running for the others.
+    async fn poll_for_answer(
+        &self,
+        confirmation_id: ConfirmationId,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        loop {
+            let (poll, started) = self.polls.join(confirmation_id.clone(), || {
+                let client = self.clone();
+                let confirmation_id = confirmation_id.clone();
+                let options = options.clone();
+                let poll: BoxFuture<_> =
+                    Box::pin(
+                        async move { client.poll_loop(confirmation_id, &options, start).await },
+                    );
+                poll
+            });
+
+            let result = poll.clone().await;
+            self.polls.finish(&confirmation_id, &poll);
+
+            match result {
+                // The loop ran out of another waiter's time or poll budget, not this one's
+                Err(WaitHumanError::Timeout { .. } | WaitHumanError::MaxPollsExceeded { .. })
+                    if !started =>
+                {
+                    continue
+                }
+                Ok((answer, stats)) => {
+                    let stats = AskStats {
+                        total_wait: start.elapsed(),
+                        ..stats
+                    };
+                    return Ok((answer, stats));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn poll_loop(
+        &self,
+        confirmation_id: ConfirmationId,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<(ConfirmationAnswerWithDate, AskStats)> {
+        let mut stats = AskStats::default();
+        let mut nudged = false;
+        // Adaptive asks start out long polling, and switch to short polls for the rest
+        // of the call if the server turns out not to support it
+        let mut options = Cow::Borrowed(options);
+        if options.adaptive_long_poll {
+            options.to_mut().long_poll = true;
+        }
+        let mut early_returns = 0;
+        let mut seen = Seen::default();
+        let target = self.poll_target(&confirmation_id, &options)?;
+
+        loop {
+            let hold = long_poll_hold(&options, options.deadline_from(start), self.max_poll_hold);
+            let sent = Instant::now();
+            let (maybe_answer, hints) = match self.poll_once(&target, &options, start).await {
+                // The server rejects the long-poll parameters
+                Err(e)
+                    if hold.is_some()
+                        && options.adaptive_long_poll
+                        && matches!(e.status(), Some(400 | 501)) =>
+                {
+                    fall_back_to_short_polls(options.to_mut(), &confirmation_id);
+                    continue;
+                }
+                result => result?,
+            };
+
+            stats.poll_count += 1;
+            if stats.poll_count == 1 {
+                stats.time_to_first_response = start.elapsed();
+            }
+
+            if let Some(answer) = maybe_answer {
+                stats.total_wait = start.elapsed();
+                return Ok((answer, stats));
+            }
+
+            // A server holding polls open only returns pending once the hold is over, or
+            // early to relay a search
+            let returned_early = returned_early(hold, sent, &hints);
+            if hold.is_some() && options.adaptive_long_poll {
+                if returned_early {
+                    early_returns += 1;
+                    if early_returns >= LONG_POLL_EARLY_RETURNS {
+                        fall_back_to_short_polls(options.to_mut(), &confirmation_id);
+                    }
+                } else if !hints.searched {
+                    early_returns = 0;
+                }
+            }
+
+            if let Some(to) = changed(&mut seen.assignee, hints.reassigned_to) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%confirmation_id, %to, "confirmation reassigned");
+                if let Some(on_reassign) = &options.on_reassign {
+                    on_reassign.call(&to);
+                }
+            }
+
+            if let Some(partial) = changed(&mut seen.partial_answer, hints.partial_answer) {
+                if let Some(on_partial_answer) = &options.on_partial_answer {
+                    on_partial_answer.call(&partial);
+                }
+            }
+
+            if let Some(on_poll) = &options.on_poll {
+                on_poll.call(PollProgress {
+                    elapsed: start.elapsed(),
+                    attempt: stats.poll_count,
+                    approvals: hints.approvals,
+                    queue_position: hints.queue_position,
+                    estimated_wait: hints.estimated_wait,
+                });
+            }
+
+            if options
+                .max_polls
+                .is_some_and(|max_polls| stats.poll_count >= max_polls)
+            {
+                return Err(WaitHumanError::MaxPollsExceeded {
+                    polls: stats.poll_count,
+                    confirmation_id,
+                });
+            }
+
+            if let Some(nudge_after) = options.nudge_after {
+                if !nudged && start.elapsed() >= nudge_after {
+                    nudged = true;
+                    // The nudge is best effort; a failure shouldn't end the wait
+                    let _ = self.nudge_at(&target.route, &confirmation_id).await;
+                }
+            }
+
+            let deadline = options.deadline_from(start);
+            let wait = next_poll_wait(
+                &options,
+                self.poll_interval,
+                self.max_poll_hold,
+                deadline,
+                hints.wait,
+            )
+            // A server ignoring the hold would otherwise be polled in a tight loop
+            .or_else(|| returned_early.then(|| poll_interval(&options, self.poll_interval)));
+            if let Some(wait) = wait {
+                self.sleep(wait).await;
+            }
+        }
+    }
+
+    /// Fetches the confirmation once, returning its answer or `None` while still pending
+    ///
+    /// Terminal states without an answer (expired, declined, cancelled) are returned as errors.
+    async fn fetch_answer(
+        &self,
+        confirmation_id: &ConfirmationId,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<Option<ConfirmationAnswerWithDate>> {
+        let target = self.poll_target(confirmation_id, options)?;
+        let (answer, _) = self.poll_once(&target, options, start).await?;
+        Ok(answer)
+    }
+
+    /// Like `fetch_answer`, also returning the hints the server gave alongside
+    async fn poll_once(
+        &self,
+        target: &PollTarget,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<(Option<ConfirmationAnswerWithDate>, PollHints)> {
+        let (status, hints) = self.poll_status(target, options, start).await?;
+        // Terminal states without an answer end the wait right away
+        Ok((status.into_answer(&target.confirmation_id)?, hints))
+    }
+
+    /// Resolves where the polls of `confirmation_id` go under `options`
+    fn poll_target(
+        &self,
+        confirmation_id: &ConfirmationId,
+        options: &AskOptions,
+    ) -> Result<PollTarget> {
+        Ok(PollTarget::new(
+            self.route_for(options)?,
+            confirmation_id.clone(),
+            options.include_question,
+        ))
+    }
+
+    /// Fetches the confirmation once, returning its status and the hints the server
+    /// gave alongside
+    ///
+    /// The wait before the next poll comes from a `poll_interval_ms` field in the body,
+    /// or else from a `Retry-After` header, and is at least [`MIN_SERVER_POLL_WAIT`]. A
+    /// `204 No Content` means still pending.
+    async fn poll_status(
+        &self,
+        target: &PollTarget,
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<(ConfirmationStatus, PollHints)> {
+        let PollTarget {
+            confirmation_id,
+            route,
+            ..
+        } = target;
+
+        let mut dropped_holds = 0;
+        let mut maintenance_retries = 0;
+        let mut garbled_polls = 0;
+        let (mut data, retry_after): (GetConfirmationResponse, _) = loop {
+            let response = loop {
+                let hold =
+                    long_poll_hold(options, options.deadline_from(start), self.max_poll_hold);
+                let request = self.request_on(route, Method::GET, &target.url(hold));
+                let response = match self.send_guarded(request).await {
+                    // An intermediary dropped the connection while the server held it,
+                    // e.g. a load balancer closing it as idle. The confirmation is
+                    // unaffected, so the poll is simply sent again on a new connection
+                    Err(e) if hold.is_some() && is_dropped_connection(&e) => {
+                        dropped_holds += 1;
+                        if dropped_holds > MAX_DROPPED_HOLDS {
+                            return Err(e);
+                        }
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(%confirmation_id, error = %e, "long poll dropped, reconnecting");
+                        continue;
+                    }
+                    result => result?,
+                };
+
+                let budget = Budget::new(options, start);
+                match response.status() {
+                    StatusCode::TOO_MANY_REQUESTS => {
+                        self.wait_for_rate_limit(&response, &budget).await?
+                    }
+                    StatusCode::SERVICE_UNAVAILABLE => {
+                        self.wait_for_maintenance(response, &budget, &mut maintenance_retries)
+                            .await?
+                    }
+                    _ => break response,
+                }
+            };
+
+            let response = check_response(response).await?;
+
+            let retry_after = retry_after(&response);
+            let data = match response.status() {
+                StatusCode::OK => {
+                    let path = response.url().path().to_string();
+                    let status = response.status();
+                    let body = response_text(response).await?;
+                    match serde_json::from_str(&body) {
+                        Ok(data) => data,
+                        // A truncated or garbled body, e.g. from a proxy hiccup, is sent
+                        // again rather than ending the wait. Well-formed JSON of the wrong
+                        // shape won't get better, so fails right away
+                        Err(e)
+                            if (e.is_syntax() || e.is_eof())
+                                && garbled_polls < MAX_GARBLED_POLLS
+                                && Budget::new(options, start).remaining()
+                                    != Some(Duration::ZERO) =>
+                        {
+                            garbled_polls += 1;
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                %confirmation_id,
+                                error = %e,
+                                body = %json_snippet(&body),
+                                "garbled poll response, polling again"
+                            );
+                            continue;
+                        }
+                        Err(e) => return Err(json_error(&path, status, &body, e)),
+                    }
+                }
+                // Some gateways answer a poll of a pending confirmation with no content
+                StatusCode::NO_CONTENT => GetConfirmationResponse::default(),
+                status => {
+                    return Err(WaitHumanError::PollFailed {
+                        status: status.as_u16(),
+                        status_text: status.to_string(),
+                    })
+                }
+            };
+            break (data, retry_after);
+        };
+
+        if let Some(metrics) = &self.metrics {
+            metrics.poll_completed();
+        }
+
+        let searches = std::mem::take(&mut data.search_queries);
+        let searched = match &options.choice_provider {
+            Some(provider) if !searches.is_empty() => {
+                self.answer_searches(route, confirmation_id, provider.as_ref(), &searches)
+                    .await;
+                true
+            }
+            _ => false,
+        };
+
+        let hints = PollHints {
+            // The human is waiting on the results, so the next poll goes out right away
+            wait: if searched {
+                Some(Duration::ZERO)
+            } else {
+                data.poll_interval_ms
+                    .map(Duration::from_millis)
+                    .or(retry_after)
+                    .map(|wait| wait.max(MIN_SERVER_POLL_WAIT))
+            },
+            approvals: data.approvals,
+            queue_position: data.queue_position,
+            estimated_wait: data.estimated_wait_ms.map(Duration::from_millis),
+            searched,
+            reassigned_to: data.reassigned_to.take(),
+            partial_answer: data.partial_answer.take(),
+        };
+        let status = data.into_status();
+        if status.is_final() {
+            self.forget(confirmation_id).await;
+        }
+        Ok((status, hints))
+    }
+
+    /// Sends the choices matching each search the human made back to the server
+    ///
+    /// Best effort: a search whose results are lost is simply made again by the human.
+    async fn answer_searches(
+        &self,
+        route: &Route,
+        confirmation_id: &ConfirmationId,
+        provider: &dyn ChoiceProvider,
+        searches: &[SearchQuery],
+    ) {
+        let url = route.url(&["confirmations", "search", confirmation_id.as_str()]);
+
+        for search in searches {
+            let (choices, error) = match provider.search(&search.query).await {
+                Ok(choices) => (choices, None),
+                Err(message) => (Vec::new(), Some(message)),
+            };
+            let results = SearchResults {
+                query_id: &search.id,
+                choices,
+                error,
+            };
+
+            let request = self
+                .request_on(route, Method::POST, &url)
+                .json_body(&results, self);
+            if let Err(_e) = self.send(request).await {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%confirmation_id, error = %_e, "failed to send search results");
+            }
+        }
+    }
+
+    /// Fetches several confirmations at once, returning their answers in the order of
+    /// `confirmation_ids`
+    ///
+    /// Uses the bulk endpoint, falling back to one request per confirmation if the server
+    /// doesn't offer it.
+    async fn fetch_answers(
+        &self,
+        confirmation_ids: &[ConfirmationId],
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<Vec<Option<ConfirmationAnswerWithDate>>> {
+        self.fetch_answer_states(confirmation_ids, options, start)
+            .await?
+            .into_iter()
+            .collect()
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", &self.api_key)
-            .json(&request_body)
-            .send()
-            .await?;
+    /// Like `fetch_answers`, but returns the outcome of each confirmation separately, so
+    /// one that ended without an answer doesn't hide the answers of the others
+    async fn fetch_answer_states(
+        &self,
+        confirmation_ids: &[ConfirmationId],
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<Vec<Result<Option<ConfirmationAnswerWithDate>>>> {
+        if confirmation_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !self.bulk_unsupported.load(Ordering::Relaxed) {
+            match self
+                .fetch_answers_bulk(confirmation_ids, options, start)
+                .await
+            {
+                Err(e) if matches!(e.status(), Some(404 | 405)) => {
+                    // Older servers: don't try the bulk endpoint again
+                    self.bulk_unsupported.store(true, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+
+        Ok(join_all(
+            confirmation_ids
+                .iter()
+                .map(|confirmation_id| self.fetch_answer(confirmation_id, options, start)),
+        )
+        .await)
+    }
+
+    async fn fetch_answers_bulk(
+        &self,
+        confirmation_ids: &[ConfirmationId],
+        options: &AskOptions,
+        start: Instant,
+    ) -> Result<Vec<Result<Option<ConfirmationAnswerWithDate>>>> {
+        let route = self.route_for(options)?;
+        let url = route.url(&["confirmations", "get-many"]);
+        let request_body = GetConfirmationsRequest {
+            ids: confirmation_ids,
+        };
+
+        let mut maintenance_retries = 0;
+        let response = loop {
+            let request = self
+                .request_on(&route, Method::POST, &url)
+                .json_body(&request_body, self);
+            let response = self.send_guarded(request).await?;
+
+            let budget = Budget::new(options, start);
+            match response.status() {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    self.wait_for_rate_limit(&response, &budget).await?
+                }
+                StatusCode::SERVICE_UNAVAILABLE => {
+                    self.wait_for_maintenance(response, &budget, &mut maintenance_retries)
+                        .await?
+                }
+                _ => break response,
+            }
+        };
+
+        let response = check_response(response).await?;
 
         if !response.status().is_success() {
-            return Err(WaitHumanError::CreateFailed {
+            return Err(WaitHumanError::PollFailed {
+                status: response.status().as_u16(),
                 status_text: response.status().to_string(),
             });
         }
 
-        let data: CreateConfirmationResponse = response.json().await?;
-        Ok(data.confirmation_request_id)
+        let data: GetConfirmationsResponse = read_json(response).await?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.poll_completed();
+        }
+
+        let mut confirmations: HashMap<_, _> = data
+            .confirmations
+            .into_iter()
+            .map(|bulk| (bulk.id, bulk.confirmation))
+            .collect();
+
+        let mut states = Vec::with_capacity(confirmation_ids.len());
+        for confirmation_id in confirmation_ids {
+            let Some(confirmation) = confirmations.remove(confirmation_id) else {
+                states.push(Err(WaitHumanError::InvalidResponse(format!(
+                    "confirmation {} missing from bulk response",
+                    confirmation_id
+                ))));
+                continue;
+            };
+            let status = confirmation.into_status();
+            if status.is_final() {
+                self.forget(confirmation_id).await;
+            }
+            states.push(status.into_answer(confirmation_id));
+        }
+        Ok(states)
+    }
+
+    /// Starts a request to the API with the headers every request carries
+    fn request(&self, method: Method, url: &Url) -> RequestBuilder {
+        self.request_with_key(method, url, self.api_key.expose())
     }
 
-    async fn poll_for_answer(
+    /// Starts a request of a call, authenticated with the API key of its route
+    fn request_on(&self, route: &Route, method: Method, url: &Url) -> RequestBuilder {
+        let api_key = route.api_key.as_deref().unwrap_or(self.api_key.expose());
+        self.request_with_key(method, url, api_key)
+    }
+
+    fn request_with_key(&self, method: Method, url: &Url, api_key: &str) -> RequestBuilder {
+        let request = self.client.request(method, url.clone());
+        let request = match HeaderValue::from_str(api_key) {
+            // Sensitive values are left out of the request's `Debug` output
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                request.header(AUTHORIZATION, value)
+            }
+            // Fails the request with the builder's own error
+            Err(_) => request.header(AUTHORIZATION, api_key),
+        };
+        request
+            .header(USER_AGENT, &self.user_agent)
+            .header(API_VERSION_HEADER, &self.api_version)
+    }
+
+    /// Sends a create or poll request through the circuit breaker, if configured
+    ///
+    /// Network errors and 5xx responses count as failures; rate limiting doesn't.
+    async fn send_guarded(&self, request: RequestBuilder) -> Result<Response> {
+        let Some(breaker) = &self.circuit_breaker else {
+            return self.send(request).await;
+        };
+
+        breaker.check()?;
+        let result = self.send(request).await;
+        breaker.record(matches!(&result, Ok(response) if !response.status().is_server_error()));
+        result
+    }
+
+    /// Sends a request, failing over to the fallback endpoints if any are configured
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        match &self.endpoints {
+            Some(endpoints) => self.send_with_failover(&client, request, endpoints).await,
+            None => self.execute(&client, request).await,
+        }
+    }
+
+    /// Sends a request to each endpoint in turn, until one can be connected to and, with
+    /// replicated backends, answers without a 5xx other than 503
+    ///
+    /// The last endpoint's answer is returned as is, whatever it is. Requests with a
+    /// streamed body, which can only be sent once, and requests elsewhere than on the
+    /// client's endpoint don't fail over.
+    async fn send_with_failover(
         &self,
-        confirmation_id: String,
-        timeout_seconds: Option<u64>,
-    ) -> Result<ConfirmationAnswerWithDate> {
-        let start = Instant::now();
+        client: &Client,
+        request: Request,
+        endpoints: &Endpoints,
+    ) -> Result<Response> {
+        let mut order = endpoints.order().into_iter().peekable();
+        while let Some(index) = order.next() {
+            let Some(url) = endpoints.rebase(request.url(), index) else {
+                return self.execute(client, request).await;
+            };
+            let retry = match order.peek() {
+                Some(_) => request.try_clone(),
+                None => None,
+            };
+            let Some(mut attempt) = retry else {
+                let mut request = request;
+                *request.url_mut() = url;
+                let result = self.execute(client, request).await;
+                endpoints.record(index, !is_endpoint_failure(&result, endpoints));
+                return result;
+            };
 
-        loop {
-            let elapsed_seconds = start.elapsed().as_secs_f64();
+            *attempt.url_mut() = url;
+            let result = self.execute(client, attempt).await;
+            let failed = is_endpoint_failure(&result, endpoints);
+            endpoints.record(index, !failed);
+            if !failed {
+                return result;
+            }
+            #[cfg(feature = "tracing")]
+            tracing::warn!(endpoint = %endpoints.url(index), "endpoint failing, trying the next one");
+        }
+        unreachable!("the last endpoint's answer is returned")
+    }
 
-            if let Some(timeout) = timeout_seconds {
-                if elapsed_seconds > timeout as f64 {
-                    return Err(WaitHumanError::Timeout { elapsed_seconds });
-                }
+    /// Sends a request, signing it first if a request signing secret is configured
+    ///
+    /// With the `debug-http` feature, the request and the head of its response are
+    /// logged here; response bodies are logged as they are read.
+    async fn execute(&self, client: &Client, mut request: Request) -> Result<Response> {
+        self.sign(&mut request)?;
+
+        #[cfg(feature = "debug-http")]
+        debug_http::log_request(&request);
+        let response = client.execute(request).await?;
+        #[cfg(feature = "debug-http")]
+        debug_http::log_response(&response);
+        Ok(response)
+    }
+
+    /// Adds the signature headers to a request, if a signing secret is set
+    fn sign(&self, request: &mut Request) -> Result<()> {
+        let Some(secret) = &self.request_signing_secret else {
+            return Ok(());
+        };
+
+        let timestamp = Utc::now().timestamp();
+        let signature = {
+            let body = match request.body() {
+                Some(body) => body.as_bytes().ok_or_else(|| {
+                    WaitHumanError::InvalidConfig {
+                        field: "request_signing_secret",
+                        message: "streamed request bodies, such as answers with attachments, can't be signed"
+                            .to_string(),
+                    }
+                })?,
+                None => &[],
+            };
+            let url = request.url();
+            let path_and_query = match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            };
+            sign_request(
+                secret,
+                timestamp,
+                request.method().as_str(),
+                &path_and_query,
+                body,
+            )
+        };
+
+        let headers = request.headers_mut();
+        headers.insert(REQUEST_TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+        // Hex digits are always a valid header value
+        if let Ok(signature) = HeaderValue::from_str(&signature) {
+            headers.insert(REQUEST_SIGNATURE_HEADER, signature);
+        }
+        Ok(())
+    }
+
+    /// Sleeps before retrying a rate-limited request, honoring the `Retry-After` header
+    ///
+    /// Falls back to the configured backoff when the header is absent.
+    async fn wait_for_rate_limit(&self, response: &Response, budget: &Budget) -> Result<()> {
+        let wait = retry_after(response).unwrap_or(self.rate_limit_backoff);
+        self.backoff(budget, wait).await
+    }
+
+    /// Waits out the maintenance reported by a 503 response before a retry
+    ///
+    /// Returns the `ServiceUnavailable` error instead when the server didn't say when it
+    /// will be back, or that is past the deadline of `budget`. Without a deadline, the
+    /// request gives up after `MAX_MAINTENANCE_RETRIES` waits, counted in `retries`.
+    async fn wait_for_maintenance(
+        &self,
+        response: Response,
+        budget: &Budget,
+        retries: &mut u32,
+    ) -> Result<()> {
+        let error = service_unavailable(response).await;
+        let exhausted = budget.remaining().is_none() && *retries >= MAX_MAINTENANCE_RETRIES;
+        match error {
+            WaitHumanError::ServiceUnavailable {
+                retry_after: Some(wait),
+                ..
+            } if !exhausted && budget.fits(wait.max(MIN_MAINTENANCE_WAIT)) => {
+                let wait = wait.max(MIN_MAINTENANCE_WAIT);
+                *retries += 1;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?wait, "server under maintenance, retrying");
+                self.sleep(wait).await;
+                Ok(())
             }
+            error => Err(error),
+        }
+    }
+}
 
-            let url = format!(
-                "{}/confirmations/get/{}?long_poll=false",
-                self.endpoint, confirmation_id
+impl fmt::Debug for WaitHuman {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("WaitHuman");
+        debug
+            .field("api_key", &self.api_key)
+            .field("endpoint", &self.endpoint.as_str())
+            .field("preserve_endpoint", &self.preserve_endpoint)
+            .field("endpoints", &self.endpoints)
+            .field("app_url", &self.app_url.as_str())
+            .field("rate_limit_backoff", &self.rate_limit_backoff)
+            .field("poll_interval", &self.poll_interval)
+            .field("max_poll_hold", &self.max_poll_hold)
+            .field("user_agent", &self.user_agent)
+            .field("api_version", &self.api_version)
+            .field("require_https", &self.require_https)
+            .field("metrics", &self.metrics.is_some())
+            .field("circuit_breaker", &self.circuit_breaker)
+            .field("in_flight", &self.in_flight())
+            .field(
+                "webhook_secret",
+                &self.webhook_secret.as_ref().map(|_| "***"),
+            )
+            .field(
+                "request_signing_secret",
+                &self.request_signing_secret.as_ref().map(|_| "***"),
             );
+        #[cfg(not(target_arch = "wasm32"))]
+        debug.field("sleeper", &self.sleeper.is_some());
+        #[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+        debug.field("store", &self.store.is_some());
+        #[cfg(feature = "compression")]
+        debug.field("compression", &self.compression);
+        #[cfg(feature = "test-util")]
+        debug.field(
+            "injected_answers",
+            &self
+                .injected_answers
+                .lock()
+                .map_or(0, |answers| answers.len()),
+        );
+        debug.field("client", &self.client).finish()
+    }
+}
 
-            let response = self
-                .client
-                .get(&url)
-                .header("Authorization", &self.api_key)
-                .send()
-                .await?;
+/// Progress of an `ask_stream` between items
+enum AskStreamState {
+    /// The confirmation is yet to be created
+    Create(ConfirmationQuestion),
+    /// The confirmation is waiting for an answer
+    Poll {
+        confirmation_id: ConfirmationId,
+        polls: u32,
+        /// Wait before the next poll
+        wait: Option<Duration>,
+        /// What the polls reported so far
+        seen: Seen,
+        /// Cancels the confirmation if the stream is dropped, with `cancel_on_drop`
+        guard: Option<Box<CancelGuard>>,
+    },
+    /// The stream ended
+    Done,
+}
 
-            if !response.status().is_success() {
-                return Err(WaitHumanError::PollFailed {
-                    status_text: response.status().to_string(),
-                });
+impl AskStreamState {
+    fn confirmation_id(&self) -> Option<ConfirmationId> {
+        match self {
+            AskStreamState::Poll {
+                confirmation_id, ..
+            } => Some(confirmation_id.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// What the server said alongside a confirmation's status, besides the status itself
+#[derive(Debug, Clone, Default)]
+struct PollHints {
+    /// How long the server asked to wait before polling again, if it did
+    wait: Option<Duration>,
+    /// Approvals collected so far, for confirmations requiring several
+    approvals: Option<ApprovalProgress>,
+    /// Position of the confirmation among those waiting for the same human
+    queue_position: Option<u32>,
+    /// How long the server expects the answer to take
+    estimated_wait: Option<Duration>,
+    /// Whether the poll relayed searches of the human, which were answered
+    searched: bool,
+    /// The human the confirmation was handed over to, once it was reassigned
+    reassigned_to: Option<String>,
+    /// The answer so far, while it is being given
+    partial_answer: Option<PartialAnswer>,
+}
+
+/// What the polls of a confirmation reported so far, to tell what the next one changed
+#[derive(Debug, Default)]
+struct Seen {
+    /// The human the confirmation was last handed over to
+    assignee: Option<String>,
+    /// The answer so far, as last reported
+    partial_answer: Option<PartialAnswer>,
+}
+
+/// Returns what a poll reported when it differs from what the polls before it did, and
+/// remembers it as the latest
+fn changed<T: PartialEq + Clone>(seen: &mut Option<T>, reported: Option<T>) -> Option<T> {
+    let reported = reported.filter(|reported| seen.as_ref() != Some(reported))?;
+    *seen = Some(reported.clone());
+    Some(reported)
+}
+
+/// Where the requests of a call go, and with which API key
+///
+/// Carried by everything that outlives the call's options, such as the guards that
+/// cancel confirmations, so follow-up requests reach the same server as the same
+/// tenant.
+#[derive(Clone)]
+pub(crate) struct Route {
+    endpoint: Url,
+    /// Whether API paths end with a slash, as the endpoint does
+    trailing_slash: bool,
+    /// Replaces the client's API key, from `AskOptions::api_key_override`
+    api_key: Option<String>,
+}
+
+impl Route {
+    fn url(&self, segments: &[&str]) -> Url {
+        join_url(&self.endpoint, segments, self.trailing_slash)
+    }
+}
+
+/// Where the polls of one confirmation go, resolved once per wait
+///
+/// Polls are sent in a tight loop while waiting, so the route and URLs are built up
+/// front rather than on every poll. Short polls reuse the same URL; long polls only add
+/// the hold to a copy of it.
+pub(crate) struct PollTarget {
+    confirmation_id: ConfirmationId,
+    route: Route,
+    /// The confirmation's URL, without query
+    url: Url,
+    /// The URL of short polls, query included
+    short_poll_url: Url,
+    include_question: bool,
+}
+
+impl PollTarget {
+    fn new(route: Route, confirmation_id: ConfirmationId, include_question: bool) -> Self {
+        let url = route.url(&["confirmations", "get", confirmation_id.as_str()]);
+        let mut short_poll_url = url.clone();
+        {
+            let mut query = short_poll_url.query_pairs_mut();
+            query.append_pair("long_poll", "false");
+            if include_question {
+                query.append_pair("include_question", "true");
+            }
+        }
+        Self {
+            confirmation_id,
+            route,
+            url,
+            short_poll_url,
+            include_question,
+        }
+    }
+
+    /// The URL of a poll the server holds open for `hold`, or of a short poll without
+    fn url(&self, hold: Option<Duration>) -> Cow<'_, Url> {
+        let Some(hold) = hold else {
+            return Cow::Borrowed(&self.short_poll_url);
+        };
+        let mut url = self.url.clone();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("long_poll", "true")
+                .append_pair("long_poll_timeout", &hold.as_secs().to_string());
+            if self.include_question {
+                query.append_pair("include_question", "true");
+            }
+        }
+        Cow::Owned(url)
+    }
+}
+
+impl fmt::Debug for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route")
+            .field("endpoint", &self.endpoint)
+            .field("trailing_slash", &self.trailing_slash)
+            .field("api_key", &self.api_key.as_deref().map(redact_api_key))
+            .finish()
+    }
+}
+
+/// Cancels a pending confirmation in the background if dropped while still armed
+///
+/// Armed while an ask with `AskOptions::cancel_on_drop` waits for its answer, so that
+/// dropping the ask future (or timing out) doesn't leave the confirmation orphaned.
+struct CancelGuard {
+    client: WaitHuman,
+    route: Route,
+    confirmation_id: Option<ConfirmationId>,
+}
+
+impl CancelGuard {
+    /// Disarms the guard once the wait ended on its own
+    fn disarm(mut self) {
+        self.confirmation_id = None;
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if let Some(confirmation_id) = self.confirmation_id.take() {
+            let client = self.client.clone();
+            let route = self.route.clone();
+
+            // Best effort: nobody is left to report a failure to
+            spawn(async move {
+                let _ = client.cancel_at(&route, &confirmation_id).await;
+            });
+        }
+    }
+}
+
+/// Cancels the confirmation of an `ask_scoped` in the background if dropped while
+/// still armed
+///
+/// Unlike [`CancelGuard`], it is armed before the confirmation is created, and picks
+/// up its id once known.
+struct ScopeGuard<'a> {
+    client: WaitHuman,
+    route: Route,
+    created_id: &'a OnceLock<ConfirmationId>,
+    armed: bool,
+}
+
+impl ScopeGuard<'_> {
+    /// Disarms the guard once the ask cleaned up after itself
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(confirmation_id) = self.created_id.get().filter(|_| self.armed) {
+            let client = self.client.clone();
+            let route = self.route.clone();
+            let confirmation_id = confirmation_id.clone();
+
+            // Best effort: nobody is left to report a failure to
+            spawn(async move {
+                let _ = client.cancel_at(&route, &confirmation_id).await;
+            });
+        }
+    }
+}
+
+/// Builds the multipart form of an answer with attachments: the answer as JSON in the
+/// `answer` field, inline files in `files` fields and URLs in `attachment_urls` fields
+fn answer_form(answer: &ConfirmationAnswer, attachments: Vec<Attachment>) -> Result<Form> {
+    let json = serde_json::to_string(answer).map_err(|e| {
+        WaitHumanError::InvalidAnswer(format!("answer could not be serialized: {}", e))
+    })?;
+    let mut form = Form::new().text("answer", json);
+
+    for attachment in attachments {
+        form = match attachment {
+            Attachment::Url { url, .. } => form.text("attachment_urls", url),
+            Attachment::Inline {
+                name,
+                content_type,
+                data,
+            } => {
+                let bytes = BASE64_STANDARD.decode(data).map_err(|e| {
+                    WaitHumanError::InvalidAnswer(format!(
+                        "attachment {} is not valid base64: {}",
+                        name, e
+                    ))
+                })?;
+                let part = Part::bytes(bytes)
+                    .file_name(name.clone())
+                    .mime_str(&content_type)
+                    .map_err(|_| {
+                        WaitHumanError::InvalidAnswer(format!(
+                            "attachment {} has an invalid content type {:?}",
+                            name, content_type
+                        ))
+                    })?;
+                form.part("files", part)
             }
+        };
+    }
+
+    Ok(form)
+}
+
+/// Sets a JSON request body, gzip-compressing it when enabled and worth it
+trait JsonBody {
+    fn json_body<T: Serialize>(self, body: &T, client: &WaitHuman) -> Self;
+}
+
+impl JsonBody for RequestBuilder {
+    #[cfg(not(feature = "compression"))]
+    fn json_body<T: Serialize>(self, body: &T, _client: &WaitHuman) -> Self {
+        self.json(body)
+    }
+
+    #[cfg(feature = "compression")]
+    fn json_body<T: Serialize>(self, body: &T, client: &WaitHuman) -> Self {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
+        use std::io::Write;
+
+        // Serialization errors are left for `json` to report when the request is sent
+        let bytes = match serde_json::to_vec(body) {
+            Ok(bytes) if client.compression && bytes.len() > COMPRESSION_THRESHOLD_BYTES => bytes,
+            _ => return self.json(body),
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        match encoder.write_all(&bytes).and_then(|_| encoder.finish()) {
+            Ok(compressed) => self
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_ENCODING, "gzip")
+                .body(compressed),
+            Err(_) => self.json(body),
+        }
+    }
+}
+
+/// Deserializes a JSON response body, reporting the endpoint, status and a snippet of
+/// the body if it doesn't match the expected schema
+///
+/// A successful status with the wrong body usually means schema drift or a gateway
+/// answering in the server's place (e.g. an HTML login page), so it is reported as
+/// `InvalidResponse` rather than a network error.
+async fn read_json<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let path = response.url().path().to_string();
+    let status = response.status();
+    let body = response_text(response).await?;
+
+    serde_json::from_str(&body).map_err(|e| json_error(&path, status, &body, e))
+}
+
+/// The `InvalidResponse` error of a body from `path` that failed to deserialize
+fn json_error(path: &str, status: StatusCode, body: &str, e: serde_json::Error) -> WaitHumanError {
+    WaitHumanError::InvalidResponse(format!(
+        "unexpected JSON from {} ({}): {} (body: {})",
+        path,
+        status,
+        e,
+        json_snippet(body)
+    ))
+}
+
+/// The start of `body`, to show in errors and logs
+fn json_snippet(body: &str) -> String {
+    let mut snippet: String = body.chars().take(JSON_ERROR_SNIPPET_CHARS).collect();
+    if snippet.len() < body.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Reads a `202 Accepted` answer to a create, from gateways that queue the request for
+/// the server instead of passing its answer through
+///
+/// The id is taken from the body when the gateway relays the server's, or else from the
+/// last segment of the `Location` header pointing to the confirmation.
+async fn read_accepted(response: Response) -> Result<CreateConfirmationResponse> {
+    let location = response
+        .headers()
+        .get(LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(str::to_string);
+    let body = response_text(response).await?;
+
+    if let Ok(data) = serde_json::from_str(&body) {
+        return Ok(data);
+    }
+    let confirmation_id = location
+        .as_deref()
+        .and_then(|location| location.split(['?', '#']).next())
+        .and_then(|path| path.trim_end_matches('/').rsplit('/').next())
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| {
+            WaitHumanError::InvalidResponse(
+                "202 Accepted for a create without a confirmation id in the body or Location header"
+                    .to_string(),
+            )
+        })?;
+    Ok(CreateConfirmationResponse {
+        confirmation_request_id: confirmation_id,
+        expires_at: None,
+        reachable_channels: None,
+    })
+}
+
+/// Reads the body of a response as text, logging it with the `debug-http` feature
+async fn response_text(response: Response) -> reqwest::Result<String> {
+    #[cfg(feature = "debug-http")]
+    let (url, status) = (response.url().clone(), response.status());
+    let body = response.text().await?;
+    #[cfg(feature = "debug-http")]
+    debug_http::log_response_body(&url, status, &body);
+    Ok(body)
+}
 
-            let data: GetConfirmationResponse = response.json().await?;
+/// Maps 401/403 responses to `Unauthorized`, including the server's error text, and
+/// 503 responses to `ServiceUnavailable`
+async fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
 
-            if let Some(answer) = data.maybe_answer {
-                return Ok(answer);
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(WaitHumanError::Unauthorized {
+            status: status.as_u16(),
+            message: response_text(response).await.unwrap_or_default(),
+        });
+    }
+    if status == StatusCode::SERVICE_UNAVAILABLE {
+        return Err(service_unavailable(response).await);
+    }
+
+    Ok(response)
+}
+
+/// Reads a 503 response into a `ServiceUnavailable` error
+///
+/// When the server will be back comes from the `Retry-After` header, else from a JSON
+/// body such as `{"message": "...", "retry_after": 600}` or one with the `ends_at` of
+/// the maintenance window.
+async fn service_unavailable(response: Response) -> WaitHumanError {
+    let header = retry_after(&response);
+    let body: MaintenanceResponse = response_text(response)
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_str(&body).ok())
+        .unwrap_or_default();
+
+    let retry_after = header
+        .or(body.retry_after.map(Duration::from_secs))
+        .or_else(|| {
+            body.ends_at
+                .map(|ends_at| (ends_at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+        });
+    WaitHumanError::ServiceUnavailable {
+        retry_after,
+        message: body
+            .message
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty()),
+    }
+}
+
+/// Returns how long the server may hold the next poll, or `None` to short poll
+///
+/// The hold is capped to `max_hold`, and to the time left before the deadline, so the
+/// last poll of a timed ask ends in time instead of overshooting it. Under a second
+/// left, it falls back to a short poll, as the server takes whole seconds.
+fn long_poll_hold(
+    options: &AskOptions,
+    deadline: Option<Instant>,
+    max_hold: Duration,
+) -> Option<Duration> {
+    if !options.long_poll {
+        return None;
+    }
+
+    let max_hold = LONG_POLL_WINDOW.min(max_hold);
+    let hold = match deadline {
+        Some(deadline) => max_hold.min(deadline.saturating_duration_since(Instant::now())),
+        None => max_hold,
+    };
+
+    (hold.as_secs() > 0).then_some(hold)
+}
+
+/// Whether a request failed because its connection was closed once established, rather
+/// than because the server couldn't be reached or answered in time
+fn is_dropped_connection(error: &WaitHumanError) -> bool {
+    matches!(
+        error,
+        WaitHumanError::NetworkError {
+            status: None,
+            is_timeout: false,
+            is_connect: false,
+            ..
+        }
+    )
+}
+
+/// Whether a request failed because of its endpoint: it couldn't be connected to, or,
+/// with replicated backends, answered with a server error
+///
+/// A 503 is maintenance, waited out rather than failed over. Without replication, the
+/// other endpoints don't know the confirmations of this one.
+fn is_endpoint_failure(result: &Result<Response>, endpoints: &Endpoints) -> bool {
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            endpoints.replicated()
+                && status.is_server_error()
+                && status != StatusCode::SERVICE_UNAVAILABLE
+        }
+        Err(e) => matches!(
+            e,
+            WaitHumanError::NetworkError {
+                is_connect: true,
+                ..
             }
+        ),
+    }
+}
+
+/// Switches an adaptive ask to polling every interval, for the rest of the call
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn fall_back_to_short_polls(options: &mut AskOptions, confirmation_id: &ConfirmationId) {
+    options.long_poll = false;
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        %confirmation_id,
+        "server doesn't support long polling, falling back to polling every interval"
+    );
+}
+
+/// Converts a wall-clock time to the `Instant` it will be reached at
+fn instant_at(time: DateTime<Utc>) -> Instant {
+    Instant::now() + (time - Utc::now()).to_std().unwrap_or_default()
+}
+
+/// Parses the endpoint or app URL configured in `field`, to which API paths are appended
+fn base_url(field: &'static str, url: &str) -> Result<Url> {
+    let parsed = Url::parse(url).map_err(|e| WaitHumanError::InvalidConfig {
+        field,
+        message: format!("invalid URL {:?}: {}", url, e),
+    })?;
+    if parsed.cannot_be_a_base() {
+        return Err(WaitHumanError::InvalidConfig {
+            field,
+            message: format!("invalid URL {:?}: not a base URL", url),
+        });
+    }
+    Ok(parsed)
+}
+
+/// Rejects a plain-HTTP URL configured in `field`, except to loopback hosts for local
+/// testing
+fn check_https(field: &'static str, url: &Url) -> Result<()> {
+    if url.scheme() != "https" && !is_loopback(url) {
+        return Err(WaitHumanError::InvalidConfig {
+            field,
+            message: format!(
+                "endpoint {} doesn't use HTTPS, which require_https demands outside of loopback hosts",
+                url
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Whether `url` points at the local machine
+fn is_loopback(url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        // IPv6 hosts come in brackets
+        let ip = host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost")
+            || ip
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    })
+}
 
-            // Wait before next poll
-            sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+/// Appends path segments to a base URL, keeping any path the base already has
+///
+/// The result is the same whether or not the base ends with a slash, and each segment
+/// is percent-encoded, so an id can't alter the rest of the path. With
+/// `trailing_slash`, the result ends with a slash too.
+fn join_url(base: &Url, segments: &[&str], trailing_slash: bool) -> Url {
+    let mut url = base.clone();
+    // Only fails for URLs that can't be a base, which `base_url` rejects
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.pop_if_empty().extend(segments);
+        if trailing_slash {
+            path.push("");
         }
     }
+    url
+}
+
+/// Runs the client-side checks of a question and the options it's asked with
+fn validate_ask(question: &ConfirmationQuestion, options: &AskOptions) -> Result<()> {
+    validate_question(question)?;
+    validate_choice_states(question, options)?;
+    validate_choice_provider(question, options)?;
+    validate_length_bounds(options)?;
+    validate_locale(options)?;
+    validate_recipient(options)?;
+    validate_auto_answer_rules(question, options)?;
+    validate_tags(options)?;
+    validate_required_approvals(options)
+}
+
+/// Returns the placeholder answer of a dry-run ask: `AskOptions::default_on_timeout`
+/// if set, otherwise the first choice, a free text satisfying the length bounds, or a
+/// form with every field filled in
+fn dry_run_answer(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+) -> ConfirmationAnswerWithDate {
+    let answer_content =
+        options
+            .default_on_timeout
+            .clone()
+            .unwrap_or_else(|| match question.answer_format {
+                AnswerFormat::FreeText => {
+                    let length = options
+                        .min_length
+                        .unwrap_or(0)
+                        .max(1)
+                        .min(options.max_length.unwrap_or(usize::MAX));
+                    AnswerContent::FreeText {
+                        text: "x".repeat(length),
+                    }
+                }
+                AnswerFormat::Options { .. } => AnswerContent::Options {
+                    selected_indexes: vec![(0..)
+                        .find(|index| !options.disabled_choices.contains(index))
+                        .unwrap_or(0)],
+                },
+                AnswerFormat::Form { ref fields } => AnswerContent::Form {
+                    values: fields
+                        .iter()
+                        .map(|field| {
+                            let value = match field.kind {
+                                FormFieldKind::Text => "x".into(),
+                                FormFieldKind::Number => 0.into(),
+                                FormFieldKind::Boolean => false.into(),
+                            };
+                            (field.name.clone(), value)
+                        })
+                        .collect(),
+                },
+                AnswerFormat::Search => AnswerContent::Search {
+                    key: "x".into(),
+                    label: "x".into(),
+                },
+                AnswerFormat::Binary { ref content_types } => AnswerContent::Binary {
+                    content_type: content_types
+                        .iter()
+                        .find(|content_type| !content_type.contains('*'))
+                        .cloned()
+                        .unwrap_or_else(|| "application/octet-stream".into()),
+                    data: Base64Bytes::default(),
+                },
+            });
+
+    local_answer(question, options, answer_content, AnswerSource::Automation)
+}
+
+/// Returns an answer the client produced itself, without a confirmation on the server
+fn local_answer(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+    answer_content: AnswerContent,
+    answered_by: AnswerSource,
+) -> ConfirmationAnswerWithDate {
+    ConfirmationAnswerWithDate {
+        answer: ConfirmationAnswer {
+            answer_content,
+            attachments: Vec::new(),
+            signature: None,
+            confidence: None,
+            comment: None,
+        },
+        answered_at: Utc::now(),
+        metadata: options.metadata.clone(),
+        answered_by,
+        locale: None,
+        tags: options.tags.clone(),
+        question: options.include_question.then(|| question.clone()),
+        due_at: None,
+        recipient: None,
+        auto_answer_rule: None,
+    }
+}
+
+/// Returns how long to wait before the next poll: the server's hint if it gave one,
+/// else the poll interval, or nothing when long polling, as the server already waited
+fn next_poll_wait(
+    options: &AskOptions,
+    default_interval: Duration,
+    max_hold: Duration,
+    deadline: Option<Instant>,
+    hint: Option<Duration>,
+) -> Option<Duration> {
+    hint.or_else(|| {
+        long_poll_hold(options, deadline, max_hold)
+            .is_none()
+            .then(|| poll_interval(options, default_interval))
+    })
+}
+
+/// Whether a poll asked to be held for `hold` came back pending well before, which a
+/// server holding polls open only does to relay a search
+fn returned_early(hold: Option<Duration>, sent: Instant, hints: &PollHints) -> bool {
+    hold.filter(|_| !hints.searched)
+        .is_some_and(|hold| sent.elapsed() < hold / 2)
+}
+
+/// Reads an environment variable, treating an empty value as unset
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Generates a random idempotency key for creates that don't set one
+fn random_idempotency_key() -> String {
+    format!("{:032x}", fastrand::u128(..))
+}
+
+/// Turns an `Abstained` error into `Ok(None)`, keeping every other result
+#[cfg(feature = "convenience")]
+fn none_on_abstain<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(WaitHumanError::Abstained { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Turns a `Timeout` error into `Ok(None)`, keeping every other result
+fn none_on_timeout<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(WaitHumanError::Timeout { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the interval before the next poll, randomly scaled by `1 ± poll_jitter`
+///
+/// `default_interval` applies when `options` doesn't set one.
+fn poll_interval(options: &AskOptions, default_interval: Duration) -> Duration {
+    let interval = options.poll_interval.unwrap_or(default_interval);
+    let jitter = match options.poll_jitter.unwrap_or(DEFAULT_POLL_JITTER) {
+        jitter if jitter.is_nan() => 0.0,
+        jitter => jitter.clamp(0.0, 1.0),
+    };
+    let factor = 1.0 + jitter * (fastrand::f64() * 2.0 - 1.0);
+    interval.mul_f64(factor)
+}
+
+/// Returns how long from now the server gives the human to answer: `due_in`, or with
+/// `sync_server_expiry` the time left before the ask's deadline, less the clock skew
+/// margin, if sooner
+fn server_due_in(options: &AskOptions, start: Instant) -> Option<Duration> {
+    let deadline = options
+        .deadline
+        .or_else(|| options.effective_timeout().map(|timeout| start + timeout))
+        .filter(|_| options.sync_server_expiry);
+    let synced = deadline.map(|deadline| {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .saturating_sub(SERVER_EXPIRY_MARGIN)
+    });
+    match (options.due_in, synced) {
+        (Some(due_in), Some(synced)) => Some(due_in.min(synced)),
+        (due_in, synced) => due_in.or(synced),
+    }
+}
+
+/// Converts a time left from now to the wall-clock time it runs out at
+fn due_at(due_in: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(due_in)
+        .ok()
+        .and_then(|due_in| Utc::now().checked_add_signed(due_in))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Parses the `Retry-After` header, given either as delay-seconds or as an HTTP-date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc) - Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Picks the HTTP client for a new instance: the configured one, a dedicated one
+/// customized by the builder closure, the proxy or the other connection settings, the
+/// process-wide one if shared, or a new one
+fn build_client(
+    http_client: Option<Client>,
+    http_client_builder: Option<HttpClientBuilderFn>,
+    proxy: Option<String>,
+    connect_timeout: Option<Duration>,
+    redirect_policy: Option<RedirectPolicy>,
+    require_https: bool,
+    shared: bool,
+) -> Result<Client> {
+    let customized = http_client_builder.is_some()
+        || proxy.is_some()
+        || connect_timeout.is_some()
+        || redirect_policy.is_some();
+    // The shared client follows any redirect, downgrades to plain HTTP included
+    if shared && (customized || require_https || http_client.is_some()) {
+        return Err(WaitHumanError::InvalidConfig {
+            field: "shared_http_client",
+            message: "shared_http_client can't be combined with http_client, http_client_builder, proxy, connect_timeout, redirect_policy or require_https"
+                .to_string(),
+        });
+    }
+    match (http_client, customized) {
+        (Some(_), true) => Err(WaitHumanError::InvalidConfig {
+            field: "http_client",
+            message: "http_client_builder, proxy, connect_timeout and redirect_policy can't be combined with a custom http_client; configure them on the client"
+                .to_string(),
+        }),
+        (Some(client), false) => Ok(client),
+        (None, true) => custom_client(
+            http_client_builder,
+            proxy.as_deref(),
+            connect_timeout,
+            redirect_policy,
+            require_https,
+        ),
+        (None, false) if require_https => custom_client(None, None, None, None, true),
+        (None, false) if shared => Ok(shared_client()),
+        (None, false) => Ok(default_client()),
+    }
+}
+
+/// Builds an HTTP client customized by `configure`, then sending every request through
+/// `proxy`, giving up on connecting after `connect_timeout` and following redirects
+/// according to `redirect_policy`, if set, except to plain HTTP under `require_https`
+#[cfg(not(target_arch = "wasm32"))]
+fn custom_client(
+    configure: Option<HttpClientBuilderFn>,
+    proxy: Option<&str>,
+    connect_timeout: Option<Duration>,
+    redirect_policy: Option<RedirectPolicy>,
+    require_https: bool,
+) -> Result<Client> {
+    let mut builder = Client::builder().tcp_keepalive(TCP_KEEPALIVE);
+
+    // The closure runs first, so the client's own settings can't be undone by it
+    if let Some(configure) = configure {
+        builder = configure(builder);
+    }
+
+    if let Some(proxy) = proxy {
+        // Credentials in the URL are sent as proxy basic auth by reqwest
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| WaitHumanError::InvalidConfig {
+            field: "proxy",
+            message: format!("invalid proxy URL: {}", e),
+        })?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(connect_timeout) = connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if redirect_policy.is_some() || require_https {
+        builder = builder.redirect(reqwest_redirect_policy(
+            redirect_policy.unwrap_or_default(),
+            require_https,
+        ));
+    }
+
+    builder.build().map_err(|e| WaitHumanError::InvalidConfig {
+        field: "http_client_builder",
+        message: format!("failed to build HTTP client: {}", e),
+    })
+}
+
+/// Translates a redirect policy into reqwest's, refusing redirects to plain HTTP under
+/// `require_https` like `check_https` refuses such endpoints
+#[cfg(not(target_arch = "wasm32"))]
+fn reqwest_redirect_policy(
+    redirect_policy: RedirectPolicy,
+    require_https: bool,
+) -> reqwest::redirect::Policy {
+    use reqwest::redirect::Policy;
+
+    match redirect_policy {
+        RedirectPolicy::Follow if !require_https => Policy::default(),
+        RedirectPolicy::None => Policy::none(),
+        policy => Policy::custom(move |attempt| {
+            // The first URL is the one originally requested
+            let Some(original) = attempt.previous().first() else {
+                return attempt.stop();
+            };
+            let target = attempt.url();
+            let same_host = target.host_str() == original.host_str()
+                && target.port_or_known_default() == original.port_or_known_default();
+            let downgrade = original.scheme() == "https" && target.scheme() != "https";
+            let insecure = require_https && (downgrade || check_https("endpoint", target).is_err());
+            let allowed = match policy {
+                RedirectPolicy::SameHost => same_host && !downgrade,
+                _ => true,
+            };
+            if attempt.previous().len() > 10 {
+                attempt.error("too many redirects")
+            } else if allowed && !insecure {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }),
+    }
+}
+
+/// Browsers manage connections and redirects themselves, so neither a proxy, a connect
+/// timeout nor a redirect policy can be set, and their mixed-content rules already keep
+/// HTTPS pages from following redirects to plain HTTP
+#[cfg(target_arch = "wasm32")]
+fn custom_client(
+    configure: Option<HttpClientBuilderFn>,
+    proxy: Option<&str>,
+    connect_timeout: Option<Duration>,
+    redirect_policy: Option<RedirectPolicy>,
+    _require_https: bool,
+) -> Result<Client> {
+    let (field, message) = match (proxy, connect_timeout, redirect_policy) {
+        (Some(_), _, _) => (
+            "proxy",
+            "proxy is not supported on wasm32; the browser's proxy settings apply",
+        ),
+        (None, Some(_), _) => (
+            "connect_timeout",
+            "connect_timeout is not supported on wasm32; the browser manages connections",
+        ),
+        (None, None, Some(_)) => (
+            "redirect_policy",
+            "redirect_policy is not supported on wasm32; the browser follows redirects",
+        ),
+        (None, None, None) => {
+            let builder = Client::builder();
+            let builder = match configure {
+                Some(configure) => configure(builder),
+                None => builder,
+            };
+            return builder.build().map_err(|e| WaitHumanError::InvalidConfig {
+                field: "http_client_builder",
+                message: format!("failed to build HTTP client: {}", e),
+            });
+        }
+    };
+    Err(WaitHumanError::InvalidConfig {
+        field,
+        message: message.to_string(),
+    })
+}
+
+/// Returns the HTTP client shared by all instances with
+/// `WaitHumanConfig::shared_http_client`, so connection pooling and DNS caching survive
+/// constructing many `WaitHuman`s
+fn shared_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(default_client).clone()
+}
+
+/// Builds the HTTP client of an instance without HTTP client settings
+#[cfg(not(target_arch = "wasm32"))]
+fn default_client() -> Client {
+    Client::builder()
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()
+        .expect("the default HTTP client settings are valid")
+}
+
+/// Browsers manage connections, so the default client has reqwest's defaults
+#[cfg(target_arch = "wasm32")]
+fn default_client() -> Client {
+    Client::new()
 }
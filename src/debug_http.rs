@@ -0,0 +1,124 @@
+//! Trace logging of HTTP traffic, compiled in with the `debug-http` feature
+//!
+//! Logs full request and response bodies at trace level under the `wait_human::http`
+//! target, to diagnose mismatches with the server's schema. Credentials are redacted:
+//! the API key, signature headers and JSON fields named like secrets. The bodies
+//! themselves, questions and answers included, are logged as they are.
+
+use crate::signing::REQUEST_SIGNATURE_HEADER;
+use crate::types::redact_api_key;
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response, StatusCode, Url};
+use serde_json::Value;
+
+const REDACTED: &str = "***";
+
+/// Words that mark a header or JSON field as holding a secret
+const SECRET_WORDS: &[&str] = &[
+    "secret",
+    "token",
+    "password",
+    "api-key",
+    "api_key",
+    "apikey",
+    "signature",
+];
+
+/// Logs an outgoing request, before it is sent
+pub(crate) fn log_request(request: &Request) {
+    let body = match request.body() {
+        Some(body) => match body.as_bytes() {
+            Some(bytes) => body_text(bytes),
+            None => "<streamed>".to_string(),
+        },
+        None => String::new(),
+    };
+
+    tracing::trace!(
+        target: "wait_human::http",
+        method = %request.method(),
+        url = %request.url(),
+        headers = ?redacted_headers(request.headers()),
+        body = %body,
+        "request",
+    );
+}
+
+/// Logs the status and headers of a response, as soon as they arrive
+pub(crate) fn log_response(response: &Response) {
+    tracing::trace!(
+        target: "wait_human::http",
+        status = %response.status(),
+        url = %response.url(),
+        headers = ?redacted_headers(response.headers()),
+        "response",
+    );
+}
+
+/// Logs the body of a response, once the client read it
+pub(crate) fn log_response_body(url: &Url, status: StatusCode, body: &str) {
+    tracing::trace!(
+        target: "wait_human::http",
+        status = %status,
+        url = %url,
+        body = %body_text(body.as_bytes()),
+        "response body",
+    );
+}
+
+fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "cookie" || name == "set-cookie" || SECRET_WORDS.iter().any(|word| name.contains(word))
+}
+
+/// Returns the headers with credentials redacted, keeping the prefix of the API key
+fn redacted_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = value.to_str().unwrap_or("<binary>");
+            let value = if name == "authorization" || name == "proxy-authorization" {
+                redact_api_key(value)
+            } else if name.as_str().eq_ignore_ascii_case(REQUEST_SIGNATURE_HEADER)
+                || is_secret(name.as_str())
+            {
+                REDACTED.to_string()
+            } else {
+                value.to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Returns a body as text, with secret-looking JSON fields redacted
+///
+/// Bodies that aren't text, such as compressed ones, are logged by size only.
+fn body_text(bytes: &[u8]) -> String {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return format!("<{} bytes>", bytes.len());
+    };
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            json.to_string()
+        }
+        Err(_) => text.to_string(),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                if is_secret(name) && !value.is_null() {
+                    *value = Value::from(REDACTED);
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
@@ -1,36 +1,352 @@
+use crate::types::{ConfirmationAnswerWithDate, ConfirmationId};
+use std::error::Error as _;
 use thiserror::Error;
 
 /// Errors that can occur when using the WaitHuman client
-#[derive(Error, Debug)]
+///
+/// Errors are `Clone`, so they can be cached or broadcast to several waiting tasks.
+#[derive(Error, Debug, Clone)]
 pub enum WaitHumanError {
     /// Request timed out waiting for an answer
     #[error("Request timed out after {elapsed_seconds:.1} seconds")]
-    Timeout { elapsed_seconds: f64 },
+    Timeout {
+        elapsed_seconds: f64,
+        /// Id of the confirmation, if it was created before the timeout. Persist it to
+        /// look up an answer that arrives later
+        confirmation_id: Option<ConfirmationId>,
+        /// Subject of the question, if the timeout ended an ask
+        subject: Option<String>,
+    },
+
+    /// The confirmation was polled `AskOptions::max_polls` times without an answer
+    #[error("No answer to confirmation {confirmation_id} after {polls} polls")]
+    MaxPollsExceeded {
+        /// Number of polls performed
+        polls: u32,
+        /// Id of the confirmation, to look up an answer that arrives later
+        confirmation_id: ConfirmationId,
+    },
 
     /// Network error occurred during HTTP request
-    #[error("Network error: {0}")]
-    NetworkError(#[from] reqwest::Error),
+    #[error("Network error: {message}")]
+    NetworkError {
+        /// Description of the error, including its underlying causes
+        message: String,
+        /// HTTP status code, if the error came from a response
+        status: Option<u16>,
+        /// Whether the request timed out
+        is_timeout: bool,
+        /// Whether the connection to the server could not be established
+        is_connect: bool,
+    },
 
     /// Failed to create confirmation request
     #[error("Failed to create confirmation: {status_text}")]
-    CreateFailed { status_text: String },
+    CreateFailed { status: u16, status_text: String },
 
     /// Failed to poll for answer
     #[error("Failed to poll for answer: {status_text}")]
-    PollFailed { status_text: String },
+    PollFailed { status: u16, status_text: String },
+
+    /// The API key was rejected (HTTP 401 or 403)
+    #[error("Unauthorized ({status}): {message}")]
+    Unauthorized {
+        status: u16,
+        /// Error text returned by the server, if any
+        message: String,
+    },
+
+    /// A request other than create or poll failed
+    #[error("Request failed: {status_text}")]
+    RequestFailed { status: u16, status_text: String },
+
+    /// The pagination cursor is invalid or expired
+    #[error("Invalid pagination cursor: {cursor}")]
+    InvalidCursor { cursor: String },
+
+    /// The server is down for maintenance (HTTP 503)
+    ///
+    /// The requests of an ask wait the maintenance out when the server says when it will
+    /// be back and that is within the ask's timeout; this is returned otherwise.
+    #[error("Service unavailable: {}", message.as_deref().unwrap_or("down for maintenance"))]
+    ServiceUnavailable {
+        /// How long until the server expects to be back, from the `Retry-After` header
+        /// or the response body, if it said
+        retry_after: Option<std::time::Duration>,
+        /// Explanation from the server, e.g. the maintenance window, fit to show users
+        message: Option<String>,
+    },
+
+    /// Requests are short-circuited after repeated failures; retry after the given delay
+    #[error("Circuit breaker is open, retry after {retry_after:?}")]
+    CircuitOpen { retry_after: std::time::Duration },
 
     /// Received unexpected answer type
     #[error("Unexpected answer type: expected {expected}, got {actual}")]
     UnexpectedAnswerType { expected: String, actual: String },
 
+    /// The free-text answer could not be deserialized into the requested type
+    #[error("Failed to parse answer: {message}")]
+    AnswerParseFailed { message: String },
+
+    /// A form answer left a required field empty
+    #[error("Form field {name:?} is required but was not filled in")]
+    MissingFormField { name: String },
+
+    /// `AskOptions::validator` rejected every answer, up to
+    /// `AskOptions::max_answer_attempts`
+    #[error("Answer rejected after {attempts} attempts: {reason}")]
+    AnswerRejected {
+        /// Id of the confirmation whose answer was rejected last
+        confirmation_id: Option<ConfirmationId>,
+        /// Rejection message of the validator for the last answer
+        reason: String,
+        /// Number of confirmations asked
+        attempts: u32,
+    },
+
+    /// A single-choice answer selected more than one option
+    #[error("Expected a single selection, got {count}")]
+    MultipleSelections { count: usize },
+
+    /// An options answer selected the same index more than once
+    #[error("Selected index {index} appears more than once")]
+    DuplicateSelectedIndex { index: u32 },
+
+    /// An options answer selected a choice that was shown as disabled
+    #[error("Selected index {index} is a disabled choice")]
+    DisabledChoiceSelected { index: u32 },
+
+    /// An options answer selected a choice that requires a comment, without one
+    #[error("Selected index {index} requires a comment")]
+    MissingComment { index: u32 },
+
+    /// A binary answer is larger than `AskOptions::max_binary_bytes`
+    #[error("Binary answer of {size} bytes exceeds the maximum of {max} bytes")]
+    BinaryAnswerTooLarge { size: usize, max: usize },
+
     /// Invalid selected index in answer
     #[error("Invalid selected index: {index}")]
     InvalidSelectedIndex { index: u32 },
 
+    /// The human explicitly abstained instead of answering, as allowed by
+    /// `AskOptions::allow_abstain`
+    #[error("The human abstained from answering")]
+    Abstained {
+        /// Id of the confirmation the human abstained on, if the answer came from one.
+        /// `None` from `AnswerContent::check_format`, which only sees the answer
+        confirmation_id: Option<ConfirmationId>,
+    },
+
+    /// The confirmation expired on the server before anyone answered it
+    #[error("Confirmation {confirmation_id} expired without an answer")]
+    Expired { confirmation_id: ConfirmationId },
+
+    /// The confirmation was answered before it could be changed, e.g. by an update racing
+    /// with the human. Carries the answer, which still stands
+    #[error("Confirmation {confirmation_id} was already answered")]
+    AlreadyAnswered {
+        confirmation_id: ConfirmationId,
+        answer: Box<ConfirmationAnswerWithDate>,
+    },
+
+    /// The human declined to answer the confirmation
+    #[error("Confirmation {confirmation_id} was declined")]
+    Declined { confirmation_id: ConfirmationId },
+
+    /// The confirmation was cancelled before it was answered
+    #[error("Confirmation {confirmation_id} was cancelled")]
+    Cancelled { confirmation_id: ConfirmationId },
+
+    /// No notification channel (device, email, ...) can reach the human, so nobody
+    /// would ever answer. The confirmation was cancelled
+    #[error("No notification channel can reach a human for confirmation {confirmation_id}")]
+    NoChannel { confirmation_id: ConfirmationId },
+
+    /// The question is invalid and was not sent
+    #[error("Invalid question: {0}")]
+    InvalidQuestion(String),
+
+    /// An inline attachment exceeds the maximum upload size
+    #[error("Attachment {name} is {size} bytes, exceeding the {max} byte limit")]
+    AttachmentTooLarge {
+        name: String,
+        size: usize,
+        max: usize,
+    },
+
+    /// The answer was rejected before or by the server, e.g. because it doesn't match
+    /// the question's answer format
+    #[error("Invalid answer: {0}")]
+    InvalidAnswer(String),
+
+    /// A confirmation id is malformed
+    #[error("Invalid confirmation id: {id:?}")]
+    InvalidConfirmationId { id: String },
+
+    /// The client configuration is invalid, e.g. an empty API key or a malformed proxy
+    /// URL
+    ///
+    /// Unlike the errors of requests, this is a setup mistake: retrying won't help.
+    #[error("Invalid configuration: {message}")]
+    InvalidConfig {
+        /// Setting at fault, named like its `WaitHumanConfig` or `AskOptions` field
+        /// (e.g. `proxy`), or the environment variable it was read from
+        field: &'static str,
+        /// What is wrong with it
+        message: String,
+    },
+
+    /// The API key file could not be read, or is empty
+    #[error("Failed to read API key from {path}: {message}")]
+    KeyFile { path: String, message: String },
+
+    /// A webhook request failed verification, e.g. a bad signature or a stale timestamp
+    #[error("Invalid webhook: {0}")]
+    InvalidWebhook(String),
+
+    /// The client is shutting down and no longer accepts new asks
+    #[error("Client is shutting down")]
+    ShuttingDown,
+
+    /// A `ConfirmationStore` failed to save, remove or load confirmations
+    #[error("Confirmation store failed: {message}")]
+    StoreFailed { message: String },
+
+    /// A required environment variable is not set
+    #[error("Environment variable {name} is not set")]
+    MissingEnvVar { name: &'static str },
+
     /// Invalid response from server
     #[error("Invalid response from server: {0}")]
     InvalidResponse(String),
 }
 
+impl WaitHumanError {
+    /// Returns the HTTP status code of the failed request, if the error came from one
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::CreateFailed { status, .. }
+            | Self::PollFailed { status, .. }
+            | Self::Unauthorized { status, .. }
+            | Self::RequestFailed { status, .. } => Some(*status),
+            Self::NetworkError { status, .. } => *status,
+            Self::ServiceUnavailable { .. } => Some(503),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status code as a `reqwest::StatusCode`, if available
+    pub fn status_code(&self) -> Option<reqwest::StatusCode> {
+        self.status()
+            .and_then(|s| reqwest::StatusCode::from_u16(s).ok())
+    }
+
+    /// Returns the `std::io::ErrorKind` closest to the error, used when converting it
+    /// into a `std::io::Error`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    /// use wait_human::WaitHumanError;
+    ///
+    /// let error = WaitHumanError::Timeout {
+    ///     elapsed_seconds: 60.0,
+    ///     confirmation_id: None,
+    ///     subject: None,
+    /// };
+    /// assert_eq!(error.io_error_kind(), ErrorKind::TimedOut);
+    /// ```
+    pub fn io_error_kind(&self) -> std::io::ErrorKind {
+        use std::io::ErrorKind;
+
+        match self {
+            Self::Timeout { .. } | Self::MaxPollsExceeded { .. } | Self::Expired { .. } => {
+                ErrorKind::TimedOut
+            }
+            Self::NetworkError {
+                is_timeout: true, ..
+            } => ErrorKind::TimedOut,
+            Self::NetworkError {
+                is_connect: true, ..
+            }
+            | Self::ServiceUnavailable { .. }
+            | Self::CircuitOpen { .. } => ErrorKind::ConnectionRefused,
+            Self::NetworkError { .. } => ErrorKind::Other,
+            Self::Unauthorized { .. } | Self::Declined { .. } => ErrorKind::PermissionDenied,
+            Self::CreateFailed { .. } | Self::PollFailed { .. } | Self::RequestFailed { .. } => {
+                ErrorKind::Other
+            }
+            Self::Cancelled { .. } => ErrorKind::ConnectionAborted,
+            Self::NoChannel { .. } => ErrorKind::NotConnected,
+            Self::UnexpectedAnswerType { .. }
+            | Self::AnswerParseFailed { .. }
+            | Self::MissingFormField { .. }
+            | Self::AnswerRejected { .. }
+            | Self::MultipleSelections { .. }
+            | Self::DuplicateSelectedIndex { .. }
+            | Self::DisabledChoiceSelected { .. }
+            | Self::MissingComment { .. }
+            | Self::BinaryAnswerTooLarge { .. }
+            | Self::InvalidSelectedIndex { .. }
+            | Self::InvalidResponse(_) => ErrorKind::InvalidData,
+            Self::InvalidCursor { .. }
+            | Self::InvalidQuestion(_)
+            | Self::AttachmentTooLarge { .. }
+            | Self::InvalidAnswer(_)
+            | Self::InvalidConfirmationId { .. }
+            | Self::InvalidConfig { .. }
+            | Self::InvalidWebhook(_) => ErrorKind::InvalidInput,
+            Self::MissingEnvVar { .. } => ErrorKind::NotFound,
+            Self::Abstained { .. }
+            | Self::AlreadyAnswered { .. }
+            | Self::KeyFile { .. }
+            | Self::ShuttingDown
+            | Self::StoreFailed { .. } => ErrorKind::Other,
+        }
+    }
+}
+
+/// Converts into an `std::io::Error` of the kind given by
+/// [`io_error_kind`](WaitHumanError::io_error_kind), keeping the original error as its
+/// inner error, e.g. for traits that return `std::io::Result`
+impl From<WaitHumanError> for std::io::Error {
+    fn from(error: WaitHumanError) -> Self {
+        std::io::Error::new(error.io_error_kind(), error)
+    }
+}
+
+impl From<reqwest::Error> for WaitHumanError {
+    fn from(error: reqwest::Error) -> Self {
+        // reqwest's message omits the underlying cause (e.g. "connection refused")
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+
+        WaitHumanError::NetworkError {
+            message,
+            status: error.status().map(|s| s.as_u16()),
+            is_timeout: error.is_timeout(),
+            is_connect: is_connect(&error),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connect(error: &reqwest::Error) -> bool {
+    error.is_connect()
+}
+
+/// The browser's fetch API doesn't tell connection failures apart
+#[cfg(target_arch = "wasm32")]
+fn is_connect(_error: &reqwest::Error) -> bool {
+    false
+}
+
 /// Result type alias for WaitHuman operations
 pub type Result<T> = std::result::Result<T, WaitHumanError>;
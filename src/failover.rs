@@ -0,0 +1,94 @@
+use crate::runtime::Instant;
+use reqwest::Url;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long an endpoint that failed is tried only after the healthy ones
+pub(crate) const ENDPOINT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// The client's endpoint and its fallbacks, with their health, shared by all clones of
+/// a client
+///
+/// Requests go to the first healthy endpoint, in configured order. An endpoint that
+/// couldn't be connected to, or answered with a 5xx other than 503 when the backends
+/// are replicated, is skipped for [`ENDPOINT_COOLDOWN`], unless every endpoint is
+/// failing.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    /// The client's endpoint first, then the fallbacks in order
+    urls: Vec<Url>,
+    /// Whether every endpoint knows every confirmation, from
+    /// `WaitHumanConfig::replicated_endpoints`
+    replicated: bool,
+    /// Until when each endpoint is tried last, after failing
+    failed_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl Endpoints {
+    pub(crate) fn new(urls: Vec<Url>, replicated: bool) -> Self {
+        let failed_until = Mutex::new(vec![None; urls.len()]);
+        Self {
+            urls,
+            replicated,
+            failed_until,
+        }
+    }
+
+    /// Whether a request that reached an endpoint may be sent again to another one
+    pub(crate) fn replicated(&self) -> bool {
+        self.replicated
+    }
+
+    /// Indexes of the endpoints in the order to try them: the healthy ones first, then
+    /// those still cooling down, each in configured order
+    pub(crate) fn order(&self) -> Vec<usize> {
+        let failed_until = self.failed_until.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let (healthy, failing): (Vec<usize>, Vec<usize>) = (0..self.urls.len())
+            .partition(|&index| failed_until[index].is_none_or(|until| until <= now));
+        healthy.into_iter().chain(failing).collect()
+    }
+
+    /// Records whether the endpoint at `index` answered a request
+    pub(crate) fn record(&self, index: usize, success: bool) {
+        let mut failed_until = self.failed_until.lock().unwrap_or_else(|e| e.into_inner());
+        failed_until[index] = (!success).then(|| Instant::now() + ENDPOINT_COOLDOWN);
+    }
+
+    /// The base URL of the endpoint at `index`
+    #[cfg(feature = "tracing")]
+    pub(crate) fn url(&self, index: usize) -> &Url {
+        &self.urls[index]
+    }
+
+    /// Moves `url` from the client's endpoint to the endpoint at `index`, keeping the
+    /// API path and query
+    ///
+    /// Returns `None` for URLs elsewhere than on the client's endpoint, such as those of
+    /// an `AskOptions::endpoint_override`.
+    pub(crate) fn rebase(&self, url: &Url, index: usize) -> Option<Url> {
+        let primary = &self.urls[0];
+        if url.origin() != primary.origin() {
+            return None;
+        }
+        let mut base: Vec<&str> = primary.path_segments()?.collect();
+        if base.last() == Some(&"") {
+            base.pop();
+        }
+        let mut segments = url.path_segments()?;
+        for expected in base {
+            if segments.next() != Some(expected) {
+                return None;
+            }
+        }
+
+        let mut rebased = self.urls[index].clone();
+        rebased
+            .path_segments_mut()
+            .ok()?
+            .pop_if_empty()
+            .extend(segments);
+        rebased.set_query(url.query());
+        Some(rebased)
+    }
+}
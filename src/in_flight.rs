@@ -0,0 +1,110 @@
+use crate::client::Route;
+use crate::error::{Result, WaitHumanError};
+use crate::types::ConfirmationId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Registry of the asks in flight, shared by all clones of a client
+///
+/// Lets `WaitHuman::shutdown` wait for them to finish, and cancel the confirmations of
+/// those that don't.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    shutting_down: bool,
+    /// Asks by registration id, with the confirmations they created and still wait for
+    asks: HashMap<u64, Vec<(Route, ConfirmationId)>>,
+}
+
+impl InFlight {
+    /// Registers a new ask, which stays in flight until the returned guard is dropped
+    ///
+    /// Returns `ShuttingDown` once a shutdown has started.
+    pub(crate) fn register(self: &Arc<Self>) -> Result<InFlightGuard> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.shutting_down {
+            return Err(WaitHumanError::ShuttingDown);
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.asks.insert(id, Vec::new());
+
+        Ok(InFlightGuard {
+            in_flight: Arc::clone(self),
+            id,
+        })
+    }
+
+    /// Number of asks in flight
+    pub(crate) fn len(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .asks
+            .len()
+    }
+
+    /// Rejects any further asks
+    pub(crate) fn begin_shutdown(&self) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .shutting_down = true;
+    }
+
+    /// Confirmations created by the asks still in flight, with their routes
+    pub(crate) fn pending_confirmations(&self) -> Vec<(Route, ConfirmationId)> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.asks.values().flatten().cloned().collect()
+    }
+}
+
+/// Keeps an ask registered as in flight until dropped
+pub(crate) struct InFlightGuard {
+    in_flight: Arc<InFlight>,
+    id: u64,
+}
+
+impl InFlightGuard {
+    /// Records a confirmation the ask created, so a shutdown can cancel it
+    pub(crate) fn created(&self, route: Route, confirmation_id: ConfirmationId) {
+        let mut state = self
+            .in_flight
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(confirmations) = state.asks.get_mut(&self.id) {
+            confirmations.push((route, confirmation_id));
+        }
+    }
+
+    /// Forgets a confirmation the ask no longer waits for, such as one of a batch that
+    /// was answered
+    pub(crate) fn finished(&self, confirmation_id: &ConfirmationId) {
+        let mut state = self
+            .in_flight
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(confirmations) = state.asks.get_mut(&self.id) {
+            confirmations.retain(|(_, id)| id != confirmation_id);
+        }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut state = self
+            .in_flight
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        state.asks.remove(&self.id);
+    }
+}
@@ -12,12 +12,16 @@
 //! - **Type-safe**: Leverages Rust's type system for compile-time safety
 //! - **Multiple answer formats**: Support for free text and multiple choice questions
 //! - **Configurable timeouts**: Optional timeout support for all requests
+//! - **WASM support**: Builds for `wasm32-unknown-unknown` for in-browser use
 //!
 //! ## Example
 //!
 //! ```no_run
 //! use wait_human::WaitHuman;
 //!
+//! # #[cfg(not(feature = "convenience"))]
+//! # fn main() {}
+//! # #[cfg(feature = "convenience")]
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     // Create a new client
@@ -46,16 +50,68 @@
 //! }
 //! ```
 
+#[cfg(not(target_arch = "wasm32"))]
+mod api;
+mod api_key;
+mod budget;
+mod builder;
+mod choices;
+mod circuit_breaker;
 mod client;
+#[cfg(feature = "debug-http")]
+mod debug_http;
 mod error;
+mod failover;
+mod in_flight;
+mod metrics;
+mod runtime;
 #[rustfmt::skip]
 mod shared_types;
+mod signing;
+mod single_flight;
+#[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+mod store;
 mod types;
+mod validation;
+mod webhook;
 
 // Public exports
+#[cfg(not(target_arch = "wasm32"))]
+pub use api::WaitHumanApi;
+pub use builder::WaitHumanBuilder;
+pub use choices::{Choice, ChoiceProvider, Choices, OrderedChoices, SearchChoice};
+pub use circuit_breaker::CircuitBreakerConfig;
 pub use client::WaitHuman;
 pub use error::{Result, WaitHumanError};
+pub use metrics::Metrics;
+/// Re-export of the HTTP client crate, for [`WaitHuman::raw_request`] and
+/// [`WaitHuman::http_client`]
+pub use reqwest;
+#[cfg(all(feature = "futures-timer", not(target_arch = "wasm32")))]
+pub use runtime::FuturesTimerSleeper;
+pub use runtime::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+pub use runtime::{Sleeper, TokioSleeper};
+/// Re-export of the secret types accepted by [`WaitHumanConfig::with_secret_api_key`]
+#[cfg(feature = "secrecy")]
+pub use secrecy;
+pub use signing::{sign_request, REQUEST_SIGNATURE_HEADER, REQUEST_TIMESTAMP_HEADER};
+#[cfg(all(feature = "store", not(target_arch = "wasm32")))]
+pub use store::{ConfirmationStore, JsonFileStore, MemoryStore, StoredConfirmation};
 pub use types::{
-    AnswerContent, AnswerFormat, AskOptions, ConfirmationAnswer, ConfirmationAnswerWithDate,
-    ConfirmationQuestion, QuestionMethod, WaitHumanConfig,
+    AnswerAttachment, AnswerContent, AnswerFormat, AnswerFormatKind, AnswerSignature, AnswerSource,
+    AnswerValidator, AnyAnswer, ApprovalProgress, AskOptions, AskStats, AskStatus, Attachment,
+    AutoAnswerRule, Base64Bytes, BinaryAnswer, BodyFormat, ChoiceOrOther, ChoiceWithComment,
+    ConfirmationAnswer, ConfirmationAnswerWithDate, ConfirmationEvent, ConfirmationId,
+    ConfirmationPage, ConfirmationQuestion, ConfirmationState, ConfirmationStatus,
+    ConfirmationSummary, ConfirmationUpdate, CreatedConfirmation, FormField, FormFieldKind,
+    HistoryEvent, HistoryEventKind, HttpClientBuilderFn, ListFilter, PartialAnswer,
+    PartialAnswerCallback, PollCallback, PollProgress, Priority, QuestionMethod, ReassignCallback,
+    RedirectPolicy, RuleOperator, WaitHumanConfig, ACKNOWLEDGE_LABEL, API_KEY_ENV, API_VERSION,
+    DEFAULT_ANSWER_ATTEMPTS, DEFAULT_MAX_BINARY_BYTES, DEFAULT_MAX_POLL_HOLD, DEFAULT_POLL_JITTER,
+    DEFAULT_USER_AGENT, ENDPOINT_ENV, LONG_POLL_WINDOW, MAX_ATTACHMENT_BYTES, MAX_TAG_LENGTH,
+    POLL_INTERVAL_ENV, SERVER_EXPIRY_MARGIN, SUPPORTED_API_VERSIONS,
+};
+pub use webhook::{
+    WebhookAnswer, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER, WEBHOOK_TOLERANCE,
 };
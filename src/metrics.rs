@@ -0,0 +1,53 @@
+use crate::error::WaitHumanError;
+use crate::types::AskStats;
+use std::time::Duration;
+
+/// Hook receiving metrics about the client's activity, to bridge to a metrics backend
+/// such as Prometheus or statsd
+///
+/// Every method has a no-op default, so implementations only override the events they
+/// record. Set it with `WaitHumanConfig::with_metrics`; without one, no metrics are
+/// collected at all. Methods run inline, so they should be cheap and must not block.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+/// use wait_human::{AskStats, Metrics, WaitHumanConfig};
+///
+/// #[derive(Default)]
+/// struct Counters {
+///     created: AtomicU64,
+///     answered: AtomicU64,
+/// }
+///
+/// impl Metrics for Counters {
+///     fn confirmation_created(&self) {
+///         self.created.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn confirmation_answered(&self, _stats: &AskStats) {
+///         self.answered.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let counters = Arc::new(Counters::default());
+/// let config = WaitHumanConfig::new("your-api-key").with_metrics(counters.clone());
+/// ```
+pub trait Metrics: Send + Sync {
+    /// A confirmation was created
+    fn confirmation_created(&self) {}
+
+    /// A poll request completed, whether or not it returned an answer
+    fn poll_completed(&self) {}
+
+    /// An ask received its answer. `stats` carries the latency and poll count
+    fn confirmation_answered(&self, _stats: &AskStats) {}
+
+    /// An ask gave up waiting after `elapsed`
+    fn confirmation_timed_out(&self, _elapsed: Duration) {}
+
+    /// An ask failed for a reason other than a timeout
+    fn ask_failed(&self, _error: &WaitHumanError) {}
+}
@@ -0,0 +1,151 @@
+//! Timer primitives for the supported targets
+//!
+//! Native builds use tokio's timer, while `wasm32` builds (browsers) use
+//! `gloo-timers` and a `performance.now()`-backed `Instant`, since neither
+//! tokio's timer nor `std::time::Instant` work there.
+//!
+//! On native targets sleeps go through tokio unless a [`Sleeper`] is configured,
+//! and clock reads always go through `tokio::time`, so tests can run under
+//! `#[tokio::test(start_paused = true)]` and advance the clock virtually instead
+//! of sleeping for real.
+
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::sleep;
+/// Point in time used for deadlines
+///
+/// This is `tokio::time::Instant` on native targets, which converts from
+/// `std::time::Instant` with `From`, and `web_time::Instant` on `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+pub use tokio::time::Instant;
+
+/// Point in time used for deadlines
+///
+/// This is `tokio::time::Instant` on native targets, which converts from
+/// `std::time::Instant` with `From`, and `web_time::Instant` on `wasm32`.
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+/// Timer the client sleeps with between polls and retries, so it can run on executors
+/// other than tokio, such as async-std or smol
+///
+/// Defaults to [`TokioSleeper`]. With the `futures-timer` feature,
+/// [`FuturesTimerSleeper`] works on any executor; other timers can be plugged in by
+/// implementing this trait. Set it with `WaitHumanConfig::with_sleeper`.
+///
+/// This only replaces the client's timers: requests still go through `reqwest`, whose
+/// connections need tokio's I/O driver, e.g. through the `async-compat` crate.
+///
+/// Not available on `wasm32`, which always uses the browser's timers.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use futures_util::future::BoxFuture;
+/// use wait_human::{Sleeper, WaitHumanConfig};
+///
+/// use wait_human::WaitHuman;
+///
+/// /// Waits a tenth of the requested time, e.g. for a demo against a test server
+/// struct FastForward;
+///
+/// impl Sleeper for FastForward {
+///     fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+///         Box::pin(tokio::time::sleep(duration / 10))
+///     }
+/// }
+///
+/// let config = WaitHumanConfig::new("your-api-key").with_sleeper(Arc::new(FastForward));
+/// let client = WaitHuman::new(config)?;
+/// # Ok::<(), wait_human::WaitHumanError>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub trait Sleeper: Send + Sync {
+    /// Returns a future that completes after `duration`
+    fn sleep(&self, duration: Duration) -> futures_util::future::BoxFuture<'static, ()>;
+}
+
+/// [`Sleeper`] using tokio's timer, the default
+///
+/// Needs a tokio runtime with the time driver enabled.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Sleeper for TokioSleeper {
+    fn sleep(&self, duration: Duration) -> futures_util::future::BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// [`Sleeper`] using the `futures-timer` crate, which runs its own timer thread and
+/// so works on any executor
+///
+/// ```
+/// use std::time::Duration;
+/// use wait_human::{FuturesTimerSleeper, Sleeper};
+///
+/// // No tokio runtime is running here
+/// futures_executor::block_on(FuturesTimerSleeper.sleep(Duration::from_millis(10)));
+/// ```
+#[cfg(all(feature = "futures-timer", not(target_arch = "wasm32")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FuturesTimerSleeper;
+
+#[cfg(all(feature = "futures-timer", not(target_arch = "wasm32")))]
+impl Sleeper for FuturesTimerSleeper {
+    fn sleep(&self, duration: Duration) -> futures_util::future::BoxFuture<'static, ()> {
+        Box::pin(futures_timer::Delay::new(duration))
+    }
+}
+
+/// Runs `future` to completion, or returns `None` if `timer` completes first
+pub(crate) async fn race_timer<F: Future>(
+    timer: impl Future<Output = ()>,
+    future: F,
+) -> Option<F::Output> {
+    use futures_util::future::{select, Either};
+
+    let future = std::pin::pin!(future);
+    let timer = std::pin::pin!(timer);
+    match select(future, timer).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+/// Runs `future` in the background, without waiting for it
+///
+/// Outside of a tokio runtime there is nothing to drive the future, so it is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn<F: Future<Output = ()> + Send + 'static>(future: F) {
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn(future);
+    }
+}
+
+/// Runs `future` in the background, without waiting for it
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn spawn<F: Future<Output = ()> + 'static>(future: F) {
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+/// Boxed future that can be shared between tasks: `Send` on native targets, where
+/// tasks may run on other threads, but not on `wasm32`, where futures never are
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type BoxFuture<T> = futures_util::future::BoxFuture<'static, T>;
+
+/// Boxed future that can be shared between tasks: `Send` on native targets, where
+/// tasks may run on other threads, but not on `wasm32`, where futures never are
+#[cfg(target_arch = "wasm32")]
+pub(crate) type BoxFuture<T> = futures_util::future::LocalBoxFuture<'static, T>;
@@ -1,6 +1,7 @@
 // This file is auto-generated by build.rs from ../app/backend/src/shared_types.rs
 // Do not edit manually - changes will be overwritten
 
+use crate::types::Base64Bytes;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -10,13 +11,52 @@ pub struct ConfirmationQuestion {
     pub method: QuestionMethod,
     pub subject: String,
     pub body: Option<String>,
+    #[serde(alias = "answerFormat")]
     pub answer_format: AnswerFormat,
 }
 
 /// The structure stored in DB for answers
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConfirmationAnswer {
+    #[serde(alias = "answerContent")]
     pub answer_content: AnswerContent,
+    /// Files the human attached to the answer
+    #[serde(default)]
+    pub attachments: Vec<AnswerAttachment>,
+    /// Electronic signature of the human, if the question required one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<AnswerSignature>,
+    /// How confident the human is in the answer, from 0.0 to 1.0, if they said
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Comment the human gave with the answer, e.g. the reason for a rejection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Electronic signature given by the human with an answer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnswerSignature {
+    /// Identity of the signer, as verified by the server
+    #[serde(alias = "signedBy")]
+    pub signed_by: String,
+    /// When the answer was signed
+    #[serde(alias = "signedAt")]
+    pub signed_at: DateTime<Utc>,
+    /// Signature value, to be kept as evidence
+    pub value: String,
+}
+
+/// A file attached to an answer, hosted by the server
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnswerAttachment {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    #[serde(alias = "contentType")]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -28,22 +68,121 @@ pub enum QuestionMethod {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConfirmationAnswerWithDate {
     pub answer: ConfirmationAnswer,
+    #[serde(alias = "answeredAt")]
     pub answered_at: DateTime<Utc>,
+    /// Metadata attached to the confirmation when it was created, if any
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    /// Who provided the answer
+    #[serde(default)]
+    #[serde(alias = "answeredBy")]
+    pub answered_by: AnswerSource,
+    /// Locale the answer UI was rendered in, if the server reports it
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Tags the confirmation was created with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The question that was asked, included on request (`AskOptions::include_question`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<ConfirmationQuestion>,
+    /// When the human's time to answer ran out, if the question set one
+    /// (`AskOptions::due_in`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "dueAt")]
+    pub due_at: Option<DateTime<Utc>>,
+    /// Who was notified, as the server resolved `AskOptions::recipient`, if it reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    /// Index in `AskOptions::auto_answer_rules` of the rule that answered, if one did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "autoAnswerRule")]
+    pub auto_answer_rule: Option<usize>,
+}
+
+/// Who provided an answer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerSource {
+    /// Answered by a human
+    Human,
+    /// Answered automatically, e.g. by a rule, before a human saw it
+    Automation,
+    /// Not reported by the server
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum AnswerFormat {
+    #[serde(alias = "freeText")]
     FreeText,
     Options {
         options: Vec<String>,
         multiple: bool,
     },
+    /// A small form of named fields, answered together
+    Form { fields: Vec<FormField> },
+    /// A single choice the human searches for, among choices the client supplies for
+    /// each query they type
+    Search,
+    /// A file the human provides, such as a signed PDF or a photo
+    Binary {
+        /// Content types accepted, e.g. `application/pdf` or `image/*`; any if empty
+        #[serde(default)]
+        #[serde(alias = "contentTypes")]
+        content_types: Vec<String>,
+    },
+}
+
+/// A field of a form answer format
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FormField {
+    /// Key of the field's value in the answer
+    pub name: String,
+    /// Label shown to the human
+    pub label: String,
+    #[serde(default)]
+    pub kind: FormFieldKind,
+    /// Whether the human must fill the field in
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Kind of value a form field takes
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FormFieldKind {
+    Text,
+    Number,
+    Boolean,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum AnswerContent {
+    #[serde(alias = "freeText")]
     FreeText { text: String },
-    Options { selected_indexes: Vec<u32> },
+    Options {
+        #[serde(alias = "selectedIndexes")]
+        selected_indexes: Vec<u32>,
+    },
+    /// The human explicitly chose not to answer, which the question allowed
+    #[serde(alias = "skipped")]
+    Abstained,
+    /// Values of a form, by field name. Fields left empty are missing or null
+    Form {
+        values: serde_json::Map<String, serde_json::Value>,
+    },
+    /// The choice selected among search results
+    Search { key: String, label: String },
+    /// Text typed in the "other" field of an options question, instead of a choice
+    Other { text: String },
+    /// A file the human provided, base64-encoded on the wire
+    Binary {
+        #[serde(alias = "contentType")]
+        content_type: String,
+        data: Base64Bytes,
+    },
 }
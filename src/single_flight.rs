@@ -0,0 +1,65 @@
+use crate::runtime::BoxFuture;
+use futures_util::future::{FutureExt, Shared, WeakShared};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Deduplicates concurrent work on the same key, shared by all clones of a client
+///
+/// The first caller for a key starts the work; callers arriving while it runs join it
+/// and all receive the same output. The map only holds weak handles, so the work is
+/// dropped once every caller waiting on it has gone away, and a caller dropping out
+/// early doesn't affect the others.
+pub(crate) struct SingleFlight<K, T> {
+    flights: Arc<Mutex<HashMap<K, WeakShared<BoxFuture<T>>>>>,
+}
+
+impl<K: Eq + Hash, T: Clone> SingleFlight<K, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            flights: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Joins the in-flight work for `key`, or starts it with `start`
+    ///
+    /// Returns the shared work and whether this call started it.
+    pub(crate) fn join<F>(&self, key: K, start: F) -> (Shared<BoxFuture<T>>, bool)
+    where
+        F: FnOnce() -> BoxFuture<T>,
+    {
+        let mut flights = self.flights.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(flight) = flights.get(&key).and_then(WeakShared::upgrade) {
+            return (flight, false);
+        }
+
+        // Forget work every caller gave up on
+        flights.retain(|_, flight| flight.upgrade().is_some());
+
+        let flight = start().shared();
+        if let Some(weak) = flight.downgrade() {
+            flights.insert(key, weak);
+        }
+        (flight, true)
+    }
+
+    /// Forgets `flight` once it has completed, so later callers start afresh rather
+    /// than receive its output
+    pub(crate) fn finish(&self, key: &K, flight: &Shared<BoxFuture<T>>) {
+        let mut flights = self.flights.lock().unwrap_or_else(|e| e.into_inner());
+
+        let current = flights.get(key).and_then(WeakShared::upgrade);
+        if current.is_some_and(|current| current.ptr_eq(flight)) {
+            flights.remove(key);
+        }
+    }
+}
+
+impl<K, T> Clone for SingleFlight<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            flights: Arc::clone(&self.flights),
+        }
+    }
+}
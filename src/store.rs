@@ -0,0 +1,239 @@
+//! Persistence of pending confirmations, so waits can resume after a restart
+//!
+//! The client saves every confirmation it creates to the configured
+//! [`ConfirmationStore`], and removes it once it is answered or ends without an answer.
+//! Confirmations still in the store after a restart are the ones to resume with
+//! `WaitHuman::wait_for_answer`.
+
+use crate::error::{Result, WaitHumanError};
+use crate::types::{ConfirmationId, ConfirmationQuestion};
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// A confirmation saved to a [`ConfirmationStore`] while waiting for its answer
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct StoredConfirmation {
+    /// Id of the confirmation
+    pub id: ConfirmationId,
+    /// The question that was asked
+    pub question: ConfirmationQuestion,
+    /// Metadata attached to the confirmation, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// When the confirmation was created
+    pub created_at: DateTime<Utc>,
+    /// When the server will expire the confirmation if nobody answers it, if it does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Storage for the confirmations the client is waiting on, set with
+/// `WaitHumanConfig::with_store`
+///
+/// [`MemoryStore`] and [`JsonFileStore`] are provided; implement this trait to keep
+/// them in a database instead. Saving a confirmation that is already stored replaces
+/// it, and removing one that isn't stored succeeds. Failures are reported as
+/// `StoreFailed`.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::future::BoxFuture;
+/// use wait_human::{ConfirmationId, ConfirmationStore, StoredConfirmation};
+///
+/// /// Discards everything, e.g. to disable persistence in tests
+/// struct NullStore;
+///
+/// impl ConfirmationStore for NullStore {
+///     fn save(&self, _: StoredConfirmation) -> BoxFuture<'_, wait_human::Result<()>> {
+///         Box::pin(async { Ok(()) })
+///     }
+///
+///     fn remove<'a>(&'a self, _: &'a ConfirmationId) -> BoxFuture<'a, wait_human::Result<()>> {
+///         Box::pin(async { Ok(()) })
+///     }
+///
+///     fn load(&self) -> BoxFuture<'_, wait_human::Result<Vec<StoredConfirmation>>> {
+///         Box::pin(async { Ok(Vec::new()) })
+///     }
+/// }
+/// ```
+pub trait ConfirmationStore: Send + Sync {
+    /// Saves a confirmation the client started waiting on
+    fn save(&self, confirmation: StoredConfirmation) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes a confirmation that no longer needs waiting on
+    fn remove<'a>(&'a self, confirmation_id: &'a ConfirmationId) -> BoxFuture<'a, Result<()>>;
+
+    /// Returns every stored confirmation, oldest first
+    fn load(&self) -> BoxFuture<'_, Result<Vec<StoredConfirmation>>>;
+}
+
+/// [`ConfirmationStore`] keeping confirmations in memory
+///
+/// Nothing survives a restart, so it is mostly useful in tests, or to list the
+/// confirmations a long-running process is waiting on.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    confirmations: Mutex<Vec<StoredConfirmation>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ConfirmationStore for MemoryStore {
+    fn save(&self, confirmation: StoredConfirmation) -> BoxFuture<'_, Result<()>> {
+        let mut confirmations = self.confirmations.lock().unwrap_or_else(|e| e.into_inner());
+        upsert(&mut confirmations, confirmation);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn remove<'a>(&'a self, confirmation_id: &'a ConfirmationId) -> BoxFuture<'a, Result<()>> {
+        let mut confirmations = self.confirmations.lock().unwrap_or_else(|e| e.into_inner());
+        confirmations.retain(|confirmation| &confirmation.id != confirmation_id);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn load(&self) -> BoxFuture<'_, Result<Vec<StoredConfirmation>>> {
+        let confirmations = self.confirmations.lock().unwrap_or_else(|e| e.into_inner());
+        let confirmations = confirmations.clone();
+        Box::pin(async { Ok(confirmations) })
+    }
+}
+
+/// [`ConfirmationStore`] keeping confirmations in a JSON file
+///
+/// The whole file is rewritten on every change, through a temporary file renamed over
+/// it, so a crash mid-write leaves the previous version intact. Meant for the handful of
+/// approvals a process waits on at a time; a missing file is an empty store. The file
+/// is read and written on tokio's blocking pool, so it doesn't stall the executor.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    file: Arc<JsonFile>,
+}
+
+impl JsonFileStore {
+    /// Creates a store backed by the file at `path`, created on the first save
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            file: Arc::new(JsonFile {
+                path: path.into(),
+                lock: Mutex::new(()),
+            }),
+        }
+    }
+}
+
+/// The file of a [`JsonFileStore`], shared with the blocking tasks accessing it
+#[derive(Debug)]
+struct JsonFile {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles within the process
+    lock: Mutex<()>,
+}
+
+impl JsonFile {
+    fn read(&self) -> Result<Vec<StoredConfirmation>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| self.failed(e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(self.failed(e)),
+        }
+    }
+
+    fn write(&self, confirmations: &[StoredConfirmation]) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(confirmations).map_err(|e| self.failed(e))?;
+        // Unique per write, so processes sharing the file don't clobber each other's
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(format!(
+            ".{}.{:016x}.tmp",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        fs::write(&temporary, bytes)
+            .and_then(|_| fs::rename(&temporary, &self.path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary);
+                self.failed(e)
+            })
+    }
+
+    /// Reads the file, applies `change` and writes the result back
+    fn update(&self, change: impl FnOnce(&mut Vec<StoredConfirmation>)) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut confirmations = self.read()?;
+        change(&mut confirmations);
+        self.write(&confirmations)
+    }
+
+    fn load(&self) -> Result<Vec<StoredConfirmation>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.read()
+    }
+
+    fn failed(&self, error: impl std::fmt::Display) -> WaitHumanError {
+        WaitHumanError::StoreFailed {
+            message: format!("{}: {}", self.path.display(), error),
+        }
+    }
+}
+
+impl ConfirmationStore for JsonFileStore {
+    fn save(&self, confirmation: StoredConfirmation) -> BoxFuture<'_, Result<()>> {
+        let file = Arc::clone(&self.file);
+        Box::pin(blocking(move || {
+            file.update(|confirmations| upsert(confirmations, confirmation))
+        }))
+    }
+
+    fn remove<'a>(&'a self, confirmation_id: &'a ConfirmationId) -> BoxFuture<'a, Result<()>> {
+        let file = Arc::clone(&self.file);
+        let confirmation_id = confirmation_id.clone();
+        Box::pin(blocking(move || {
+            file.update(|confirmations| {
+                confirmations.retain(|confirmation| confirmation.id != confirmation_id)
+            })
+        }))
+    }
+
+    fn load(&self) -> BoxFuture<'_, Result<Vec<StoredConfirmation>>> {
+        let file = Arc::clone(&self.file);
+        Box::pin(blocking(move || file.load()))
+    }
+}
+
+/// Runs the file I/O of `work` on tokio's blocking pool, or in place outside of a tokio
+/// runtime
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return work();
+    };
+    match handle.spawn_blocking(work).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(WaitHumanError::StoreFailed {
+            message: e.to_string(),
+        }),
+    }
+}
+
+/// Replaces the stored confirmation with the same id, or appends it
+fn upsert(confirmations: &mut Vec<StoredConfirmation>, confirmation: StoredConfirmation) {
+    match confirmations
+        .iter_mut()
+        .find(|stored| stored.id == confirmation.id)
+    {
+        Some(stored) => *stored = confirmation,
+        None => confirmations.push(confirmation),
+    }
+}
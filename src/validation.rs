@@ -0,0 +1,645 @@
+use crate::error::{Result, WaitHumanError};
+use crate::types::{
+    AnswerContent, AnswerFormat, AnswerSource, AskOptions, Attachment, ConfirmationAnswerWithDate,
+    ConfirmationQuestion, FormField, FormFieldKind, QuestionMethod, MAX_ATTACHMENT_BYTES,
+    MAX_TAG_LENGTH,
+};
+use serde_json::{Map, Value};
+
+/// Rejects questions the server would refuse, before any request is sent
+///
+/// Format constraints that hold for every method are checked first, then the
+/// constraints specific to the delivery method. New methods with their own limits
+/// (e.g. message length over SMS) get an arm in the method match.
+pub(crate) fn validate_question(question: &ConfirmationQuestion) -> Result<()> {
+    // Usually a subject that was never filled in, which would notify the human of a
+    // blank question
+    if question.subject.trim().is_empty() {
+        return Err(invalid("subject must not be empty"));
+    }
+    if let AnswerFormat::Options { options, .. } = &question.answer_format {
+        if options.is_empty() {
+            return Err(invalid(
+                "options answer format requires at least one option",
+            ));
+        }
+        if options.iter().any(|option| option.trim().is_empty()) {
+            return Err(invalid("options must not be empty"));
+        }
+    }
+    if let AnswerFormat::Form { fields } = &question.answer_format {
+        validate_form_fields(fields)?;
+    }
+
+    match question.method {
+        QuestionMethod::Push => Ok(()),
+    }
+}
+
+/// Rejects forms without fields, and fields with a blank or repeated name or a blank
+/// label
+fn validate_form_fields(fields: &[FormField]) -> Result<()> {
+    if fields.is_empty() {
+        return Err(invalid("form answer format requires at least one field"));
+    }
+    for (position, field) in fields.iter().enumerate() {
+        if field.name.trim().is_empty() || field.label.trim().is_empty() {
+            return Err(invalid("form field names and labels must not be empty"));
+        }
+        if fields[..position]
+            .iter()
+            .any(|other| other.name == field.name)
+        {
+            return Err(WaitHumanError::InvalidQuestion(format!(
+                "form field {:?} appears more than once",
+                field.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the values of a form answer against the form's fields
+///
+/// Every required field must have a value, null or an empty text counting as missing,
+/// and every value must be of its field's kind. Values for fields the form doesn't have
+/// are rejected.
+pub(crate) fn check_form_values(fields: &[FormField], values: &Map<String, Value>) -> Result<()> {
+    for field in fields {
+        let value = values.get(&field.name).filter(|value| {
+            !value.is_null() && value.as_str().is_none_or(|text| !text.trim().is_empty())
+        });
+        let Some(value) = value else {
+            if field.required {
+                return Err(WaitHumanError::MissingFormField {
+                    name: field.name.clone(),
+                });
+            }
+            continue;
+        };
+
+        let (fits, kind) = match field.kind {
+            FormFieldKind::Text => (value.is_string(), "text"),
+            FormFieldKind::Number => (value.is_number(), "number"),
+            FormFieldKind::Boolean => (value.is_boolean(), "boolean"),
+        };
+        if !fits {
+            return Err(WaitHumanError::InvalidResponse(format!(
+                "form field {:?} should be a {}, got {}",
+                field.name, kind, value
+            )));
+        }
+    }
+    if let Some(name) = values
+        .keys()
+        .find(|name| !fields.iter().any(|field| &&field.name == name))
+    {
+        return Err(WaitHumanError::InvalidResponse(format!(
+            "form has no field {:?}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects oversized or malformed inline attachments before anything is uploaded
+pub(crate) fn check_attachment_sizes(attachments: &[Attachment]) -> Result<()> {
+    for attachment in attachments {
+        let Attachment::Inline { name, .. } = attachment else {
+            continue;
+        };
+        match attachment.size() {
+            None => {
+                return Err(WaitHumanError::InvalidQuestion(format!(
+                    "attachment {:?} is not valid base64",
+                    name
+                )))
+            }
+            Some(size) if size > MAX_ATTACHMENT_BYTES => {
+                return Err(WaitHumanError::AttachmentTooLarge {
+                    name: name.clone(),
+                    size,
+                    max: MAX_ATTACHMENT_BYTES,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Rejects free-text length bounds that no answer could satisfy
+pub(crate) fn validate_length_bounds(options: &AskOptions) -> Result<()> {
+    if let (Some(min), Some(max)) = (options.min_length, options.max_length) {
+        if min > max {
+            return Err(invalid("min_length must not exceed max_length"));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects locales that aren't well-formed BCP-47 tags, e.g. `en` or `zh-Hant-TW`
+///
+/// Only the shape is checked (a 2-8 letter language, then 1-8 character alphanumeric
+/// subtags), not whether the server supports the locale.
+pub(crate) fn validate_locale(options: &AskOptions) -> Result<()> {
+    let Some(locale) = &options.locale else {
+        return Ok(());
+    };
+
+    let mut subtags = locale.split('-');
+    let language_ok = subtags.next().is_some_and(|language| {
+        (2..=8).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic())
+    });
+    let subtags_ok = subtags.all(|subtag| {
+        (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    if !language_ok || !subtags_ok {
+        return Err(WaitHumanError::InvalidQuestion(format!(
+            "locale {:?} is not a valid BCP-47 tag",
+            locale
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects a blank recipient
+pub(crate) fn validate_recipient(options: &AskOptions) -> Result<()> {
+    match &options.recipient {
+        Some(recipient) if recipient.trim().is_empty() => {
+            Err(invalid("recipient must not be empty"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects blank tags and tags longer than [`MAX_TAG_LENGTH`] characters
+pub(crate) fn validate_tags(options: &AskOptions) -> Result<()> {
+    for tag in &options.tags {
+        if tag.trim().is_empty() {
+            return Err(invalid("tags must not be empty"));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(WaitHumanError::InvalidQuestion(format!(
+                "tag {:?} is longer than {} characters",
+                tag, MAX_TAG_LENGTH
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects a quorum of zero approvals
+pub(crate) fn validate_required_approvals(options: &AskOptions) -> Result<()> {
+    if options.required_approvals == Some(0) {
+        return Err(invalid("required_approvals must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Rejects searchable questions asked without a provider of their choices
+pub(crate) fn validate_choice_provider(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+) -> Result<()> {
+    if matches!(question.answer_format, AnswerFormat::Search) && options.choice_provider.is_none() {
+        return Err(invalid(
+            "a search answer format needs AskOptions::choice_provider",
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects disabled choices and choice help that don't point at a choice of the
+/// question, and questions whose every choice is disabled
+pub(crate) fn validate_choice_states(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+) -> Result<()> {
+    let indexes = options
+        .disabled_choices
+        .iter()
+        .chain(options.choice_help.iter().map(|(index, _)| index))
+        .chain(&options.require_comment_on);
+
+    let count = match &question.answer_format {
+        AnswerFormat::Options { options, .. } => options.len(),
+        AnswerFormat::FreeText
+        | AnswerFormat::Form { .. }
+        | AnswerFormat::Search
+        | AnswerFormat::Binary { .. } => {
+            if options.allow_other {
+                return Err(invalid("allow_other needs an options answer format"));
+            }
+            return match indexes.count() {
+                0 => Ok(()),
+                _ => Err(invalid(
+                    "disabled choices, choice help and required comments need an options answer format",
+                )),
+            };
+        }
+    };
+
+    for index in indexes {
+        if choice_index(*index, count).is_err() {
+            return Err(WaitHumanError::InvalidQuestion(format!(
+                "choice index {} is out of range",
+                index
+            )));
+        }
+    }
+    let disabled =
+        |index| u32::try_from(index).is_ok_and(|index| options.disabled_choices.contains(&index));
+    if (0..count).all(disabled) {
+        return Err(invalid("at least one choice must be enabled"));
+    }
+    if let Some(default) = &options.default_on_timeout {
+        if check_enabled_selection(default, &options.disabled_choices).is_err() {
+            return Err(invalid("default_on_timeout selects a disabled choice"));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `content_type` is accepted by `accepted`, a content type such as
+/// `application/pdf` or a wildcard such as `image/*`, ignoring case and parameters
+pub(crate) fn content_type_matches(accepted: &str, content_type: &str) -> bool {
+    let essence = |content_type: &str| {
+        content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+    let (accepted, content_type) = (essence(accepted), essence(content_type));
+    if accepted == "*/*" {
+        return true;
+    }
+    match accepted.strip_suffix("/*") {
+        Some(kind) => content_type
+            .split_once('/')
+            .is_some_and(|(content_kind, _)| content_kind == kind),
+        None => accepted == content_type,
+    }
+}
+
+/// Rejects options answers that select a disabled choice
+pub(crate) fn check_enabled_selection(content: &AnswerContent, disabled: &[u32]) -> Result<()> {
+    if let AnswerContent::Options { selected_indexes } = content {
+        if let Some(index) = selected_indexes
+            .iter()
+            .find(|index| disabled.contains(index))
+        {
+            return Err(WaitHumanError::DisabledChoiceSelected { index: *index });
+        }
+    }
+    Ok(())
+}
+
+/// Rejects answers selecting a disabled choice, or a choice requiring a comment
+/// without one, "other" answers to questions not offering the field, files larger
+/// than `AskOptions::max_binary_bytes` and free text [`check_free_text`] rejects
+///
+/// Answers filled in by the client, from `default_on_timeout`, need no comment.
+pub(crate) fn check_selection(
+    answer: &ConfirmationAnswerWithDate,
+    options: &AskOptions,
+) -> Result<()> {
+    check_enabled_selection(answer.content(), &options.disabled_choices)?;
+    if matches!(answer.content(), AnswerContent::Other { .. }) && !options.allow_other {
+        return Err(WaitHumanError::InvalidResponse(
+            "\"other\" answer to a question that doesn't offer one".to_string(),
+        ));
+    }
+    if let AnswerContent::FreeText { text } = answer.content() {
+        check_free_text(
+            text,
+            options.min_length,
+            options.max_length,
+            options.reject_empty,
+        )?;
+    }
+    if let Some((_, data)) = answer.as_binary() {
+        if data.len() > options.binary_limit() {
+            return Err(WaitHumanError::BinaryAnswerTooLarge {
+                size: data.len(),
+                max: options.binary_limit(),
+            });
+        }
+    }
+    if matches!(answer.answered_by, AnswerSource::Automation)
+        || answer
+            .comment()
+            .is_some_and(|comment| !comment.trim().is_empty())
+    {
+        return Ok(());
+    }
+    if let Some(index) = answer
+        .selected_indexes()
+        .unwrap_or_default()
+        .iter()
+        .find(|index| options.require_comment_on.contains(index))
+    {
+        return Err(WaitHumanError::MissingComment { index: *index });
+    }
+    Ok(())
+}
+
+/// Rejects a `default_on_timeout` answer that doesn't fit the question's answer format
+pub(crate) fn validate_default_answer(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+) -> Result<()> {
+    match &options.default_on_timeout {
+        Some(default) => check_preset_answer(question, options, default, "default_on_timeout"),
+        None => Ok(()),
+    }
+}
+
+/// Rejects auto-answer rules the server couldn't apply: blank fields, ordering
+/// comparisons with something other than a number, and answers that don't fit the
+/// question's answer format
+pub(crate) fn validate_auto_answer_rules(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+) -> Result<()> {
+    for (index, rule) in options.auto_answer_rules.iter().enumerate() {
+        let name = format!("auto_answer_rules[{}]", index);
+        if rule.field.trim().is_empty() {
+            return Err(invalid(&format!("{} compares an empty field", name)));
+        }
+        if rule.operator.is_ordering() && !rule.value.is_number() {
+            return Err(invalid(&format!(
+                "{} orders {} against a value that isn't a number",
+                name, rule.field
+            )));
+        }
+        check_preset_answer(question, options, &rule.answer, &name)?;
+    }
+    Ok(())
+}
+
+/// Rejects an answer set up front, named `name` in errors, that doesn't fit the
+/// question's answer format
+fn check_preset_answer(
+    question: &ConfirmationQuestion,
+    options: &AskOptions,
+    answer: &AnswerContent,
+    name: &str,
+) -> Result<()> {
+    match (&question.answer_format, answer) {
+        (AnswerFormat::FreeText, AnswerContent::FreeText { text }) => check_free_text(
+            text,
+            options.min_length,
+            options.max_length,
+            options.reject_empty,
+        )
+        .map_err(|_| {
+            invalid(&format!(
+                "{} is blank or outside the min_length and max_length bounds",
+                name
+            ))
+        }),
+        (_, AnswerContent::Abstained) if options.allow_abstain => Ok(()),
+        (
+            AnswerFormat::Options { options, multiple },
+            AnswerContent::Options { selected_indexes },
+        ) => {
+            if selected_indexes.is_empty() || (!multiple && selected_indexes.len() > 1) {
+                return Err(invalid(&format!(
+                    "{} selects the wrong number of options",
+                    name
+                )));
+            }
+            if selected_indexes
+                .iter()
+                .any(|index| choice_index(*index, options.len()).is_err())
+            {
+                return Err(invalid(&format!("{} selects an index out of range", name)));
+            }
+            Ok(())
+        }
+        (AnswerFormat::Form { fields }, AnswerContent::Form { values }) => {
+            check_form_values(fields, values)
+                .map_err(|e| WaitHumanError::InvalidQuestion(format!("{}: {}", name, e)))
+        }
+        _ => Err(invalid(&format!(
+            "{} doesn't match the question's answer format",
+            name
+        ))),
+    }
+}
+
+/// Rejects options that set both a relative timeout and an absolute deadline, or a
+/// zero `due_in`
+pub(crate) fn validate_timeout(options: &AskOptions) -> Result<()> {
+    if options.effective_timeout().is_some() && options.deadline.is_some() {
+        return Err(invalid("timeout and deadline are mutually exclusive"));
+    }
+    if options.due_in.is_some_and(|due_in| due_in.is_zero()) {
+        return Err(invalid("due_in must be positive"));
+    }
+    if options.max_polls == Some(0) {
+        return Err(invalid("max_polls must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Rejects answers no question could accept, before they are submitted
+///
+/// Whether the answer matches the question's format can only be checked by the server.
+pub(crate) fn validate_answer(content: &AnswerContent) -> Result<()> {
+    if let AnswerContent::Options { selected_indexes } = content {
+        if selected_indexes.is_empty() {
+            return Err(WaitHumanError::InvalidAnswer(
+                "options answer requires at least one selected index".to_string(),
+            ));
+        }
+        let mut sorted = selected_indexes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != selected_indexes.len() {
+            return Err(WaitHumanError::InvalidAnswer(
+                "selected indexes must not repeat".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the only selected index of a single-choice answer
+pub(crate) fn single_selection(selected_indexes: &[u32]) -> Result<u32> {
+    match selected_indexes {
+        [] => Err(WaitHumanError::InvalidResponse(
+            "No selection received".to_string(),
+        )),
+        [index] => Ok(*index),
+        _ => Err(WaitHumanError::MultipleSelections {
+            count: selected_indexes.len(),
+        }),
+    }
+}
+
+/// Maps a selected index to its position in a list of `len` choices
+///
+/// Fails with `InvalidSelectedIndex` if it is out of range, including an index that
+/// doesn't fit in a `usize` on 16-bit targets.
+pub(crate) fn choice_index(index: u32, len: usize) -> Result<usize> {
+    usize::try_from(index)
+        .ok()
+        .filter(|position| *position < len)
+        .ok_or(WaitHumanError::InvalidSelectedIndex { index })
+}
+
+/// Rejects options answers that select the same index more than once
+pub(crate) fn check_distinct_selection(selected_indexes: &[u32]) -> Result<()> {
+    for (position, index) in selected_indexes.iter().enumerate() {
+        if selected_indexes[..position].contains(index) {
+            return Err(WaitHumanError::DuplicateSelectedIndex { index: *index });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the content of `answer` once checked against `format`
+///
+/// With `coerce`, an answer of another kind is converted with
+/// [`ConfirmationAnswerWithDate::coerce_to`] when possible, instead of failing with
+/// `UnexpectedAnswerType`.
+pub(crate) fn content_for(
+    answer: ConfirmationAnswerWithDate,
+    format: &AnswerFormat,
+    coerce: bool,
+) -> Result<AnswerContent> {
+    match answer.content().check_format(format) {
+        Ok(()) => Ok(answer.answer.answer_content),
+        Err(e @ WaitHumanError::UnexpectedAnswerType { .. }) if coerce => {
+            let content = answer.coerce_to(format).ok_or(e)?;
+            content.check_format(format)?;
+            Ok(content)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// The checks of a free-text answer, taken from the options of its ask before they are
+/// handed over to it
+///
+/// Shared by `WaitHuman::ask_free_text` and the default `WaitHumanApi::ask_free_text`,
+/// so an implementation only providing `ask` checks answers the same way.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FreeTextChecks {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    reject_empty: bool,
+    coerce_answers: bool,
+}
+
+impl FreeTextChecks {
+    pub(crate) fn new(options: Option<&AskOptions>) -> Self {
+        options.map_or_else(Self::default, |options| Self {
+            min_length: options.min_length,
+            max_length: options.max_length,
+            reject_empty: options.reject_empty,
+            coerce_answers: options.coerce_answers,
+        })
+    }
+
+    /// Returns the text of `answer`, converted from another kind with
+    /// `coerce_answers`, once checked against the length bounds and `reject_empty`
+    pub(crate) fn text_of(&self, answer: ConfirmationAnswerWithDate) -> Result<String> {
+        let AnswerContent::FreeText { text } =
+            content_for(answer, &AnswerFormat::FreeText, self.coerce_answers)?
+        else {
+            unreachable!("checked by content_for");
+        };
+        check_free_text(&text, self.min_length, self.max_length, self.reject_empty)?;
+        Ok(text)
+    }
+}
+
+/// Checks a received free-text answer against the requested length bounds and, with
+/// `reject_empty`, that it isn't blank
+pub(crate) fn check_free_text(
+    text: &str,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    reject_empty: bool,
+) -> Result<()> {
+    if reject_empty {
+        check_answer_not_empty(text)?;
+    }
+    check_answer_length(text, min_length, max_length)
+}
+
+/// Checks a received free-text answer against the requested length bounds
+fn check_answer_length(
+    text: &str,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+) -> Result<()> {
+    let length = text.chars().count();
+
+    if let Some(min) = min_length.filter(|min| length < *min) {
+        return Err(WaitHumanError::InvalidResponse(format!(
+            "answer is {} characters, shorter than the minimum of {}",
+            length, min
+        )));
+    }
+    if let Some(max) = max_length.filter(|max| length > *max) {
+        return Err(WaitHumanError::InvalidResponse(format!(
+            "answer is {} characters, longer than the maximum of {}",
+            length, max
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a received free-text answer has more than whitespace in it
+fn check_answer_not_empty(text: &str) -> Result<()> {
+    if text.trim().is_empty() {
+        return Err(WaitHumanError::InvalidResponse(
+            "answer is empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects a blank phrase for `ask_confirm_phrase`, which an empty answer would match
+#[cfg(feature = "convenience")]
+pub(crate) fn validate_phrase(expected_phrase: &str) -> Result<()> {
+    if expected_phrase.trim().is_empty() {
+        return Err(invalid("expected_phrase must not be blank"));
+    }
+    Ok(())
+}
+
+/// Compares a typed answer with the phrase it must match, optionally ignoring case and
+/// surrounding or repeated whitespace
+#[cfg(feature = "convenience")]
+pub(crate) fn phrase_matches(
+    answer: &str,
+    expected_phrase: &str,
+    ignore_case: bool,
+    ignore_whitespace: bool,
+) -> bool {
+    let normalize = |text: &str| {
+        let text = if ignore_whitespace {
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            text.to_string()
+        };
+        if ignore_case {
+            text.to_lowercase()
+        } else {
+            text
+        }
+    };
+    normalize(answer) == normalize(expected_phrase)
+}
+
+fn invalid(message: &str) -> WaitHumanError {
+    WaitHumanError::InvalidQuestion(message.to_string())
+}
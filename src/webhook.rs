@@ -0,0 +1,76 @@
+use crate::error::{Result, WaitHumanError};
+use crate::types::{ConfirmationAnswerWithDate, ConfirmationId};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+/// Header carrying the signature of a webhook request, as `v1=<hex HMAC-SHA256>`
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "WaitHuman-Signature";
+
+/// Header carrying the Unix time, in seconds, at which a webhook request was signed
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "WaitHuman-Timestamp";
+
+/// How far the timestamp of a webhook request may be from the current time. Older
+/// requests are rejected as possible replays
+pub const WEBHOOK_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// An answer delivered by webhook, as returned by `WaitHuman::verify_webhook`
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct WebhookAnswer {
+    /// Id of the answered confirmation
+    #[serde(rename = "confirmation_request_id", alias = "confirmationRequestId")]
+    pub confirmation_id: ConfirmationId,
+    /// The answer
+    pub answer: ConfirmationAnswerWithDate,
+}
+
+/// Verifies the signature and freshness of a webhook request, then parses its body
+///
+/// The signature is an HMAC-SHA256, keyed with the shared secret, of the timestamp
+/// and the raw body joined by a `.`.
+pub(crate) fn verify(
+    secret: &str,
+    body: &[u8],
+    signature: &str,
+    timestamp: &str,
+) -> Result<WebhookAnswer> {
+    let signed_at: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| invalid(format!("malformed timestamp {:?}", timestamp)))?;
+    let age = Utc::now().timestamp().abs_diff(signed_at);
+    if age > WEBHOOK_TOLERANCE.as_secs() {
+        return Err(invalid(format!(
+            "timestamp is {}s off, outside the {}s tolerance",
+            age,
+            WEBHOOK_TOLERANCE.as_secs()
+        )));
+    }
+
+    let signature = signature
+        .trim()
+        .strip_prefix("v1=")
+        .and_then(|hex| hex::decode(hex).ok())
+        .ok_or_else(|| invalid("malformed signature".to_string()))?;
+
+    // Any key length is accepted by HMAC
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| {
+        WaitHumanError::InvalidConfig {
+            field: "webhook_secret",
+            message: format!("invalid webhook secret: {}", e),
+        }
+    })?;
+    mac.update(timestamp.trim().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    // Constant-time comparison
+    mac.verify_slice(&signature)
+        .map_err(|_| invalid("signature mismatch".to_string()))?;
+
+    serde_json::from_slice(body).map_err(|e| invalid(format!("malformed body: {}", e)))
+}
+
+fn invalid(message: String) -> WaitHumanError {
+    WaitHumanError::InvalidWebhook(message)
+}
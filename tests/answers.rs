@@ -0,0 +1,1054 @@
+//! Submitting answers, and rejecting malformed answers from the server
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use std::collections::BTreeMap;
+use wait_human::{
+    AnswerContent, BinaryAnswer, Choice, ChoiceOrOther, ChoiceWithComment, Choices, ConfirmationId,
+    FormField, SearchChoice, WaitHumanError,
+};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn answer_path() -> String {
+    format!("/confirmations/answer/{}", CONFIRMATION_ID)
+}
+
+fn confirmation_id() -> ConfirmationId {
+    CONFIRMATION_ID.parse().expect("valid id")
+}
+
+/// Expects one answer with `body` to be submitted
+async fn expect_answer(server: &MockServer, body: serde_json::Value) {
+    Mock::given(method("POST"))
+        .and(path(answer_path()))
+        .and(header("Authorization", API_KEY))
+        .and(body_json(body))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn submits_a_free_text_answer() {
+    let server = MockServer::start().await;
+    expect_answer(
+        &server,
+        json!({
+            "answer_content": { "type": "free_text", "text": "ship it" },
+            "attachments": [],
+        }),
+    )
+    .await;
+
+    client(&server)
+        .submit_answer(
+            &confirmation_id(),
+            AnswerContent::FreeText {
+                text: "ship it".to_string(),
+            },
+        )
+        .await
+        .expect("submitted");
+}
+
+#[tokio::test]
+async fn submits_an_options_answer() {
+    let server = MockServer::start().await;
+    expect_answer(
+        &server,
+        json!({
+            "answer_content": { "type": "options", "selected_indexes": [0, 2] },
+            "attachments": [],
+        }),
+    )
+    .await;
+
+    client(&server)
+        .submit_answer(
+            &confirmation_id(),
+            AnswerContent::Options {
+                selected_indexes: vec![0, 2],
+            },
+        )
+        .await
+        .expect("submitted");
+}
+
+#[tokio::test]
+async fn reports_an_answer_the_server_rejects_as_invalid() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(answer_path()))
+        .respond_with(ResponseTemplate::new(422).set_body_string("expected options"))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .submit_answer(
+            &confirmation_id(),
+            AnswerContent::FreeText {
+                text: "ship it".to_string(),
+            },
+        )
+        .await;
+
+    match result {
+        Err(WaitHumanError::InvalidAnswer(message)) => assert_eq!(message, "expected options"),
+        other => panic!("expected InvalidAnswer, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rejects_several_selections_for_a_single_choice_question() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[0, 1]))).await;
+
+    let result = client(&server)
+        .ask_multiple_choice("Deploy?", ["Yes", "No"], None::<&str>, Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::MultipleSelections { count: 2 })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_duplicate_selection_for_a_multiple_choice_question() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1, 1]))).await;
+
+    let question = options_question("Environments?", &["Staging", "Production"], true);
+    let answer = client(&server)
+        .ask(question.clone(), Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert!(matches!(
+        answer.check_format(&question),
+        Err(WaitHumanError::DuplicateSelectedIndex { index: 1 })
+    ));
+}
+
+#[tokio::test]
+async fn rejects_an_answer_selecting_a_disabled_choice() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let result = client(&server)
+        .ask_choice_value(
+            "Replicas?",
+            [Choice::new("3", 3), Choice::new("5", 5).disabled()],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::DisabledChoiceSelected { index: 1 })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_free_text_answer_outside_the_length_bounds() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Why?"),
+            Some(fast_options().with_min_length(10)),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_blank_free_text_answer_when_asked_to() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("  "))).await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Why?"),
+            Some(fast_options().with_reject_empty()),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_default_answer_outside_the_length_bounds_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Why?"),
+            Some(fast_options().with_max_length(3).with_default_on_timeout(
+                AnswerContent::FreeText {
+                    text: "no reason given".to_string(),
+                },
+            )),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidQuestion(_))),
+        "{:?}",
+        result
+    );
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+/// Body of a confirmation answered by selecting `index`, with `comment`
+fn commented(index: u32, comment: &str) -> serde_json::Value {
+    json!({
+        "maybe_answer": {
+            "answer": { "answer_content": selected(&[index]), "comment": comment },
+            "answered_at": "2024-05-01T12:00:00Z",
+        },
+    })
+}
+
+#[tokio::test]
+async fn returns_the_comment_given_with_a_choice() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, commented(1, "Tests are flaky")).await;
+
+    let review = client(&server)
+        .ask_multiple_choice_with_comment(
+            "Merge?",
+            ["Approve", "Reject"],
+            None::<&str>,
+            Some(fast_options().with_comment_required_on(1)),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(
+        review,
+        ChoiceWithComment {
+            choice: "Reject".to_string(),
+            index: 1,
+            comment: Some("Tests are flaky".to_string()),
+        }
+    );
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["allow_comment"], json!(true));
+    assert_eq!(body["require_comment_on"], json!([1]));
+}
+
+#[tokio::test]
+async fn rejects_a_choice_requiring_a_comment_answered_with_a_blank_one() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, commented(1, "  ")).await;
+
+    let result = client(&server)
+        .ask_multiple_choice_with_comment(
+            "Merge?",
+            ["Approve", "Reject"],
+            None::<&str>,
+            Some(fast_options().with_comment_required_on(1)),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::MissingComment { index: 1 })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn returns_the_text_typed_as_other() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let other = json!({ "type": "other", "text": "Disk full" });
+    mount_poll(&server, answered(other)).await;
+
+    let reason = client(&server)
+        .ask_choice_or_other(
+            "Why roll back?",
+            ["Health checks", "Error rate"],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(reason, ChoiceOrOther::Other("Disk full".to_string()));
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["allow_other"], json!(true));
+}
+
+#[tokio::test]
+async fn returns_the_index_of_a_predefined_choice_when_other_is_offered() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let reason = client(&server)
+        .ask_choice_or_other(
+            "Why roll back?",
+            ["Health checks", "Error rate"],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(reason, ChoiceOrOther::Predefined(1));
+}
+
+#[tokio::test]
+async fn rejects_an_other_answer_to_a_question_not_offering_one() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let other = json!({ "type": "other", "text": "Disk full" });
+    mount_poll(&server, answered(other)).await;
+
+    let result = client(&server)
+        .ask_multiple_choice(
+            "Why roll back?",
+            ["Health checks", "Error rate"],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        result
+    );
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert!(body.get("allow_other").is_none());
+}
+
+#[tokio::test]
+async fn an_abstention_is_none_when_allowed() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(json!({ "type": "abstained" }))).await;
+
+    let answer = client(&server)
+        .ask_free_text_or_abstain("Any concerns?", None::<&str>, Some(fast_options()))
+        .await
+        .expect("abstained");
+
+    assert_eq!(answer, None);
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["allow_abstain"], json!(true));
+}
+
+#[tokio::test]
+async fn an_abstention_fails_a_typed_ask() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(json!({ "type": "abstained" }))).await;
+
+    let result = client(&server)
+        .ask_multiple_choice(
+            "Merge?",
+            ["Merge", "Close"],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await;
+
+    match result {
+        Err(WaitHumanError::Abstained { confirmation_id }) => {
+            assert_eq!(confirmation_id, Some(self::confirmation_id()));
+        }
+        other => panic!("expected Abstained, got {:?}", other),
+    }
+}
+
+/// Asks to type `expected` and answers with `typed`
+async fn confirm_phrase(expected: &str, typed: &str, options: wait_human::AskOptions) -> bool {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text(typed))).await;
+
+    client(&server)
+        .ask_confirm_phrase(
+            "Type the database name to drop it",
+            expected,
+            None::<&str>,
+            Some(options),
+        )
+        .await
+        .expect("asked")
+}
+
+#[tokio::test]
+async fn confirms_a_phrase_only_on_an_exact_match() {
+    assert!(confirm_phrase("orders-prod", "orders-prod", fast_options()).await);
+    assert!(!confirm_phrase("orders-prod", "orders-dev", fast_options()).await);
+    assert!(!confirm_phrase("orders-prod", " orders-prod ", fast_options()).await);
+    assert!(!confirm_phrase("orders-prod", "Orders-Prod", fast_options()).await);
+}
+
+#[tokio::test]
+async fn confirms_a_phrase_ignoring_whitespace_if_asked() {
+    let options = || fast_options().with_phrase_ignore_whitespace();
+
+    assert!(confirm_phrase("drop orders", "  drop   orders\n", options()).await);
+    assert!(!confirm_phrase("drop orders", "droporders", options()).await);
+    assert!(!confirm_phrase("drop orders", "Drop orders", options()).await);
+}
+
+#[tokio::test]
+async fn confirms_a_phrase_ignoring_case_if_asked() {
+    let options = || fast_options().with_phrase_ignore_case();
+
+    assert!(confirm_phrase("Orders-Prod", "orders-PROD", options()).await);
+    assert!(!confirm_phrase("Orders-Prod", "orders-prod ", options()).await);
+}
+
+#[tokio::test]
+async fn rejects_a_blank_phrase_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask_confirm_phrase("Confirm", "  ", None::<&str>, Some(fast_options()))
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+fn scaling_form() -> Vec<FormField> {
+    vec![
+        FormField::number("replicas", "Replicas").required(),
+        FormField::text("reason", "Reason"),
+    ]
+}
+
+/// Asks the scaling form and answers with `values`
+async fn ask_scaling(
+    values: serde_json::Value,
+) -> wait_human::Result<serde_json::Map<String, serde_json::Value>> {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(
+        &server,
+        answered(json!({ "type": "form", "values": values })),
+    )
+    .await;
+
+    client(&server)
+        .ask_form(
+            "Scale the cluster?",
+            scaling_form(),
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn sends_the_fields_of_a_form() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(
+        &server,
+        answered(json!({ "type": "form", "values": { "replicas": 3 } })),
+    )
+    .await;
+
+    let values = client(&server)
+        .ask_form(
+            "Scale the cluster?",
+            scaling_form(),
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(values["replicas"], 3);
+    let create = &requests_to(&server, "/confirmations/create").await[0];
+    let body: serde_json::Value = create.body_json().unwrap();
+    assert_eq!(
+        body["question"]["answer_format"],
+        json!({
+            "type": "form",
+            "fields": [
+                { "name": "replicas", "label": "Replicas", "kind": "number", "required": true },
+                { "name": "reason", "label": "Reason", "kind": "text", "required": false },
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn deserializes_a_form_answer() {
+    #[derive(serde::Deserialize)]
+    struct Scaling {
+        replicas: u32,
+        reason: Option<String>,
+    }
+
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(
+        &server,
+        answered(json!({ "type": "form", "values": { "replicas": 3, "reason": "launch" } })),
+    )
+    .await;
+
+    let scaling: Scaling = client(&server)
+        .ask_form_as(
+            "Scale the cluster?",
+            scaling_form(),
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(scaling.replicas, 3);
+    assert_eq!(scaling.reason.as_deref(), Some("launch"));
+}
+
+#[tokio::test]
+async fn rejects_a_form_answer_missing_a_required_field() {
+    for values in [json!({ "reason": "launch" }), json!({ "replicas": null })] {
+        let result = ask_scaling(values).await;
+
+        assert!(
+            matches!(&result, Err(WaitHumanError::MissingFormField { name }) if name == "replicas"),
+            "{:?}",
+            result
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_form_values_that_dont_fit_their_fields() {
+    for values in [
+        json!({ "replicas": "three" }),
+        json!({ "replicas": 3, "reason": 42 }),
+        json!({ "replicas": 3, "region": "eu" }),
+    ] {
+        let result = ask_scaling(values).await;
+
+        assert!(
+            matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+            "{:?}",
+            result
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_a_form_with_repeated_field_names_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask_form(
+            "Scale the cluster?",
+            vec![
+                FormField::number("replicas", "Replicas"),
+                FormField::text("replicas", "Replicas again"),
+            ],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn maps_a_selected_choice_back_to_its_key_in_map_order() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let environments = BTreeMap::from([(42, "Production"), (17, "Staging")]);
+    let environment_id = client(&server)
+        .ask_multiple_choice_map(
+            "Deploy to?",
+            &environments,
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(environment_id, 42);
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(
+        body["question"]["answer_format"]["options"],
+        json!(["Staging", "Production"])
+    );
+}
+
+fn email_options() -> wait_human::AskOptions {
+    fast_options().with_validator(|answer| match answer.as_free_text() {
+        Some(text) if text.contains('@') => Ok(()),
+        _ => Err("Please enter an email address".to_string()),
+    })
+}
+
+#[tokio::test]
+async fn asks_again_with_the_validation_message_until_an_answer_passes() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(format!("/confirmations/get/{}", CONFIRMATION_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(answered(free_text("ops"))))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ops@example.com"))).await;
+
+    let email = client(&server)
+        .ask_free_text(
+            "On-call email?",
+            Some("Who gets paged tonight"),
+            Some(email_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(email, "ops@example.com");
+    let requests = requests_to(&server, "/confirmations/create").await;
+    assert_eq!(requests.len(), 2);
+    let followup: serde_json::Value = requests[1].body_json().expect("JSON body");
+    assert_eq!(followup["parent_id"], json!(CONFIRMATION_ID));
+    assert_eq!(
+        followup["question"]["body"],
+        json!("Please enter an email address\n\nWho gets paged tonight")
+    );
+}
+
+#[tokio::test]
+async fn fails_once_every_attempt_is_rejected() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ops"))).await;
+
+    let result = client(&server)
+        .ask_free_text(
+            "On-call email?",
+            None::<&str>,
+            Some(email_options().with_max_answer_attempts(2)),
+        )
+        .await;
+
+    match result {
+        Err(WaitHumanError::AnswerRejected {
+            reason, attempts, ..
+        }) => {
+            assert_eq!(reason, "Please enter an email address");
+            assert_eq!(attempts, 2);
+        }
+        other => panic!("expected AnswerRejected, got {:?}", other),
+    }
+    assert_eq!(requests_to(&server, "/confirmations/create").await.len(), 2);
+}
+
+#[tokio::test]
+async fn rejects_an_answer_of_another_kind_by_default() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[0]))).await;
+
+    let result = client(&server)
+        .ask_free_text("Release notes?", None::<&str>, Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::UnexpectedAnswerType { .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn coerces_selected_options_into_their_labels() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let mut body = answered(selected(&[0, 2]));
+    body["maybe_answer"]["question"] = json!({
+        "method": { "type": "push" },
+        "subject": "Release notes?",
+        "answer_format": {
+            "type": "options",
+            "options": ["Fixes", "Features", "Docs"],
+            "multiple": true,
+        },
+    });
+    mount_poll(&server, body).await;
+
+    let text = client(&server)
+        .ask_free_text(
+            "Release notes?",
+            None::<&str>,
+            Some(fast_options().with_coerce_answers()),
+        )
+        .await
+        .expect("coerced");
+
+    assert_eq!(text, "Fixes, Docs");
+}
+
+#[tokio::test]
+async fn coerces_selected_options_into_their_indexes_without_labels() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let text = client(&server)
+        .ask_free_text(
+            "Release notes?",
+            None::<&str>,
+            Some(fast_options().with_coerce_answers()),
+        )
+        .await
+        .expect("coerced");
+
+    assert_eq!(text, "1");
+}
+
+#[tokio::test]
+async fn coerces_a_form_into_one_line_per_field() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let form = json!({ "type": "form", "values": { "owner": "ops", "replicas": 3 } });
+    mount_poll(&server, answered(form)).await;
+
+    let text = client(&server)
+        .ask_free_text(
+            "Release notes?",
+            None::<&str>,
+            Some(fast_options().with_coerce_answers()),
+        )
+        .await
+        .expect("coerced");
+
+    assert_eq!(text, "owner: ops\nreplicas: 3");
+}
+
+#[tokio::test]
+async fn coerces_free_text_matching_a_choice_into_its_selection() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("  production "))).await;
+
+    let choice = client(&server)
+        .ask_multiple_choice(
+            "Deploy to?",
+            ["Staging", "Production"],
+            None::<&str>,
+            Some(fast_options().with_coerce_answers()),
+        )
+        .await
+        .expect("coerced");
+
+    assert_eq!(choice, "Production");
+}
+
+#[tokio::test]
+async fn keeps_free_text_matching_no_choice_an_unexpected_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("both"))).await;
+
+    let result = client(&server)
+        .ask_multiple_choice(
+            "Deploy to?",
+            ["Staging", "Production"],
+            None::<&str>,
+            Some(fast_options().with_coerce_answers()),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::UnexpectedAnswerType { .. })),
+        "{:?}",
+        result
+    );
+}
+
+/// Choices whose third variant was added without a label
+#[derive(Debug)]
+enum Stage {
+    Build,
+    Test,
+    Release,
+}
+
+impl Choices for Stage {
+    fn labels() -> Vec<String> {
+        vec!["Build".to_string(), "Test".to_string()]
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        [Stage::Build, Stage::Test, Stage::Release]
+            .into_iter()
+            .nth(index)
+    }
+}
+
+#[test]
+fn reports_choices_with_a_value_past_their_labels() {
+    assert!(matches!(
+        Stage::check_labels(),
+        Err(WaitHumanError::InvalidQuestion(_))
+    ));
+}
+
+#[tokio::test]
+#[should_panic(expected = "inconsistent Choices impl")]
+async fn panics_on_inconsistent_choices_in_debug_builds() {
+    let server = MockServer::start().await;
+
+    let _ = client(&server)
+        .ask_choice::<Stage, _, _>("Restart from?", None::<&str>, Some(fast_options()))
+        .await;
+}
+
+#[tokio::test]
+async fn answers_the_searches_of_a_searchable_choice() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "maybe_answer": null,
+            "search_queries": [{ "id": "q1", "query": "ac" }, { "id": "q2", "query": "zz" }],
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(
+        &server,
+        answered(json!({ "type": "search", "key": "cus_1", "label": "Acme" })),
+    )
+    .await;
+    let search_path = format!("/confirmations/search/{}", CONFIRMATION_ID);
+    Mock::given(method("POST"))
+        .and(path(search_path.as_str()))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let customer = client(&server)
+        .ask_searchable_choice(
+            "Which customer?",
+            |query: String| async move {
+                match query.as_str() {
+                    "ac" => Ok(vec![SearchChoice::new("cus_1", "Acme")]),
+                    _ => Err("No customer matches".to_string()),
+                }
+            },
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(customer, SearchChoice::new("cus_1", "Acme"));
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(
+        body["question"]["answer_format"],
+        json!({ "type": "search" })
+    );
+    let results: Vec<serde_json::Value> = requests_to(&server, &search_path)
+        .await
+        .iter()
+        .map(|request| request.body_json().expect("JSON body"))
+        .collect();
+    assert_eq!(
+        results,
+        [
+            json!({ "query_id": "q1", "choices": [{ "key": "cus_1", "label": "Acme" }] }),
+            json!({ "query_id": "q2", "choices": [], "error": "No customer matches" }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_search_question_without_a_choice_provider() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let question = wait_human::ConfirmationQuestion {
+        method: wait_human::QuestionMethod::Push,
+        subject: "Which customer?".to_string(),
+        body: None,
+        answer_format: wait_human::AnswerFormat::Search,
+    };
+    let result = client(&server).ask(question, Some(fast_options())).await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidQuestion(_))),
+        "{:?}",
+        result
+    );
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+/// Body of a confirmation answered with a file
+fn uploaded(content_type: &str, data: &str) -> serde_json::Value {
+    answered(json!({ "type": "binary", "content_type": content_type, "data": data }))
+}
+
+#[tokio::test]
+async fn decodes_a_binary_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, uploaded("application/pdf", "JVBERi0x\nLjQ=")).await;
+
+    let file = client(&server)
+        .ask_binary(
+            "Signed contract?",
+            ["application/pdf", "image/*"],
+            None::<&str>,
+            Some(fast_options().with_max_binary_bytes(1024)),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(
+        file,
+        BinaryAnswer {
+            content_type: "application/pdf".to_string(),
+            data: b"%PDF-1.4".to_vec(),
+        }
+    );
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(
+        body["question"]["answer_format"],
+        json!({ "type": "binary", "content_types": ["application/pdf", "image/*"] })
+    );
+    assert_eq!(body["max_binary_bytes"], json!(1024));
+}
+
+#[tokio::test]
+async fn rejects_a_binary_answer_over_the_maximum_size() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, uploaded("image/png", "iVBORw0KGgo=")).await;
+
+    let result = client(&server)
+        .ask_binary(
+            "Photo of the meter?",
+            ["image/*"],
+            None::<&str>,
+            Some(fast_options().with_max_binary_bytes(4)),
+        )
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::BinaryAnswerTooLarge { size: 8, max: 4 })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_binary_answer_of_another_content_type() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, uploaded("text/html", "PGgxPg==")).await;
+
+    let result = client(&server)
+        .ask_binary(
+            "Photo of the meter?",
+            ["image/*"],
+            None::<&str>,
+            Some(fast_options()),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn accepts_a_binary_answer_of_any_content_type_with_a_full_wildcard() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, uploaded("text/csv", "YSxi")).await;
+
+    let file = client(&server)
+        .ask_binary("Export?", ["*/*"], None::<&str>, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(file.content_type, "text/csv");
+    assert_eq!(file.data, b"a,b");
+}
+
+#[test]
+fn form_fields_without_a_kind_take_text() {
+    let field: FormField = serde_json::from_value(json!({ "name": "reason", "label": "Reason" }))
+        .expect("valid form field");
+
+    assert!(matches!(field.kind, wait_human::FormFieldKind::Text));
+    assert!(!field.required);
+}
+
+#[test]
+fn binary_answers_round_trip_as_base64() {
+    let content = AnswerContent::Binary {
+        content_type: "application/pdf".to_string(),
+        data: b"%PDF-".to_vec().into(),
+    };
+
+    let wire = serde_json::to_value(&content).expect("serialized");
+    let back: AnswerContent = serde_json::from_value(wire.clone()).expect("deserialized");
+
+    assert_eq!(wire["data"], "JVBERi0=");
+    assert_eq!(back.as_binary(), Some(("application/pdf", &b"%PDF-"[..])));
+}
@@ -0,0 +1,132 @@
+//! Depending on the client through the object-safe `WaitHumanApi` trait
+
+mod common;
+
+use common::*;
+use futures_util::future::BoxFuture;
+use std::sync::{Arc, Mutex};
+use wait_human::{
+    AskOptions, ConfirmationAnswerWithDate, ConfirmationQuestion, WaitHumanApi, WaitHumanError,
+};
+use wiremock::MockServer;
+
+/// Records the subject of every question, then forwards it to another implementation
+struct Recording {
+    inner: Box<dyn WaitHumanApi>,
+    subjects: Arc<Mutex<Vec<String>>>,
+}
+
+impl WaitHumanApi for Recording {
+    fn ask(
+        &self,
+        question: ConfirmationQuestion,
+        options: Option<AskOptions>,
+    ) -> BoxFuture<'_, wait_human::Result<ConfirmationAnswerWithDate>> {
+        self.subjects.lock().unwrap().push(question.subject.clone());
+        self.inner.ask(question, options)
+    }
+}
+
+#[tokio::test]
+async fn the_client_answers_through_a_trait_object() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+    let api: Box<dyn WaitHumanApi> = Box::new(client(&server));
+
+    let answer = api
+        .ask_free_text("Deploy?".to_string(), None, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer, "ship it");
+}
+
+#[tokio::test]
+async fn the_typed_methods_default_to_ask() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+    let subjects = Arc::new(Mutex::new(Vec::new()));
+    let api: Box<dyn WaitHumanApi> = Box::new(Recording {
+        inner: Box::new(client(&server)),
+        subjects: subjects.clone(),
+    });
+
+    let choice = api
+        .ask_multiple_choice(
+            "Deploy?".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            None,
+            Some(fast_options()),
+        )
+        .await
+        .expect("answered");
+
+    assert_eq!(choice, "No");
+    assert_eq!(*subjects.lock().unwrap(), ["Deploy?"]);
+}
+
+#[tokio::test]
+async fn the_default_typed_methods_check_the_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[0]))).await;
+    let api = Recording {
+        inner: Box::new(client(&server)),
+        subjects: Arc::default(),
+    };
+
+    let result = api
+        .ask_free_text("Deploy?".to_string(), None, Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::UnexpectedAnswerType { .. })),
+        "{:?}",
+        result
+    );
+}
+
+/// Answers every question with the same free text, without checking it
+struct Answers(&'static str);
+
+impl WaitHumanApi for Answers {
+    fn ask(
+        &self,
+        _question: ConfirmationQuestion,
+        _options: Option<AskOptions>,
+    ) -> BoxFuture<'_, wait_human::Result<ConfirmationAnswerWithDate>> {
+        let answer = serde_json::from_value(answered(free_text(self.0))["maybe_answer"].clone());
+        Box::pin(async move { Ok(answer.expect("valid answer")) })
+    }
+}
+
+#[tokio::test]
+async fn the_default_free_text_ask_checks_the_answer_options() {
+    let short = Answers("ok")
+        .ask_free_text(
+            "Why?".to_string(),
+            None,
+            Some(AskOptions::new().with_min_length(10)),
+        )
+        .await;
+    let blank = Answers(" ")
+        .ask_free_text(
+            "Why?".to_string(),
+            None,
+            Some(AskOptions::new().with_reject_empty()),
+        )
+        .await;
+
+    assert!(
+        matches!(short, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        short
+    );
+    assert!(
+        matches!(blank, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        blank
+    );
+}
@@ -0,0 +1,80 @@
+//! Discovering the answer formats the server supports
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use wait_human::AnswerFormatKind;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_capabilities(server: &MockServer, response: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path("/capabilities"))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn lists_the_known_formats_the_server_supports_and_caches_them() {
+    let server = MockServer::start().await;
+    mount_capabilities(
+        &server,
+        ResponseTemplate::new(200).set_body_json(json!({
+            "answer_formats": ["freeText", "options", "hologram"],
+        })),
+    )
+    .await;
+    let client = client(&server);
+
+    let formats = client.supported_answer_formats().await.expect("listed");
+    let again = client
+        .clone()
+        .supported_answer_formats()
+        .await
+        .expect("cached");
+
+    assert_eq!(
+        formats,
+        [AnswerFormatKind::FreeText, AnswerFormatKind::Options]
+    );
+    assert_eq!(again, formats);
+    assert_eq!(requests_to(&server, "/capabilities").await.len(), 1);
+}
+
+#[tokio::test]
+async fn assumes_every_known_format_without_a_capabilities_endpoint() {
+    let server = MockServer::start().await;
+    mount_capabilities(&server, ResponseTemplate::new(404)).await;
+
+    let formats = client(&server)
+        .supported_answer_formats()
+        .await
+        .expect("compiled-in formats");
+
+    assert_eq!(formats, AnswerFormatKind::ALL);
+}
+
+#[tokio::test]
+async fn asks_again_after_a_failed_capabilities_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/capabilities"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_capabilities(
+        &server,
+        ResponseTemplate::new(200).set_body_json(json!({ "answer_formats": ["form"] })),
+    )
+    .await;
+    let client = client(&server);
+
+    assert!(client.supported_answer_formats().await.is_err());
+    let formats = client.supported_answer_formats().await.expect("listed");
+
+    assert_eq!(formats, [AnswerFormatKind::Form]);
+}
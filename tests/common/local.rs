@@ -0,0 +1,161 @@
+//! A minimal HTTP server running on the test's own tokio runtime
+//!
+//! Under `#[tokio::test(start_paused = true)]` tokio advances the clock to the next
+//! timer whenever the runtime is idle, including while it waits on sockets. Against
+//! `wiremock`, which serves from its own thread, the next timer is usually the ask's
+//! deadline, so asks would time out in the middle of a request. This server runs on
+//! the test's runtime, next to a timer ticking every [`TICK`], so the clock only moves
+//! by a few ticks while a request is in flight.
+//!
+//! Request times are therefore exact to within some milliseconds: compare them in
+//! whole seconds.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// How far the paused clock can move at once while a request is in flight
+pub const TICK: Duration = Duration::from_millis(10);
+
+/// Status that makes the server close the connection without responding, as a load
+/// balancer dropping an idle connection does
+pub const DROP: u16 = 0;
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct Received {
+    pub method: String,
+    pub path: String,
+    /// Query string, without the `?`. Empty when there is none
+    pub query: String,
+    /// Virtual time since the server started
+    pub at: Duration,
+}
+
+type Handler = Box<dyn Fn(&Received) -> (u16, String, Duration) + Send + Sync>;
+
+pub struct LocalServer {
+    uri: String,
+    received: Arc<Mutex<Vec<Received>>>,
+}
+
+impl LocalServer {
+    /// Starts a server answering every request with `handler`'s status and JSON body
+    pub async fn start(
+        handler: impl Fn(&Received) -> (u16, String) + Send + Sync + 'static,
+    ) -> Self {
+        Self::start_delayed(move |request| {
+            let (status, body) = handler(request);
+            (status, body, Duration::ZERO)
+        })
+        .await
+    }
+
+    /// Like `start`, holding each response for the virtual time `handler` returns, as
+    /// a server holding a long poll open does
+    pub async fn start_delayed(
+        handler: impl Fn(&Received) -> (u16, String, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let uri = format!("http://{}", listener.local_addr().expect("address"));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(Box::new(handler));
+        let started = tokio::time::Instant::now();
+
+        tokio::spawn(async {
+            loop {
+                tokio::time::sleep(TICK).await;
+            }
+        });
+
+        let log = received.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let log = log.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, started, &log, &handler).await;
+                });
+            }
+        });
+
+        LocalServer { uri, received }
+    }
+
+    pub fn uri(&self) -> String {
+        self.uri.clone()
+    }
+
+    /// Returns the requests received for `path`
+    pub fn requests_to(&self, path: &str) -> Vec<Received> {
+        self.received
+            .lock()
+            .expect("request log")
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    started: tokio::time::Instant,
+    log: &Mutex<Vec<Received>>,
+    handler: &Handler,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while buffer.len() < head_end + content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let received = Received {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        at: started.elapsed(),
+    };
+    log.lock().expect("request log").push(received.clone());
+
+    let (status, body, delay) = handler(&received);
+    tokio::time::sleep(delay).await;
+    if status == DROP {
+        return Ok(());
+    }
+    let response = format!(
+        "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,140 @@
+//! Helpers shared by the integration tests, which run the client against a local mock
+//! of the WaitHuman API
+//!
+//! Each endpoint the client calls gets a `mount_*` helper here, so new tests (and tests
+//! for new endpoints) describe the server's behavior in one line.
+
+#![allow(dead_code)]
+
+pub mod local;
+
+use serde_json::{json, Value};
+use std::time::Duration;
+use wait_human::{AnswerFormat, AskOptions, ConfirmationQuestion, QuestionMethod, WaitHuman};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+pub const API_KEY: &str = "test-api-key";
+pub const CONFIRMATION_ID: &str = "conf_123";
+
+/// Returns a client talking to `server`
+pub fn client(server: &MockServer) -> WaitHuman {
+    WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .build()
+        .expect("valid test configuration")
+}
+
+/// Options that poll quickly, without jitter, so tests run fast and deterministically
+pub fn fast_options() -> AskOptions {
+    AskOptions::new()
+        .with_poll_interval(Duration::from_millis(10))
+        .with_poll_jitter(0.0)
+}
+
+pub fn free_text_question(subject: &str) -> ConfirmationQuestion {
+    ConfirmationQuestion {
+        method: QuestionMethod::Push,
+        subject: subject.to_string(),
+        body: None,
+        answer_format: AnswerFormat::FreeText,
+    }
+}
+
+pub fn options_question(subject: &str, choices: &[&str], multiple: bool) -> ConfirmationQuestion {
+    ConfirmationQuestion {
+        method: QuestionMethod::Push,
+        subject: subject.to_string(),
+        body: None,
+        answer_format: AnswerFormat::Options {
+            options: choices.iter().map(|c| c.to_string()).collect(),
+            multiple,
+        },
+    }
+}
+
+/// Body of an answered confirmation with the given answer content
+pub fn answered(content: Value) -> Value {
+    json!({
+        "maybe_answer": {
+            "answer": { "answer_content": content },
+            "answered_at": "2024-05-01T12:00:00Z",
+        },
+    })
+}
+
+pub fn free_text(text: &str) -> Value {
+    json!({ "type": "free_text", "text": text })
+}
+
+pub fn selected(indexes: &[u32]) -> Value {
+    json!({ "type": "options", "selected_indexes": indexes })
+}
+
+/// Body of a confirmation still waiting for an answer
+pub fn pending() -> Value {
+    json!({ "maybe_answer": null })
+}
+
+/// Makes creates succeed with [`CONFIRMATION_ID`]
+pub async fn mount_create(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .mount(server)
+        .await;
+}
+
+/// Makes polls of [`CONFIRMATION_ID`] return `body`
+pub async fn mount_poll(server: &MockServer, body: Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/confirmations/get/{}", CONFIRMATION_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+/// Makes the first `pending_polls` polls of [`CONFIRMATION_ID`] return no answer, and
+/// the following ones return `answer`
+pub async fn mount_poll_answered_after(server: &MockServer, pending_polls: u64, answer: Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/confirmations/get/{}", CONFIRMATION_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(pending()))
+        .up_to_n_times(pending_polls)
+        .with_priority(1)
+        .mount(server)
+        .await;
+    mount_poll(server, answer).await;
+}
+
+/// Makes cancels of [`CONFIRMATION_ID`] succeed
+pub async fn mount_cancel(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path(format!("/confirmations/cancel/{}", CONFIRMATION_ID)))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(server)
+        .await;
+}
+
+/// Returns the requests `server` received for `path`
+pub async fn requests_to(server: &MockServer, request_path: &str) -> Vec<wiremock::Request> {
+    server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|request| request.url.path() == request_path)
+        .collect()
+}
+
+pub fn poll_path() -> String {
+    format!("/confirmations/get/{}", CONFIRMATION_ID)
+}
+
+pub fn cancel_path() -> String {
+    format!("/confirmations/cancel/{}", CONFIRMATION_ID)
+}
@@ -0,0 +1,912 @@
+//! Creating confirmations: the request sent to the server, and how its responses are
+//! handled
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use std::time::Duration;
+use wait_human::{
+    AnswerContent, Attachment, AutoAnswerRule, ConfirmationId, RuleOperator, WaitHuman,
+    WaitHumanError, API_VERSION, SERVER_EXPIRY_MARGIN,
+};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn sends_the_question_with_the_api_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .and(header("Authorization", API_KEY))
+        .and(header("WaitHuman-Api-Version", API_VERSION))
+        .and(header("Content-Type", "application/json"))
+        .and(body_json(json!({
+            "question": {
+                "method": { "type": "push" },
+                "subject": "Deploy?",
+                "body": "To production",
+                "answer_format": {
+                    "type": "options",
+                    "options": ["Yes", "No"],
+                    "multiple": false,
+                },
+            },
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut question = options_question("Deploy?", &["Yes", "No"], false);
+    question.body = Some("To production".to_string());
+    let created = client(&server)
+        .create(question, Some(fast_options()))
+        .await
+        .expect("created");
+
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+}
+
+#[tokio::test]
+async fn sends_the_extras_set_on_the_options() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let options = fast_options()
+        .with_tag("deploy")
+        .with_metadata(json!({ "build": 42 }))
+        .with_idempotency_key("deploy-42");
+    client(&server)
+        .create(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("created");
+
+    let requests = requests_to(&server, "/confirmations/create").await;
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["tags"], json!(["deploy"]));
+    assert_eq!(body["metadata"], json!({ "build": 42 }));
+    assert_eq!(
+        requests[0]
+            .headers
+            .get("Idempotency-Key")
+            .map(|v| v.as_bytes()),
+        Some(&b"deploy-42"[..])
+    );
+}
+
+#[tokio::test]
+async fn targets_the_recipient_and_echoes_the_resolved_one() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let mut answer = answered(free_text("ok"));
+    answer["maybe_answer"]["recipient"] = json!("user_42");
+    mount_poll(&server, answer).await;
+
+    let options = fast_options().with_recipient("alice@example.com");
+    let answer = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer.recipient.as_deref(), Some("user_42"));
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["recipient"], json!("alice@example.com"));
+}
+
+#[tokio::test]
+async fn rejects_a_blank_recipient_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let result = client(&server)
+        .create(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_recipient("  ")),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn sends_auto_answer_rules_and_reports_the_rule_that_answered() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let mut answer = answered(json!({ "type": "options", "selected_indexes": [0] }));
+    answer["maybe_answer"]["answered_by"] = json!("automation");
+    answer["maybe_answer"]["auto_answer_rule"] = json!(0);
+    mount_poll(&server, answer).await;
+
+    let options = fast_options()
+        .with_metadata(json!({ "amount": 42 }))
+        .with_auto_answer_rule(AutoAnswerRule::new(
+            "amount",
+            RuleOperator::LessThan,
+            100,
+            AnswerContent::Options {
+                selected_indexes: vec![0],
+            },
+        ));
+    let answer = client(&server)
+        .ask(
+            options_question("Refund?", &["Approve", "Deny"], false),
+            Some(options),
+        )
+        .await
+        .expect("answered");
+
+    assert!(answer.is_automated());
+    assert_eq!(answer.auto_answer_rule, Some(0));
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(
+        body["rules"],
+        json!([{
+            "field": "amount",
+            "operator": "lt",
+            "value": 100,
+            "answer": { "type": "options", "selected_indexes": [0] },
+        }])
+    );
+}
+
+#[tokio::test]
+async fn rejects_malformed_auto_answer_rules_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let approve = AnswerContent::Options {
+        selected_indexes: vec![0],
+    };
+    let rules = [
+        AutoAnswerRule::new("", RuleOperator::Equal, "eu", approve.clone()),
+        AutoAnswerRule::new("amount", RuleOperator::LessThan, "100", approve),
+        AutoAnswerRule::new(
+            "amount",
+            RuleOperator::LessThan,
+            100,
+            AnswerContent::Options {
+                selected_indexes: vec![5],
+            },
+        ),
+    ];
+
+    for rule in rules {
+        let result = client(&server)
+            .create(
+                options_question("Refund?", &["Approve", "Deny"], false),
+                Some(fast_options().with_auto_answer_rule(rule)),
+            )
+            .await;
+        assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    }
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn rejects_an_empty_or_blank_subject_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    for subject in ["", " \t\n"] {
+        let result = client(&server)
+            .ask(free_text_question(subject), Some(fast_options()))
+            .await;
+        assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    }
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn rejects_a_malformed_inline_attachment_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    for data in ["=", "==", "abc"] {
+        let attachment = Attachment::Inline {
+            name: "notes.txt".to_string(),
+            content_type: "text/plain".to_string(),
+            data: data.to_string(),
+        };
+        let result = client(&server)
+            .ask(
+                free_text_question("Deploy?"),
+                Some(fast_options().with_attachment(attachment)),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(WaitHumanError::InvalidQuestion(_))),
+            "{:?}",
+            result
+        );
+    }
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn rejects_a_blank_subject_in_the_typed_asks() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let client = client(&server);
+
+    let free_text = client
+        .ask_free_text("  ", None::<&str>, Some(fast_options()))
+        .await;
+    let choice = client
+        .ask_multiple_choice("", ["Yes", "No"], None::<&str>, Some(fast_options()))
+        .await;
+
+    assert!(matches!(free_text, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(matches!(choice, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn reports_a_server_error_as_create_failed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::CreateFailed { status: 500, .. })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn reports_maintenance_past_the_timeout_as_service_unavailable() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+            "message": "Scheduled maintenance until 14:00 UTC",
+            "retry_after": 600,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let options = fast_options().with_timeout(Duration::from_secs(5));
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    let Err(WaitHumanError::ServiceUnavailable {
+        retry_after,
+        message,
+    }) = result
+    else {
+        panic!("expected ServiceUnavailable, got {:?}", result);
+    };
+    assert_eq!(retry_after, Some(Duration::from_secs(600)));
+    assert_eq!(
+        message.as_deref(),
+        Some("Scheduled maintenance until 14:00 UTC")
+    );
+}
+
+#[tokio::test]
+async fn reports_a_rejected_key_as_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::Unauthorized { .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn reports_an_unexpected_body_with_its_status_and_content() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("<html><body>Gateway login</body></html>"),
+        )
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    match result {
+        Err(WaitHumanError::InvalidResponse(message)) => {
+            assert!(message.contains("200"), "{}", message);
+            assert!(message.contains("Gateway login"), "{}", message);
+        }
+        other => panic!("expected InvalidResponse, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn accepts_camel_case_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmationRequestId": CONFIRMATION_ID })),
+        )
+        .mount(&server)
+        .await;
+    mount_poll(
+        &server,
+        json!({
+            "maybeAnswer": {
+                "answer": { "answerContent": { "type": "free_text", "text": "ship it" } },
+                "answeredAt": "2024-05-01T12:00:00Z",
+            },
+        }),
+    )
+    .await;
+
+    let answer = client(&server)
+        .ask_free_text("Deploy?", None::<&str>, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer, "ship it");
+}
+
+#[tokio::test]
+async fn fails_without_polling_when_no_channel_reaches_the_human() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmation_request_id": CONFIRMATION_ID,
+            "reachable_channels": 0,
+        })))
+        .mount(&server)
+        .await;
+    mount_cancel(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let options = fast_options()
+        .with_timeout(Duration::from_secs(5))
+        .with_require_channel();
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    assert!(
+        matches!(&result, Err(WaitHumanError::NoChannel { confirmation_id }) if confirmation_id.as_str() == CONFIRMATION_ID),
+        "{:?}",
+        result
+    );
+    let cancel_path = format!("/confirmations/cancel/{}", CONFIRMATION_ID);
+    assert_eq!(requests_to(&server, &cancel_path).await.len(), 1);
+    assert!(requests_to(&server, &poll_path()).await.is_empty());
+}
+
+#[tokio::test]
+async fn sends_disabled_choices_and_their_help() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let options = fast_options()
+        .with_disabled_choice(2)
+        .with_choice_help(2, "Needs a larger quota");
+    client(&server)
+        .create(
+            options_question("Replicas?", &["1", "3", "5"], false),
+            Some(options),
+        )
+        .await
+        .expect("created");
+
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().expect("JSON body");
+    assert_eq!(body["disabled_options"], json!([2]));
+    assert_eq!(body["option_help"], json!({ "2": "Needs a larger quota" }));
+}
+
+#[tokio::test]
+async fn rejects_disabling_every_choice_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let options = fast_options()
+        .with_disabled_choice(0)
+        .with_disabled_choice(1);
+    let result = client(&server)
+        .create(
+            options_question("Deploy?", &["Yes", "No"], false),
+            Some(options),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn rejects_requiring_a_comment_on_a_missing_choice_before_sending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+
+    let result = client(&server)
+        .create(
+            options_question("Deploy?", &["Yes", "No"], false),
+            Some(fast_options().with_comment_required_on(2)),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(requests_to(&server, "/confirmations/create")
+        .await
+        .is_empty());
+}
+
+/// Makes creates answer `202 Accepted` with `response`, as a queueing gateway does
+async fn mount_create_accepted(server: &MockServer, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn takes_the_id_of_an_accepted_create_from_its_location() {
+    let server = MockServer::start().await;
+    mount_create_accepted(
+        &server,
+        ResponseTemplate::new(202)
+            .insert_header(
+                "Location",
+                format!("/confirmations/get/{}", CONFIRMATION_ID),
+            )
+            .set_body_string("queued"),
+    )
+    .await;
+
+    let created = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("created");
+
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+}
+
+#[tokio::test]
+async fn reads_an_accepted_create_relaying_the_servers_body() {
+    let server = MockServer::start().await;
+    mount_create_accepted(
+        &server,
+        ResponseTemplate::new(202)
+            .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+    )
+    .await;
+
+    let created = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("created");
+
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+}
+
+#[tokio::test]
+async fn rejects_an_accepted_create_without_an_id() {
+    let server = MockServer::start().await;
+    mount_create_accepted(&server, ResponseTemplate::new(202)).await;
+
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn reports_a_create_answered_with_no_content_as_failed() {
+    let server = MockServer::start().await;
+    mount_create_accepted(&server, ResponseTemplate::new(204)).await;
+
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::CreateFailed { status: 204, .. })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn keeps_the_api_key_over_headers_set_by_the_client_builder() {
+    use wait_human::reqwest::header::{HeaderMap, HeaderValue};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .and(header("Authorization", API_KEY))
+        .and(header("X-Trace", "abc"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .http_client_builder(|builder| {
+            let mut headers = HeaderMap::new();
+            headers.insert("Authorization", HeaderValue::from_static("wrong"));
+            headers.insert("X-Trace", HeaderValue::from_static("abc"));
+            builder.default_headers(headers)
+        })
+        .build()
+        .unwrap();
+
+    let answer = client
+        .ask_free_text("Name?", None::<&str>, Some(fast_options()))
+        .await
+        .unwrap();
+
+    assert_eq!(answer, "ok");
+}
+
+#[test]
+fn rejects_a_client_builder_combined_with_a_custom_client() {
+    let result = WaitHuman::builder()
+        .api_key(API_KEY)
+        .http_client(wait_human::reqwest::Client::new())
+        .http_client_builder(|builder| builder)
+        .build();
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::InvalidConfig {
+            field: "http_client",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn rejects_an_empty_api_key_as_a_config_error() {
+    let result = WaitHuman::new_from_key("");
+
+    match result {
+        Err(
+            error @ WaitHumanError::InvalidConfig {
+                field: "api_key", ..
+            },
+        ) => {
+            assert_eq!(
+                error.to_string(),
+                "Invalid configuration: api_key is mandatory"
+            );
+        }
+        other => panic!("expected InvalidConfig, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn sends_when_the_human_runs_out_of_time() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+    let before = chrono::Utc::now();
+
+    client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_due_in(Duration::from_secs(600))),
+        )
+        .await
+        .unwrap();
+
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().unwrap();
+    let due_at: chrono::DateTime<chrono::Utc> =
+        serde_json::from_value(body["due_at"].clone()).unwrap();
+    let due_in = (due_at - before).num_seconds();
+    assert!((600..602).contains(&due_in), "{}", due_in);
+}
+
+/// Returns how long after `before` the question sent by the create expires
+async fn sent_due_in(server: &MockServer, before: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let requests = requests_to(server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().unwrap();
+    let due_at: Option<chrono::DateTime<chrono::Utc>> =
+        serde_json::from_value(body["due_at"].clone()).unwrap();
+    due_at.map(|due_at| (due_at - before).num_seconds())
+}
+
+#[tokio::test]
+async fn syncs_the_server_expiry_with_the_timeout_less_the_margin() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+    let before = chrono::Utc::now();
+
+    let options = fast_options()
+        .with_timeout(Duration::from_secs(600))
+        .with_sync_server_expiry();
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .unwrap();
+
+    let due_in = sent_due_in(&server, before).await.expect("expiry sent");
+    let expected = 600 - SERVER_EXPIRY_MARGIN.as_secs() as i64;
+    assert!((expected - 1..=expected).contains(&due_in), "{}", due_in);
+}
+
+#[tokio::test]
+async fn keeps_a_sooner_due_in_when_syncing_the_server_expiry() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+    let before = chrono::Utc::now();
+
+    let options = fast_options()
+        .with_timeout(Duration::from_secs(600))
+        .with_due_in(Duration::from_secs(120))
+        .with_sync_server_expiry();
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .unwrap();
+
+    let due_in = sent_due_in(&server, before).await.expect("expiry sent");
+    assert!((120..122).contains(&due_in), "{}", due_in);
+}
+
+#[tokio::test]
+async fn leaves_the_server_expiry_alone_unless_synced() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_timeout(Duration::from_secs(600))),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(sent_due_in(&server, chrono::Utc::now()).await, None);
+}
+
+#[tokio::test]
+async fn rejects_a_zero_due_in_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_due_in(Duration::ZERO)),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn authenticates_every_request_of_the_call_with_the_api_key_override() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+    mount_cancel(&server).await;
+
+    let result = client(&server)
+        .ask_scoped(
+            free_text_question("Deploy?"),
+            Duration::from_millis(50),
+            Some(fast_options().with_api_key_override("tenant-key")),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::Timeout { .. })));
+    for request_path in ["/confirmations/create", &poll_path(), &cancel_path()] {
+        let requests = requests_to(&server, request_path).await;
+        assert!(!requests.is_empty(), "{}", request_path);
+        for request in requests {
+            assert_eq!(request.headers["Authorization"], "tenant-key");
+        }
+    }
+}
+
+#[tokio::test]
+async fn rejects_an_empty_api_key_override_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .create(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_api_key_override("")),
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::InvalidConfig {
+            field: "api_key_override",
+            ..
+        })
+    ));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+/// Asks through `endpoint` on `server`, which answers any create and poll, and
+/// returns the paths of the requests it received
+async fn request_paths(server: &MockServer, endpoint: String, preserve: bool) -> Vec<String> {
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(answered(free_text("ok"))))
+        .mount(server)
+        .await;
+
+    let mut builder = WaitHuman::builder().api_key(API_KEY).endpoint(endpoint);
+    if preserve {
+        builder = builder.preserve_endpoint();
+    }
+    builder
+        .build()
+        .unwrap()
+        .ask(free_text_question("Name?"), Some(fast_options()))
+        .await
+        .unwrap();
+
+    server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|request| request.url.path().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn trims_the_trailing_slash_of_the_endpoint_by_default() {
+    for endpoint in ["/waithuman", "/waithuman/"] {
+        let server = MockServer::start().await;
+
+        let paths = request_paths(&server, format!("{}{}", server.uri(), endpoint), false).await;
+
+        assert_eq!(
+            paths,
+            [
+                "/waithuman/confirmations/create".to_string(),
+                format!("/waithuman/confirmations/get/{}", CONFIRMATION_ID),
+            ],
+            "{}",
+            endpoint
+        );
+    }
+}
+
+#[tokio::test]
+async fn keeps_the_trailing_slash_of_a_preserved_endpoint() {
+    let server = MockServer::start().await;
+
+    let paths = request_paths(&server, format!("{}/waithuman/", server.uri()), true).await;
+
+    assert_eq!(
+        paths,
+        [
+            "/waithuman/confirmations/create/".to_string(),
+            format!("/waithuman/confirmations/get/{}/", CONFIRMATION_ID),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn adds_no_slash_for_a_preserved_endpoint_without_one() {
+    let server = MockServer::start().await;
+
+    let paths = request_paths(&server, format!("{}/waithuman", server.uri()), true).await;
+
+    assert_eq!(
+        paths,
+        [
+            "/waithuman/confirmations/create".to_string(),
+            format!("/waithuman/confirmations/get/{}", CONFIRMATION_ID),
+        ]
+    );
+}
+
+#[test]
+fn deserializing_a_confirmation_id_validates_it() {
+    let id: ConfirmationId = serde_json::from_value(json!("conf_123")).expect("valid id");
+    assert_eq!(id.as_str(), "conf_123");
+    assert_eq!(serde_json::to_value(&id).expect("serialized"), "conf_123");
+
+    for invalid in ["", "a/b"] {
+        assert!(
+            serde_json::from_value::<ConfirmationId>(json!(invalid)).is_err(),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_an_empty_confirmation_id_from_the_server() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "confirmation_request_id": "" })),
+        )
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidResponse { .. })),
+        "{:?}",
+        result
+    );
+    assert!(requests_to(&server, "/confirmations/get/").await.is_empty());
+}
@@ -0,0 +1,72 @@
+//! Trace logging of HTTP traffic with the `debug-http` feature, and the redaction of
+//! credentials in it
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use std::fmt::{Debug, Write};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use wiremock::MockServer;
+
+/// Subscriber keeping every event as a `field=value` line
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<String>>>);
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = String::new();
+        event.record(&mut Line(&mut line));
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+struct Line<'a>(&'a mut String);
+
+impl Visit for Line<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let _ = write!(self.0, "{}={:?} ", field.name(), value);
+    }
+}
+
+#[tokio::test]
+async fn logs_bodies_with_credentials_redacted() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(capture.clone());
+
+    let options = fast_options().with_metadata(json!({ "order": 1234, "api_token": "s3cr3t" }));
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .unwrap();
+
+    let lines = capture.0.lock().unwrap().join("\n");
+    assert!(lines.contains("Deploy?"), "{}", lines);
+    assert!(lines.contains("1234"), "{}", lines);
+    assert!(lines.contains(CONFIRMATION_ID), "{}", lines);
+    assert!(lines.contains("ship it"), "{}", lines);
+    assert!(!lines.contains(API_KEY), "{}", lines);
+    assert!(!lines.contains("s3cr3t"), "{}", lines);
+}
@@ -0,0 +1,107 @@
+//! Formatting of questions and answers for log lines
+
+use serde_json::json;
+use wait_human::{
+    AnswerContent, AnswerFormat, ConfirmationAnswerWithDate, ConfirmationQuestion, FormField,
+    FormFieldKind, QuestionMethod,
+};
+
+fn question(answer_format: AnswerFormat) -> ConfirmationQuestion {
+    ConfirmationQuestion {
+        method: QuestionMethod::Push,
+        subject: "Deploy?".to_string(),
+        body: Some("A long body that isn't shown".to_string()),
+        answer_format,
+    }
+}
+
+fn answer(
+    content: AnswerContent,
+    asked: Option<ConfirmationQuestion>,
+) -> ConfirmationAnswerWithDate {
+    let mut answer: ConfirmationAnswerWithDate = serde_json::from_value(json!({
+        "answer": { "answer_content": content },
+        "answered_at": "2024-05-01T12:00:00Z",
+    }))
+    .expect("valid answer");
+    answer.question = asked;
+    answer
+}
+
+fn yes_no(multiple: bool) -> AnswerFormat {
+    AnswerFormat::Options {
+        options: vec!["Yes".to_string(), "No".to_string()],
+        multiple,
+    }
+}
+
+#[test]
+fn shows_a_question_with_its_answer_format() {
+    assert_eq!(
+        question(AnswerFormat::FreeText).to_string(),
+        "Deploy? [free_text]"
+    );
+    assert_eq!(
+        question(yes_no(false)).to_string(),
+        "Deploy? [options: Yes, No]"
+    );
+    assert_eq!(
+        question(yes_no(true)).to_string(),
+        "Deploy? [multiple options: Yes, No]"
+    );
+    let form = AnswerFormat::Form {
+        fields: vec![
+            FormField::new("region", "Region", FormFieldKind::Text),
+            FormField::new("replicas", "Replicas", FormFieldKind::Number),
+        ],
+    };
+    assert_eq!(
+        question(form).to_string(),
+        "Deploy? [form: region, replicas]"
+    );
+}
+
+#[test]
+fn shows_each_kind_of_answer_content() {
+    let text = AnswerContent::FreeText {
+        text: "Ship it".to_string(),
+    };
+    assert_eq!(text.to_string(), "Ship it");
+
+    let options = AnswerContent::Options {
+        selected_indexes: vec![0, 2],
+    };
+    assert_eq!(options.to_string(), "#0, #2");
+
+    assert_eq!(AnswerContent::Abstained.to_string(), "(abstained)");
+
+    let form: AnswerContent = serde_json::from_value(json!({
+        "type": "form",
+        "values": { "region": "eu-west", "replicas": 3 },
+    }))
+    .expect("valid form answer");
+    assert_eq!(form.to_string(), "region: eu-west, replicas: 3");
+}
+
+#[test]
+fn shows_the_labels_of_an_answer_when_the_question_was_included() {
+    let selected = || AnswerContent::Options {
+        selected_indexes: vec![1, 5],
+    };
+
+    assert_eq!(
+        answer(selected(), Some(question(yes_no(true)))).to_string(),
+        "No, #5"
+    );
+    assert_eq!(answer(selected(), None).to_string(), "#1, #5");
+    assert_eq!(
+        answer(
+            AnswerContent::FreeText {
+                text: "Ship it".to_string()
+            },
+            Some(question(AnswerFormat::FreeText))
+        )
+        .to_string(),
+        "Ship it"
+    );
+}
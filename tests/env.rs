@@ -0,0 +1,25 @@
+//! Configuration from environment variables
+//!
+//! Kept in its own test binary, as the variables are process-wide and would leak into
+//! the clients of other tests.
+
+use wait_human::{WaitHuman, WaitHumanConfig, WaitHumanError, POLL_INTERVAL_ENV};
+
+#[test]
+fn rejects_a_poll_interval_of_zero_from_the_environment() {
+    std::env::set_var(POLL_INTERVAL_ENV, "0");
+
+    let result = WaitHuman::new(WaitHumanConfig::new("test-api-key"));
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::InvalidConfig {
+                field: POLL_INTERVAL_ENV,
+                ..
+            })
+        ),
+        "{:?}",
+        result
+    );
+}
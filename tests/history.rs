@@ -0,0 +1,96 @@
+//! Fetching the audit trail of a confirmation
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use wait_human::{ConfirmationId, HistoryEventKind, WaitHumanError};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn history_path() -> String {
+    format!("/confirmations/history/{}", CONFIRMATION_ID)
+}
+
+fn confirmation_id() -> ConfirmationId {
+    CONFIRMATION_ID.parse().expect("valid id")
+}
+
+async fn mount_history(server: &MockServer, response: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path(history_path()))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn returns_the_events_of_a_confirmation_oldest_first() {
+    let server = MockServer::start().await;
+    mount_history(
+        &server,
+        ResponseTemplate::new(200).set_body_json(json!({
+            "events": [
+                { "kind": "answered", "at": "2024-05-01T12:30:00Z", "actor": "ana@example.com" },
+                { "kind": "created", "at": "2024-05-01T12:00:00Z" },
+                { "kind": "reminded", "at": "2024-05-01T12:20:00Z", "channel": "slack" },
+                { "kind": "escalated", "at": "2024-05-01T12:10:00Z" },
+            ],
+        })),
+    )
+    .await;
+
+    let history = client(&server)
+        .get_history(&confirmation_id())
+        .await
+        .expect("history");
+
+    let kinds: Vec<HistoryEventKind> = history.iter().map(|event| event.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            HistoryEventKind::Created,
+            HistoryEventKind::Other,
+            HistoryEventKind::Reminded,
+            HistoryEventKind::Answered,
+        ]
+    );
+    assert_eq!(history[2].channel.as_deref(), Some("slack"));
+    assert_eq!(history[3].actor.as_deref(), Some("ana@example.com"));
+}
+
+#[tokio::test]
+async fn a_confirmation_without_events_has_an_empty_history() {
+    for response in [
+        ResponseTemplate::new(200).set_body_json(json!({})),
+        ResponseTemplate::new(200).set_body_json(json!({ "events": [] })),
+        ResponseTemplate::new(204),
+    ] {
+        let server = MockServer::start().await;
+        mount_history(&server, response).await;
+
+        let history = client(&server)
+            .get_history(&confirmation_id())
+            .await
+            .expect("history");
+
+        assert!(history.is_empty(), "{:?}", history);
+    }
+}
+
+#[tokio::test]
+async fn reports_the_history_of_an_unknown_confirmation_as_failed() {
+    let server = MockServer::start().await;
+    mount_history(&server, ResponseTemplate::new(404)).await;
+
+    let result = client(&server).get_history(&confirmation_id()).await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::RequestFailed { status: 404, .. })
+        ),
+        "{:?}",
+        result
+    );
+}
@@ -0,0 +1,57 @@
+//! Converting errors into `std::io::Error`, for callers behind `io::Result` traits
+
+mod common;
+
+use common::*;
+use std::io::ErrorKind;
+use wait_human::WaitHumanError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Asks like a caller implementing a trait that returns `io::Result`
+async fn ask_io(server: &MockServer) -> std::io::Result<String> {
+    let answer = client(server)
+        .ask_free_text("Deploy?", None::<&str>, Some(fast_options()))
+        .await?;
+    Ok(answer)
+}
+
+#[tokio::test]
+async fn a_rejected_key_becomes_permission_denied() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let error = ask_io(&server).await.expect_err("the key is rejected");
+
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    let inner = error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<WaitHumanError>());
+    assert!(matches!(inner, Some(WaitHumanError::Unauthorized { .. })));
+}
+
+#[tokio::test]
+async fn a_malformed_answer_becomes_invalid_data() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[0]))).await;
+
+    let error = ask_io(&server).await.expect_err("not a free-text answer");
+
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn the_message_is_kept() {
+    let error = WaitHumanError::InvalidQuestion("options must not be empty".to_string());
+    let message = error.to_string();
+
+    let error = std::io::Error::from(error);
+
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert_eq!(error.to_string(), message);
+}
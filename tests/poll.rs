@@ -0,0 +1,1231 @@
+//! Waiting for answers: polling until answered, deadlines, and the poll schedule
+
+mod common;
+
+use common::*;
+use futures_util::future::BoxFuture;
+use futures_util::StreamExt;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wait_human::{
+    ApprovalProgress, AskOptions, AskStatus, ConfirmationId, ConfirmationStatus, PollCallback,
+    Sleeper, WaitHuman, WaitHumanConfig, WaitHumanError,
+};
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn polls_until_the_confirmation_is_answered() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll_answered_after(&server, 3, answered(free_text("ship it"))).await;
+
+    let answer = client(&server)
+        .ask_free_text("Deploy?", None::<&str>, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer, "ship it");
+    let polls = requests_to(&server, &poll_path()).await;
+    assert_eq!(polls.len(), 4);
+    for poll in &polls {
+        assert_eq!(
+            poll.headers.get("Authorization").map(|v| v.as_bytes()),
+            Some(API_KEY.as_bytes())
+        );
+        assert_eq!(poll.url.query(), Some("long_poll=false"));
+    }
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn maps_selected_indexes_back_to_the_choices() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let choice = client(&server)
+        .ask_multiple_choice("Deploy?", ["Yes", "No"], None::<&str>, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(choice, "No");
+}
+
+#[tokio::test]
+async fn reports_a_server_error_while_polling_as_poll_failed() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::PollFailed { status: 500, .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn waits_out_a_maintenance_while_polling() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+            "message": "Upgrading the database",
+            "retry_after": 0,
+        })))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+
+    let answer = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered after the maintenance");
+
+    assert_eq!(answer.as_free_text(), Some("ship it"));
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 3);
+}
+
+#[tokio::test]
+async fn reports_maintenance_without_an_end_as_service_unavailable() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(503).set_body_string("<h1>Maintenance</h1>"))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::ServiceUnavailable {
+                retry_after: None,
+                message: None,
+            })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn times_out_with_the_confirmation_id() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let options = fast_options().with_deadline(Instant::now() + Duration::from_millis(200));
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    match result {
+        Err(WaitHumanError::Timeout {
+            confirmation_id, ..
+        }) => assert_eq!(
+            confirmation_id.as_ref().map(ConfirmationId::as_str),
+            Some(CONFIRMATION_ID)
+        ),
+        other => panic!("expected Timeout, got {:?}", other),
+    }
+    assert!(!requests_to(&server, &poll_path()).await.is_empty());
+}
+
+#[tokio::test]
+async fn honors_a_sub_second_timeout() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let timeout = Duration::from_millis(300);
+    let started = Instant::now();
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_timeout(timeout)),
+        )
+        .await;
+    let elapsed = started.elapsed();
+
+    let Err(WaitHumanError::Timeout {
+        elapsed_seconds, ..
+    }) = result
+    else {
+        panic!("expected Timeout, got {:?}", result);
+    };
+    assert!(elapsed >= timeout, "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(550), "{:?}", elapsed);
+    assert!(
+        (0.3..elapsed.as_secs_f64()).contains(&elapsed_seconds),
+        "{}",
+        elapsed_seconds
+    );
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn still_honors_the_timeout_in_seconds() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let options = AskOptions {
+        timeout_seconds: Some(1),
+        ..fast_options()
+    };
+    let started = Instant::now();
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::Timeout { .. })));
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn enforces_the_timeout_in_the_middle_of_a_slow_request() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(pending())
+                .set_delay(Duration::from_secs(30)),
+        )
+        .mount(&server)
+        .await;
+
+    let timeout = Duration::from_secs(1);
+    let started = Instant::now();
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_timeout(timeout)),
+        )
+        .await;
+    let elapsed = started.elapsed();
+
+    assert!(matches!(result, Err(WaitHumanError::Timeout { .. })));
+    assert!(elapsed >= timeout, "{:?}", elapsed);
+    assert!(
+        elapsed < timeout + Duration::from_millis(250),
+        "{:?}",
+        elapsed
+    );
+}
+
+#[tokio::test]
+async fn polls_again_after_a_garbled_response() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"maybe_answer": {"ans"#))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+
+    let answer = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered after the garbled poll");
+
+    assert_eq!(answer.as_free_text(), Some("ship it"));
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 2);
+}
+
+#[tokio::test]
+async fn reports_a_poll_response_of_the_wrong_shape_as_invalid_right_away() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, json!({ "maybe_answer": "yes" })).await;
+
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidResponse(_))));
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 1);
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn takes_a_poll_answered_with_no_content_as_pending() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(204))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+
+    let answer = client(&server)
+        .ask_free_text("Deploy?", None::<&str>, Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer, "ship it");
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 3);
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test]
+async fn reports_a_poll_with_another_success_status_as_failed() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(202))
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .ask_free_text("Deploy?", None::<&str>, Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::PollFailed { status: 202, .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn caps_the_long_poll_hold_to_the_time_left() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ship it"))).await;
+
+    let options = fast_options()
+        .with_long_poll()
+        .with_timeout(Duration::from_secs(5));
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    let polls = requests_to(&server, &poll_path()).await;
+    let hold: u64 = polls[0]
+        .url
+        .query_pairs()
+        .find(|(name, _)| name == "long_poll_timeout")
+        .and_then(|(_, value)| value.parse().ok())
+        .expect("long_poll_timeout is sent");
+    assert!((4..=5).contains(&hold), "{}", hold);
+}
+
+#[tokio::test]
+async fn concurrent_waits_on_one_confirmation_share_its_polls() {
+    let server = MockServer::start().await;
+    mount_poll_answered_after(&server, 3, answered(free_text("ship it"))).await;
+
+    let client = client(&server);
+    let confirmation_id: ConfirmationId = CONFIRMATION_ID.parse().expect("valid id");
+    let (first, second) = tokio::join!(
+        client.wait_for_answer(&confirmation_id, Some(fast_options())),
+        client.wait_for_answer(&confirmation_id, Some(fast_options())),
+    );
+
+    assert_eq!(first.expect("answered").as_free_text(), Some("ship it"));
+    assert_eq!(second.expect("answered").as_free_text(), Some("ship it"));
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 4);
+}
+
+/// Returns immediately, recording how long the client asked to sleep
+#[derive(Default)]
+struct RecordingSleeper {
+    sleeps: Mutex<Vec<Duration>>,
+}
+
+impl Sleeper for RecordingSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.sleeps.lock().expect("sleeps").push(duration);
+        Box::pin(async {})
+    }
+}
+
+#[tokio::test]
+async fn sleeps_between_polls_with_the_configured_sleeper() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll_answered_after(&server, 2, answered(free_text("ship it"))).await;
+
+    let sleeper = Arc::new(RecordingSleeper::default());
+    let config = WaitHumanConfig::new(API_KEY)
+        .with_endpoint(server.uri())
+        .with_sleeper(sleeper.clone());
+    let options = AskOptions::new()
+        .with_poll_interval(Duration::from_secs(60))
+        .with_poll_jitter(0.0);
+
+    // An hour of polling, which the sleeper skips
+    WaitHuman::new(config)
+        .expect("valid configuration")
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    let sleeps = sleeper.sleeps.lock().expect("sleeps").clone();
+    assert_eq!(sleeps, [Duration::from_secs(60), Duration::from_secs(60)]);
+}
+
+#[tokio::test]
+async fn ask_many_returns_the_answers_received_by_the_deadline() {
+    let server = MockServer::start().await;
+    for (subject, id) in [("A", "conf_a"), ("B", "conf_b"), ("C", "conf_c")] {
+        Mock::given(method("POST"))
+            .and(path("/confirmations/create"))
+            .and(body_partial_json(
+                json!({ "question": { "subject": subject } }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "confirmation_request_id": id })),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmations": [
+                { "id": "conf_a", "maybe_answer": answered(free_text("yes"))["maybe_answer"] },
+                { "id": "conf_b", "maybe_answer": null, "status": "declined" },
+                { "id": "conf_c", "maybe_answer": null, "status": "pending" },
+            ],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/cancel/conf_c"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let options = fast_options()
+        .with_deadline(Instant::now() + Duration::from_millis(200))
+        .with_cancel_on_drop();
+    let results = client(&server)
+        .ask_many(["A", "B", "C"].map(free_text_question), Some(options))
+        .await
+        .expect("batch ran");
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].as_ref().ok().and_then(|a| a.as_free_text()),
+        Some("yes")
+    );
+    assert!(
+        matches!(&results[1], Err(WaitHumanError::Declined { confirmation_id }) if confirmation_id.as_str() == "conf_b"),
+        "{:?}",
+        results[1]
+    );
+    assert!(
+        matches!(&results[2], Err(WaitHumanError::Timeout { confirmation_id: Some(id), .. }) if id.as_str() == "conf_c"),
+        "{:?}",
+        results[2]
+    );
+
+    // The pending confirmation is cancelled in the background
+    for _ in 0..50 {
+        if !requests_to(&server, "/confirmations/cancel/conf_c")
+            .await
+            .is_empty()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the pending confirmation was not cancelled");
+}
+
+async fn mount_batch_creates(server: &MockServer) {
+    for (subject, id) in [("A", "conf_a"), ("B", "conf_b")] {
+        Mock::given(method("POST"))
+            .and(path("/confirmations/create"))
+            .and(body_partial_json(
+                json!({ "question": { "subject": subject } }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "confirmation_request_id": id })),
+            )
+            .mount(server)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn ask_many_keeps_polling_after_a_failed_poll() {
+    let server = MockServer::start().await;
+    mount_batch_creates(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmations": [
+                { "id": "conf_a", "maybe_answer": answered(free_text("a"))["maybe_answer"] },
+                { "id": "conf_b", "maybe_answer": answered(free_text("b"))["maybe_answer"] },
+            ],
+        })))
+        .mount(&server)
+        .await;
+
+    let results = client(&server)
+        .ask_many(["A", "B"].map(free_text_question), Some(fast_options()))
+        .await
+        .expect("batch ran");
+
+    assert!(results.iter().all(Result::is_ok), "{:?}", results);
+    assert_eq!(
+        requests_to(&server, "/confirmations/get-many").await.len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn ask_many_fails_the_pending_questions_once_polls_keep_failing() {
+    let server = MockServer::start().await;
+    mount_batch_creates(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let results = client(&server)
+        .ask_many(["A", "B"].map(free_text_question), Some(fast_options()))
+        .await
+        .expect("batch ran");
+
+    assert_eq!(results.len(), 2);
+    for result in &results {
+        assert_eq!(result.as_ref().err().and_then(|e| e.status()), Some(500));
+    }
+}
+
+#[tokio::test]
+async fn ask_many_returns_once_no_poll_fits_before_the_deadline() {
+    let server = MockServer::start().await;
+    mount_batch_creates(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmations": [
+                { "id": "conf_a", "maybe_answer": answered(free_text("a"))["maybe_answer"] },
+                { "id": "conf_b", "maybe_answer": null, "status": "pending" },
+            ],
+        })))
+        .mount(&server)
+        .await;
+
+    let started = Instant::now();
+    let options = fast_options()
+        .with_poll_interval(Duration::from_secs(10))
+        .with_deadline(started + Duration::from_secs(5));
+    let results = client(&server)
+        .ask_many(["A", "B"].map(free_text_question), Some(options))
+        .await
+        .expect("batch ran");
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(results[0].is_ok());
+    assert!(matches!(
+        &results[1],
+        Err(WaitHumanError::Timeout { confirmation_id: Some(id), .. }) if id.as_str() == "conf_b"
+    ));
+}
+
+#[tokio::test]
+async fn shutdown_cancels_the_pending_confirmations_of_ask_many() {
+    let server = MockServer::start().await;
+    mount_batch_creates(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmations": [
+                { "id": "conf_a", "maybe_answer": answered(free_text("a"))["maybe_answer"] },
+                { "id": "conf_b", "maybe_answer": null, "status": "pending" },
+            ],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/cancel/conf_b"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = WaitHuman::new(
+        WaitHumanConfig::new(API_KEY)
+            .with_endpoint(server.uri())
+            .with_in_flight_tracking(),
+    )
+    .expect("valid configuration");
+
+    let batch = tokio::spawn({
+        let client = client.clone();
+        async move {
+            client
+                .ask_many(["A", "B"].map(free_text_question), Some(fast_options()))
+                .await
+        }
+    });
+    while requests_to(&server, "/confirmations/get-many")
+        .await
+        .is_empty()
+    {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let cancelled = client.shutdown(Duration::ZERO).await.expect("shut down");
+    batch.abort();
+
+    assert_eq!(cancelled, 1);
+}
+
+#[tokio::test]
+async fn ask_many_gives_each_question_its_own_idempotency_key() {
+    let server = MockServer::start().await;
+    for (key, id) in [("batch-0", "conf_a"), ("batch-1", "conf_b")] {
+        Mock::given(method("POST"))
+            .and(path("/confirmations/create"))
+            .and(header("Idempotency-Key", key))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "confirmation_request_id": id })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/confirmations/get-many"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "confirmations": [
+                { "id": "conf_a", "maybe_answer": answered(free_text("a"))["maybe_answer"] },
+                { "id": "conf_b", "maybe_answer": answered(free_text("b"))["maybe_answer"] },
+            ],
+        })))
+        .mount(&server)
+        .await;
+
+    let options = fast_options().with_idempotency_key("batch");
+    let results = client(&server)
+        .ask_many(["A", "B"].map(free_text_question), Some(options))
+        .await
+        .expect("batch ran");
+
+    let answers: Vec<_> = results
+        .iter()
+        .map(|result| result.as_ref().ok().and_then(|a| a.as_free_text()))
+        .collect();
+    assert_eq!(answers, [Some("a"), Some("b")]);
+}
+
+#[tokio::test]
+async fn reports_each_status_of_a_confirmation() {
+    let cases = [
+        (pending(), "Pending"),
+        (
+            json!({ "maybe_answer": null, "status": "pending" }),
+            "Pending",
+        ),
+        (
+            json!({ "maybe_answer": null, "status": "expired" }),
+            "Expired",
+        ),
+        (
+            json!({ "maybe_answer": null, "status": "declined" }),
+            "Declined",
+        ),
+        (
+            json!({ "maybe_answer": null, "status": "cancelled" }),
+            "Cancelled",
+        ),
+        (answered(free_text("ok")), "Answered"),
+    ];
+    for (body, expected) in cases {
+        let server = MockServer::start().await;
+        mount_poll(&server, body).await;
+
+        let status = client(&server)
+            .status(&CONFIRMATION_ID.parse().unwrap())
+            .await
+            .unwrap();
+
+        let name = match &status {
+            ConfirmationStatus::Pending => "Pending",
+            ConfirmationStatus::Answered(answer) => {
+                assert_eq!(answer.as_free_text(), Some("ok"));
+                "Answered"
+            }
+            ConfirmationStatus::Expired => "Expired",
+            ConfirmationStatus::Declined => "Declined",
+            ConfirmationStatus::Cancelled => "Cancelled",
+        };
+        assert_eq!(name, expected);
+        assert_eq!(status.is_final(), expected != "Pending");
+    }
+}
+
+/// Waits until `server` received `count` requests to `request_path`
+async fn wait_for_requests(server: &MockServer, request_path: &str, count: usize) {
+    for _ in 0..100 {
+        if requests_to(server, request_path).await.len() >= count {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("{} did not receive {} requests", request_path, count);
+}
+
+#[tokio::test]
+async fn ask_scoped_leaves_an_answered_confirmation_alone() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll_answered_after(&server, 1, answered(free_text("ok"))).await;
+    mount_cancel(&server).await;
+
+    let answer = client(&server)
+        .ask_scoped(
+            free_text_question("Deploy?"),
+            Duration::from_secs(5),
+            Some(fast_options()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(answer.as_free_text(), Some("ok"));
+    assert!(requests_to(&server, &cancel_path()).await.is_empty());
+}
+
+#[tokio::test]
+async fn ask_scoped_cancels_before_returning_an_error() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    mount_cancel(&server).await;
+
+    let result = client(&server)
+        .ask_scoped(
+            free_text_question("Deploy?"),
+            Duration::from_secs(5),
+            Some(fast_options()),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::PollFailed { .. })));
+    assert_eq!(requests_to(&server, &cancel_path()).await.len(), 1);
+}
+
+#[tokio::test]
+async fn ask_scoped_cancels_when_dropped_mid_poll() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+    mount_cancel(&server).await;
+
+    let client = client(&server);
+    let ask = tokio::spawn(async move {
+        client
+            .ask_scoped(
+                free_text_question("Deploy?"),
+                Duration::from_secs(5),
+                Some(fast_options()),
+            )
+            .await
+    });
+    wait_for_requests(&server, &poll_path(), 2).await;
+    ask.abort();
+
+    wait_for_requests(&server, &cancel_path(), 1).await;
+}
+
+#[tokio::test]
+async fn ask_scoped_cancels_again_when_dropped_mid_cancel() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(cancel_path()))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let ask = tokio::spawn(async move {
+        client
+            .ask_scoped(
+                free_text_question("Deploy?"),
+                Duration::from_secs(5),
+                Some(fast_options()),
+            )
+            .await
+    });
+    wait_for_requests(&server, &cancel_path(), 1).await;
+    ask.abort();
+
+    // The interrupted cancellation is started again in the background
+    wait_for_requests(&server, &cancel_path(), 2).await;
+}
+
+#[tokio::test]
+async fn reports_approvals_until_the_quorum_is_reached() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let approvals = |approved| json!({ "maybe_answer": null, "approvals": { "approved": approved, "required": 2 } });
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(approvals(0)))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(approvals(1)))
+        .up_to_n_times(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(selected(&[0]))).await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let options = fast_options()
+        .with_required_approvals(2)
+        .with_on_poll(PollCallback::new(move |poll| {
+            seen.lock().unwrap().push(poll.approvals);
+        }));
+    client(&server)
+        .ask(
+            options_question("Rotate the root keys?", &["Approve", "Reject"], false),
+            Some(options),
+        )
+        .await
+        .expect("answered");
+
+    let requests = requests_to(&server, "/confirmations/create").await;
+    let body: serde_json::Value = requests[0].body_json().unwrap();
+    assert_eq!(body["required_approvals"], json!(2));
+    let progress = progress.lock().unwrap().clone();
+    assert_eq!(
+        progress,
+        [
+            Some(ApprovalProgress {
+                approved: 0,
+                required: 2
+            }),
+            Some(ApprovalProgress {
+                approved: 1,
+                required: 2
+            }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_quorum_of_zero_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask(
+            options_question("Rotate the root keys?", &["Approve", "Reject"], false),
+            Some(fast_options().with_required_approvals(0)),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn stops_after_the_maximum_number_of_polls() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_max_polls(3)),
+        )
+        .await;
+
+    match result {
+        Err(WaitHumanError::MaxPollsExceeded {
+            polls,
+            confirmation_id,
+        }) => {
+            assert_eq!(polls, 3);
+            assert_eq!(confirmation_id.as_str(), CONFIRMATION_ID);
+        }
+        other => panic!("expected MaxPollsExceeded, got {:?}", other),
+    }
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 3);
+}
+
+#[tokio::test]
+async fn stops_at_the_timeout_before_the_maximum_number_of_polls() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(
+                fast_options()
+                    .with_max_polls(1000)
+                    .with_timeout(Duration::from_millis(100)),
+            ),
+        )
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::Timeout { .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn ends_an_ask_stream_after_the_maximum_number_of_polls() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+
+    let client = client(&server);
+    let statuses: Vec<_> = client
+        .ask_stream(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_max_polls(2)),
+        )
+        .collect()
+        .await;
+
+    assert_eq!(statuses.len(), 2, "{:?}", statuses);
+    assert!(statuses[0].is_ok());
+    assert!(matches!(
+        statuses[1],
+        Err(WaitHumanError::MaxPollsExceeded { polls: 2, .. })
+    ));
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 2);
+}
+
+#[tokio::test]
+async fn ask_stream_rejects_an_answer_selecting_a_disabled_choice() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(selected(&[1]))).await;
+
+    let client = client(&server);
+    let statuses: Vec<_> = client
+        .ask_stream(
+            options_question("Merge?", &["Approve", "Reject"], false),
+            Some(fast_options().with_disabled_choice(1)),
+        )
+        .collect()
+        .await;
+
+    assert_eq!(statuses.len(), 1, "{:?}", statuses);
+    assert!(
+        matches!(
+            statuses[0],
+            Err(WaitHumanError::DisabledChoiceSelected { index: 1 })
+        ),
+        "{:?}",
+        statuses
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_maximum_of_zero_polls_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_max_polls(0)),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reports_the_queue_position_and_estimated_wait() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "maybe_answer": null,
+            "queue_position": 3,
+            "estimated_wait_ms": 300_000,
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(pending()))
+        .up_to_n_times(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let options = fast_options().with_on_poll(PollCallback::new(move |poll| {
+        seen.lock()
+            .unwrap()
+            .push((poll.queue_position, poll.estimated_wait));
+    }));
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    let progress = progress.lock().unwrap().clone();
+    assert_eq!(
+        progress,
+        [(Some(3), Some(Duration::from_secs(300))), (None, None)]
+    );
+}
+
+#[tokio::test]
+async fn streams_the_queue_position_of_a_pending_ask() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(
+        &server,
+        json!({ "maybe_answer": null, "queue_position": 2 }),
+    )
+    .await;
+
+    let client = client(&server);
+    let mut statuses =
+        std::pin::pin!(client.ask_stream(free_text_question("Deploy?"), Some(fast_options())));
+    let status = statuses.next().await.expect("a status").expect("pending");
+
+    assert!(
+        matches!(
+            status,
+            AskStatus::Pending {
+                queue_position: Some(2),
+                estimated_wait: None,
+                ..
+            }
+        ),
+        "{:?}",
+        status
+    );
+}
+
+#[tokio::test]
+async fn writes_the_queue_in_progress_lines() {
+    #[derive(Clone, Default)]
+    struct Lines(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let queued = json!({ "maybe_answer": null, "queue_position": 3, "estimated_wait_ms": 290_000 });
+    mount_poll(&server, queued).await;
+
+    let lines = Lines::default();
+    let options = fast_options()
+        .with_max_polls(1)
+        .with_on_poll(PollCallback::progress_writer(lines.clone()));
+    let result = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::MaxPollsExceeded { .. })
+    ));
+    let written = String::from_utf8(lines.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(written, "Waiting for an answer… (0s, #3 in line, ~5 min)\n");
+}
+
+#[tokio::test]
+async fn keeps_waiting_across_a_reassignment() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    let reassigned =
+        json!({ "maybe_answer": null, "status": "reassigned", "reassigned_to": "bob" });
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(reassigned))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("approved by bob"))).await;
+
+    let assignees = Arc::new(Mutex::new(Vec::new()));
+    let seen = assignees.clone();
+    let options =
+        fast_options().with_on_reassign(move |to| seen.lock().unwrap().push(to.to_string()));
+    let answer = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer.as_free_text(), Some("approved by bob"));
+    // Both polls reported bob, who is only announced once
+    assert_eq!(*assignees.lock().unwrap(), ["bob"]);
+    assert_eq!(requests_to(&server, &poll_path()).await.len(), 3);
+}
+
+#[tokio::test]
+async fn streams_a_reassignment_then_the_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(pending()))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(poll_path()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "maybe_answer": null,
+            "status": "reassigned",
+            "reassignedTo": "carol",
+        })))
+        .up_to_n_times(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    let client = client(&server);
+    let statuses = client.ask_stream(free_text_question("Deploy?"), Some(fast_options()));
+    let statuses: Vec<_> = statuses
+        .map(|status| status.expect("no error"))
+        .collect()
+        .await;
+
+    assert_eq!(statuses.len(), 3, "{:?}", statuses);
+    assert!(matches!(statuses[0], AskStatus::Pending { .. }));
+    assert!(matches!(&statuses[1], AskStatus::Reassigned { to } if to == "carol"));
+    assert!(matches!(statuses[2], AskStatus::Answered(_)));
+}
+
+/// Mounts polls reporting the approvers so far, one response per list, in order
+async fn mount_approvals_so_far(server: &MockServer, approvers: &[&[&str]]) {
+    for (position, approvers) in approvers.iter().enumerate() {
+        Mock::given(method("GET"))
+            .and(path(poll_path()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "maybe_answer": null,
+                "partialAnswer": { "approvers": approvers },
+            })))
+            .up_to_n_times(1)
+            .with_priority(position as u8 + 1)
+            .mount(server)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn tells_the_answer_so_far_as_it_progresses() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_approvals_so_far(&server, &[&["alice"], &["alice"], &["alice", "bob"]]).await;
+    mount_poll(&server, answered(free_text("approved"))).await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let options = fast_options().with_on_partial_answer(move |partial| {
+        seen.lock().unwrap().push(partial.approvers.clone())
+    });
+    let answer = client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer.as_free_text(), Some("approved"));
+    // The repeated progress is only told once
+    assert_eq!(
+        *progress.lock().unwrap(),
+        [
+            vec!["alice".to_string()],
+            vec!["alice".to_string(), "bob".to_string()]
+        ]
+    );
+}
+
+#[tokio::test]
+async fn streams_partial_answers_then_the_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_approvals_so_far(&server, &[&["alice"]]).await;
+    mount_poll(&server, answered(free_text("approved"))).await;
+
+    let client = client(&server);
+    let statuses = client.ask_stream(free_text_question("Deploy?"), Some(fast_options()));
+    let statuses: Vec<_> = statuses
+        .map(|status| status.expect("no error"))
+        .collect()
+        .await;
+
+    assert_eq!(statuses.len(), 2, "{:?}", statuses);
+    assert!(
+        matches!(&statuses[0], AskStatus::PartialAnswer(partial) if partial.approvers == ["alice"])
+    );
+    assert!(matches!(statuses[1], AskStatus::Answered(_)));
+}
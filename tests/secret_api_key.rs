@@ -0,0 +1,73 @@
+//! Passing the API key as a `secrecy::SecretString`
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use wait_human::secrecy::SecretString;
+use wait_human::{WaitHuman, WaitHumanConfig, WaitHumanError};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_create_with_api_key(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .and(header("Authorization", API_KEY))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn authenticates_with_the_secret_api_key() {
+    let server = MockServer::start().await;
+    mount_create_with_api_key(&server).await;
+    let client = WaitHuman::new(
+        WaitHumanConfig::from(SecretString::from(API_KEY)).with_endpoint(server.uri()),
+    )
+    .expect("valid configuration");
+
+    let created = client
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("created");
+
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+    assert!(!format!("{:?}", client).contains(API_KEY));
+}
+
+#[tokio::test]
+async fn the_secret_api_key_takes_precedence_over_the_plain_one() {
+    let server = MockServer::start().await;
+    mount_create_with_api_key(&server).await;
+    let client = WaitHuman::builder()
+        .api_key("plain-key")
+        .secret_api_key(SecretString::from(API_KEY))
+        .endpoint(server.uri())
+        .build()
+        .expect("valid configuration");
+
+    client
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("created");
+}
+
+#[test]
+fn an_empty_secret_api_key_is_rejected() {
+    let config = WaitHumanConfig::from(SecretString::from(""));
+
+    let result = WaitHuman::new(config);
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::InvalidConfig {
+            field: "api_key",
+            ..
+        })
+    ));
+}
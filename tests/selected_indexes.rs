@@ -0,0 +1,96 @@
+//! Property tests for mapping selected indexes of an options answer back to choices
+
+use proptest::prelude::*;
+use wait_human::{AnswerContent, AnswerFormat, WaitHumanError};
+
+fn choices(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("Choice {}", i)).collect()
+}
+
+fn single_choice(count: usize) -> AnswerFormat {
+    AnswerFormat::Options {
+        options: choices(count),
+        multiple: false,
+    }
+}
+
+/// Returns the index an `InvalidSelectedIndex` error reported, if that is the error
+fn rejected_index<T>(result: wait_human::Result<T>) -> Option<u32> {
+    match result {
+        Err(WaitHumanError::InvalidSelectedIndex { index }) => Some(index),
+        _ => None,
+    }
+}
+
+/// Indexes biased towards the boundaries, where off-by-one and cast bugs live
+fn index(count: usize) -> impl Strategy<Value = u32> {
+    let count = count as u32;
+    prop_oneof![
+        0..count.max(1) + 2,
+        Just(u32::MAX),
+        Just(u32::MAX - 1),
+        Just(1 << 31),
+        any::<u32>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn single_selection_is_accepted_only_in_range(
+        (count, index) in (0usize..32).prop_flat_map(|count| (Just(count), index(count))),
+    ) {
+        let answer = AnswerContent::Options { selected_indexes: vec![index] };
+        let result = answer.check_format(&single_choice(count));
+
+        if (index as u64) < count as u64 {
+            prop_assert!(result.is_ok());
+        } else {
+            prop_assert_eq!(rejected_index(result), Some(index));
+        }
+    }
+
+    #[test]
+    fn selected_labels_maps_each_index_to_its_choice(
+        (count, indexes) in (1usize..32).prop_flat_map(|count| {
+            (Just(count), proptest::sample::subsequence((0..count as u32).collect::<Vec<_>>(), 0..=count))
+        }),
+    ) {
+        let choices = choices(count);
+        let answer = AnswerContent::Options { selected_indexes: indexes.clone() };
+
+        let labels = answer.selected_labels(&choices).expect("indexes are in range");
+
+        prop_assert_eq!(labels.len(), indexes.len());
+        for ((position, label), index) in labels.iter().zip(&indexes) {
+            prop_assert_eq!(*position as u32, *index);
+            prop_assert_eq!(label, &choices[*position]);
+        }
+    }
+
+    #[test]
+    fn selected_labels_rejects_an_out_of_range_index(
+        (count, index) in (0usize..32).prop_flat_map(|count| {
+            (Just(count), (count as u32..=u32::MAX))
+        }),
+    ) {
+        let answer = AnswerContent::Options { selected_indexes: vec![index] };
+
+        prop_assert_eq!(rejected_index(answer.selected_labels(&choices(count))), Some(index));
+    }
+
+    #[test]
+    fn multiple_selection_is_rejected_if_any_index_is_out_of_range(
+        count in 1usize..32,
+        indexes in proptest::collection::hash_set(any::<u32>(), 1..8),
+    ) {
+        let indexes: Vec<u32> = indexes.into_iter().collect();
+        let answer = AnswerContent::Options { selected_indexes: indexes.clone() };
+        let format = AnswerFormat::Options {
+            options: choices(count),
+            multiple: true,
+        };
+
+        let in_range = indexes.iter().all(|index| (*index as u64) < count as u64);
+        prop_assert_eq!(answer.check_format(&format).is_ok(), in_range);
+    }
+}
@@ -0,0 +1,105 @@
+//! Compile-time checks that the client can be shared across threads, and that its
+//! futures and streams can be spawned on multi-threaded runtimes
+//!
+//! These fail to compile, rather than at runtime, when something that isn't `Send` or
+//! `Sync` (e.g. an `Rc` or a callback without the bounds) slips into the client or is
+//! held across an `.await`.
+
+mod common;
+
+use common::*;
+use futures_util::Stream;
+use std::future::Future;
+use std::time::Duration;
+use wait_human::{
+    AnswerContent, AskOptions, CircuitBreakerConfig, ConfirmationId, ListFilter, PollCallback,
+    WaitHuman, WaitHumanConfig, WaitHumanError,
+};
+
+fn assert_send_sync_clone<T: Send + Sync + Clone + 'static>() {}
+
+fn assert_send<F: Future + Send>(_: F) {}
+
+fn assert_send_stream<S: Stream + Send>(_: S) {}
+
+#[test]
+fn the_client_and_its_settings_are_send_sync_and_clone() {
+    assert_send_sync_clone::<WaitHuman>();
+    assert_send_sync_clone::<WaitHumanConfig>();
+    assert_send_sync_clone::<AskOptions>();
+    assert_send_sync_clone::<CircuitBreakerConfig>();
+    assert_send_sync_clone::<PollCallback>();
+    assert_send_sync_clone::<WaitHumanError>();
+}
+
+/// Options carrying callbacks, which must not make the futures lose Send
+fn options() -> Option<AskOptions> {
+    Some(
+        AskOptions::new()
+            .with_on_poll(PollCallback::new(|_| {}))
+            .with_on_reassign(|_| {})
+            .with_on_partial_answer(|_| {})
+            .with_validator(|_| Ok(())),
+    )
+}
+
+#[test]
+fn the_futures_of_the_client_are_send() {
+    let client = WaitHuman::new_from_key(API_KEY).expect("valid test configuration");
+    let id: ConfirmationId = CONFIRMATION_ID.parse().expect("valid id");
+
+    assert_send(client.ask(free_text_question("Deploy?"), options()));
+    assert_send(client.ask_with_stats(free_text_question("Deploy?"), options()));
+    assert_send(client.ask_scoped(
+        free_text_question("Deploy?"),
+        Duration::from_secs(60),
+        options(),
+    ));
+    assert_send(client.ask_many([free_text_question("Deploy?")], options()));
+    assert_send(client.try_ask(free_text_question("Deploy?"), options()));
+    assert_send(client.create(free_text_question("Deploy?"), options()));
+    assert_send(client.create_idempotent(free_text_question("Deploy?"), options()));
+    assert_send(client.wait_for_answer(&id, options()));
+    assert_send(client.get_answer(&id));
+    assert_send(client.status(&id));
+    assert_send(client.get_answers(std::slice::from_ref(&id)));
+    assert_send(client.get_history(&id));
+    assert_send(client.cancel_confirmation(&id));
+    assert_send(client.nudge_confirmation(&id));
+    assert_send(client.update_confirmation(&id, free_text_question("Deploy?")));
+    assert_send(client.submit_answer(
+        &id,
+        AnswerContent::FreeText {
+            text: "yes".to_string(),
+        },
+    ));
+    assert_send(client.list_confirmations(&ListFilter::default(), None));
+    assert_send(client.verify_credentials());
+    assert_send(client.supported_answer_formats());
+    assert_send(client.shutdown(Duration::from_secs(1)));
+    assert_send_stream(client.subscribe(id.clone()));
+    assert_send_stream(client.ask_stream(free_text_question("Deploy?"), options()));
+}
+
+#[cfg(feature = "convenience")]
+#[test]
+fn the_futures_of_the_convenience_methods_are_send() {
+    let client = WaitHuman::new_from_key(API_KEY).expect("valid test configuration");
+
+    assert_send(client.ask_free_text("Deploy?", None::<&str>, options()));
+    assert_send(client.ask_multiple_choice("Deploy?", ["Yes", "No"], None::<&str>, options()));
+    assert_send(client.ask_searchable_choice(
+        "Customer?",
+        |_: String| async { Ok(Vec::new()) },
+        None::<&str>,
+        options(),
+    ));
+    assert_send(client.ask_choice_or_other("Why?", ["Errors", "Latency"], None::<&str>, options()));
+    assert_send(client.ask_binary("Contract?", ["application/pdf"], None::<&str>, options()));
+    assert_send(client.ask_choice_value(
+        "Replicas?",
+        [("One", 1), ("Three", 3)],
+        None::<&str>,
+        options(),
+    ));
+}
@@ -0,0 +1,107 @@
+//! Request signing: the signature scheme, and the headers the client sends with it
+
+mod common;
+
+use common::*;
+use wait_human::{sign_request, WaitHuman, REQUEST_SIGNATURE_HEADER, REQUEST_TIMESTAMP_HEADER};
+use wiremock::MockServer;
+
+const SECRET: &str = "secret";
+
+#[test]
+fn matches_known_vectors() {
+    assert_eq!(
+        sign_request(
+            SECRET,
+            1700000000,
+            "POST",
+            "/confirmations/create",
+            br#"{"question":{}}"#,
+        ),
+        "v1=493ad4b3f1fef74e97638fc28acd64cf4b9063f8bac0b0310ecbd72a6906aa93"
+    );
+    assert_eq!(
+        sign_request(
+            SECRET,
+            1700000000,
+            "GET",
+            "/confirmations/get/conf_123?long_poll=false",
+            b"",
+        ),
+        "v1=ade879667b47234bebbc3abaa4949fc235507e0429e6b66c92833a49e3304c93"
+    );
+    assert_eq!(
+        sign_request("", 0, "GET", "/", b""),
+        "v1=934bbbab3ecf25373a3bb81fd981e454246256a24d91b74e2332886285179c27"
+    );
+}
+
+#[test]
+fn canonicalizes_the_method_to_uppercase() {
+    assert_eq!(
+        sign_request(SECRET, 1700000000, "post", "/confirmations/create", b"{}"),
+        sign_request(SECRET, 1700000000, "POST", "/confirmations/create", b"{}"),
+    );
+}
+
+/// Recomputes the signature of a received request from its headers and body
+fn expected_signature(request: &wiremock::Request) -> String {
+    let timestamp = request.headers[REQUEST_TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let path_and_query = match request.url.query() {
+        Some(query) => format!("{}?{}", request.url.path(), query),
+        None => request.url.path().to_string(),
+    };
+    sign_request(
+        SECRET,
+        timestamp,
+        request.method.as_str(),
+        &path_and_query,
+        &request.body,
+    )
+}
+
+#[tokio::test]
+async fn signs_every_request_when_a_secret_is_set() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll_answered_after(&server, 1, answered(free_text("ok"))).await;
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .request_signing_secret(SECRET)
+        .build()
+        .unwrap();
+
+    client
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    for request in &requests {
+        let signature = request.headers[REQUEST_SIGNATURE_HEADER].to_str().unwrap();
+        assert_eq!(signature, expected_signature(request), "{}", request.url);
+    }
+}
+
+#[tokio::test]
+async fn sends_no_signature_without_a_secret() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("ok"))).await;
+
+    client(&server)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .unwrap();
+
+    for request in server.received_requests().await.unwrap() {
+        assert!(!request.headers.contains_key(REQUEST_SIGNATURE_HEADER));
+        assert!(!request.headers.contains_key(REQUEST_TIMESTAMP_HEADER));
+    }
+}
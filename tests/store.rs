@@ -0,0 +1,188 @@
+//! Persisting pending confirmations, so waits can resume after a restart
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use wait_human::{
+    ConfirmationStore, Instant, JsonFileStore, MemoryStore, StoredConfirmation, WaitHuman,
+    WaitHumanError,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client_with_store(server: &MockServer, store: Arc<dyn ConfirmationStore>) -> WaitHuman {
+    WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .store(store)
+        .build()
+        .expect("valid test configuration")
+}
+
+#[tokio::test]
+async fn keeps_a_timed_out_confirmation_until_a_resumed_wait_gets_its_answer() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, pending()).await;
+    let store = Arc::new(MemoryStore::new());
+    let client = client_with_store(&server, store.clone());
+
+    let options = fast_options()
+        .with_deadline(Instant::now() + Duration::from_millis(100))
+        .with_metadata(json!({ "order": 1234 }));
+    let result = client.ask(free_text_question("Ship?"), Some(options)).await;
+    assert!(matches!(result, Err(WaitHumanError::Timeout { .. })));
+
+    let stored = client.stored_confirmations().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].id.as_str(), CONFIRMATION_ID);
+    assert_eq!(stored[0].question.subject, "Ship?");
+    assert_eq!(stored[0].metadata, Some(json!({ "order": 1234 })));
+
+    server.reset().await;
+    mount_poll(&server, answered(free_text("yes"))).await;
+    let answer = client
+        .wait_for_answer(&stored[0].id, Some(fast_options()))
+        .await
+        .unwrap();
+
+    assert_eq!(answer.as_free_text(), Some("yes"));
+    assert!(client.stored_confirmations().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn forgets_a_cancelled_confirmation() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_cancel(&server).await;
+    let store = Arc::new(MemoryStore::new());
+    let client = client_with_store(&server, store.clone());
+
+    let created = client
+        .create(free_text_question("Ship?"), Some(fast_options()))
+        .await
+        .unwrap();
+    assert_eq!(store.load().await.unwrap().len(), 1);
+
+    client.cancel_confirmation(&created.id).await.unwrap();
+
+    assert!(store.load().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn keeps_the_updated_question() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    Mock::given(method("PATCH"))
+        .and(path(format!("/confirmations/update/{}", CONFIRMATION_ID)))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    let store = Arc::new(MemoryStore::new());
+    let client = client_with_store(&server, store.clone());
+
+    let created = client
+        .create(free_text_question("Buy at $12.10?"), Some(fast_options()))
+        .await
+        .unwrap();
+    client
+        .update_confirmation(&created.id, free_text_question("Buy at $12.40?"))
+        .await
+        .unwrap();
+
+    let stored = store.load().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].question.subject, "Buy at $12.40?");
+}
+
+#[tokio::test]
+async fn json_file_store_survives_a_restart() {
+    let path = std::env::temp_dir().join(format!(
+        "wait-human-store-{}-{}.json",
+        std::process::id(),
+        line!()
+    ));
+    let stored = |id: &str| StoredConfirmation {
+        id: id.parse().unwrap(),
+        question: free_text_question(id),
+        metadata: None,
+        created_at: chrono::Utc::now(),
+        expires_at: None,
+    };
+
+    let store = JsonFileStore::new(&path);
+    assert!(store.load().await.unwrap().is_empty());
+    store.save(stored("conf_a")).await.unwrap();
+    store.save(stored("conf_b")).await.unwrap();
+    store.save(stored("conf_a")).await.unwrap();
+    drop(store);
+
+    let store = JsonFileStore::new(&path);
+    let ids: Vec<String> = store
+        .load()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|stored| stored.id.to_string())
+        .collect();
+    assert_eq!(ids, ["conf_a", "conf_b"]);
+
+    store.remove(&"conf_a".parse().unwrap()).await.unwrap();
+    let remaining = JsonFileStore::new(&path).load().await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id.as_str(), "conf_b");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn reports_a_corrupt_file_as_store_failed() {
+    let path = std::env::temp_dir().join(format!(
+        "wait-human-store-{}-{}.json",
+        std::process::id(),
+        line!()
+    ));
+    std::fs::write(&path, "not json").unwrap();
+
+    let result = JsonFileStore::new(&path).load().await;
+
+    assert!(matches!(result, Err(WaitHumanError::StoreFailed { .. })));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn cancels_a_confirmation_the_store_failed_to_save() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_cancel(&server).await;
+    let path = std::env::temp_dir()
+        .join(format!("wait-human-missing-{}", std::process::id()))
+        .join("store.json");
+    let client = client_with_store(&server, Arc::new(JsonFileStore::new(path)));
+
+    let result = client
+        .create(free_text_question("Ship?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::StoreFailed { .. })),
+        "{:?}",
+        result
+    );
+    assert_eq!(requests_to(&server, &cancel_path()).await.len(), 1);
+}
+
+#[tokio::test]
+async fn listing_without_a_store_is_a_config_error() {
+    let server = MockServer::start().await;
+
+    let result = client(&server).stored_confirmations().await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::InvalidConfig { field: "store", .. })
+    ));
+}
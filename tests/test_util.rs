@@ -0,0 +1,95 @@
+//! Answer injection with the `test-util` feature, answering asks without a server
+
+mod common;
+
+use common::*;
+use wait_human::{AnswerContent, AnswerSource, WaitHumanError};
+use wiremock::MockServer;
+
+fn text(text: &str) -> AnswerContent {
+    AnswerContent::FreeText {
+        text: text.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn answers_an_ask_without_contacting_the_server() {
+    let server = MockServer::start().await;
+    let client = client(&server).with_answer_injection(text("ship it"));
+
+    let answer = client
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer.as_free_text(), Some("ship it"));
+    assert!(matches!(answer.answered_by, AnswerSource::Human));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn feeds_the_validator_the_injected_answers_in_order() {
+    let server = MockServer::start().await;
+    let client = client(&server)
+        .with_answer_injection(text("tomorrow"))
+        .with_answer_injection(text("ops@example.com"));
+
+    let options = fast_options().with_validator(|answer| match answer.as_free_text() {
+        Some(text) if text.contains('@') => Ok(()),
+        _ => Err("Please enter an email address".to_string()),
+    });
+    let answer = client
+        .ask(free_text_question("Who's on call?"), Some(options))
+        .await
+        .expect("answered");
+
+    assert_eq!(answer.as_free_text(), Some("ops@example.com"));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn still_checks_the_question_and_the_answer() {
+    let server = MockServer::start().await;
+    let client = client(&server).with_answer_injection(AnswerContent::Options {
+        selected_indexes: vec![1],
+    });
+
+    // The invalid ask leaves the answer queued for the next one
+    let invalid = client
+        .ask(options_question("Deploy?", &[], false), None)
+        .await;
+    assert!(matches!(invalid, Err(WaitHumanError::InvalidQuestion(_))));
+
+    let options = fast_options().with_disabled_choice(1);
+    let disabled = client
+        .ask(
+            options_question("Deploy?", &["Yes", "No"], false),
+            Some(options),
+        )
+        .await;
+    assert!(matches!(
+        disabled,
+        Err(WaitHumanError::DisabledChoiceSelected { index: 1 })
+    ));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn asks_the_server_once_the_injected_answers_ran_out() {
+    let server = MockServer::start().await;
+    mount_create(&server).await;
+    mount_poll(&server, answered(free_text("from the server"))).await;
+    let client = client(&server).with_answer_injection(text("injected"));
+
+    let first = client
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered");
+    let second = client
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered");
+
+    assert_eq!(first.as_free_text(), Some("injected"));
+    assert_eq!(second.as_free_text(), Some("from the server"));
+}
@@ -0,0 +1,440 @@
+//! Timeout and poll scheduling under tokio's paused clock, so waits of seconds or
+//! minutes run instantly and the number of polls is exact
+
+mod common;
+
+use common::local::{LocalServer, Received, DROP};
+use common::*;
+use futures_util::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use wait_human::{AskOptions, WaitHuman, WaitHumanError};
+
+/// Starts a server that creates [`CONFIRMATION_ID`] and answers its polls with
+/// `poll_body`
+async fn server(poll_body: fn(usize) -> serde_json::Value) -> LocalServer {
+    let polls = std::sync::atomic::AtomicUsize::new(0);
+    LocalServer::start(move |request| {
+        if request.path == "/confirmations/create" {
+            let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+            return (200, body.to_string());
+        }
+        let poll = polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        (200, poll_body(poll).to_string())
+    })
+    .await
+}
+
+fn local_client(server: &LocalServer) -> WaitHuman {
+    WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .build()
+        .expect("valid test configuration")
+}
+
+/// Asserts the time between consecutive requests, in milliseconds, allowing for the
+/// few ticks each request takes
+fn assert_gaps(requests: &[Received], expected: &[u64]) {
+    let gaps: Vec<u64> = requests
+        .windows(2)
+        .map(|pair| (pair[1].at - pair[0].at).as_millis() as u64)
+        .collect();
+    assert_eq!(gaps.len(), expected.len(), "{:?}", gaps);
+    for (gap, expected) in gaps.iter().zip(expected) {
+        assert!((*expected..expected + 100).contains(gap), "{:?}", gaps);
+    }
+}
+
+fn slow_options(timeout: Duration) -> AskOptions {
+    AskOptions::new()
+        .with_timeout(timeout)
+        .with_poll_interval(Duration::from_secs(3))
+        .with_poll_jitter(0.0)
+}
+
+#[tokio::test(start_paused = true)]
+async fn times_out_after_polling_every_interval() {
+    let server = server(|_| pending()).await;
+
+    let result = local_client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(slow_options(Duration::from_secs(10))),
+        )
+        .await;
+
+    match result {
+        Err(WaitHumanError::Timeout {
+            elapsed_seconds, ..
+        }) => assert!(
+            (10.0..10.1).contains(&elapsed_seconds),
+            "{}",
+            elapsed_seconds
+        ),
+        other => panic!("expected Timeout, got {:?}", other),
+    }
+    let polls = server.requests_to(&poll_path());
+    let times: Vec<u64> = polls.iter().map(|poll| poll.at.as_secs()).collect();
+    assert_eq!(times, [0, 3, 6, 9]);
+}
+
+#[cfg(feature = "convenience")]
+#[tokio::test(start_paused = true)]
+async fn returns_the_answer_once_a_poll_has_it() {
+    let server = server(|poll| {
+        if poll < 5 {
+            pending()
+        } else {
+            answered(free_text("ship it"))
+        }
+    })
+    .await;
+
+    let answer = local_client(&server)
+        .ask_free_text(
+            "Deploy?",
+            None::<&str>,
+            Some(slow_options(Duration::from_secs(60))),
+        )
+        .await
+        .expect("answered before the timeout");
+
+    assert_eq!(answer, "ship it");
+    let polls = server.requests_to(&poll_path());
+    assert_eq!(polls.len(), 6);
+    assert_eq!(polls[5].at.as_secs(), 15);
+}
+
+#[tokio::test(start_paused = true)]
+async fn long_timeouts_elapse_in_virtual_time() {
+    let server = server(|_| pending()).await;
+    let real_start = std::time::Instant::now();
+    let options =
+        slow_options(Duration::from_secs(600)).with_poll_interval(Duration::from_secs(60));
+
+    let result = local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::Timeout { .. })));
+    assert_eq!(server.requests_to(&poll_path()).len(), 10);
+    assert!(real_start.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_as_long_as_the_server_suggests_between_polls() {
+    let server = server(|poll| match poll {
+        0 => json!({ "maybe_answer": null, "poll_interval_ms": 10_000 }),
+        1 => json!({ "maybe_answer": null, "pollIntervalMs": 500 }),
+        2 => pending(),
+        _ => answered(free_text("ship it")),
+    })
+    .await;
+
+    local_client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(slow_options(Duration::from_secs(60))),
+        )
+        .await
+        .expect("answered before the timeout");
+
+    let polls = server.requests_to(&poll_path());
+    assert_gaps(&polls, &[10_000, 500, 3_000]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_a_floor_when_the_server_suggests_polling_right_away() {
+    let server = server(|poll| match poll {
+        0 => json!({ "maybe_answer": null, "poll_interval_ms": 0 }),
+        1 => json!({ "maybe_answer": null, "poll_interval_ms": 100 }),
+        _ => answered(free_text("ship it")),
+    })
+    .await;
+
+    local_client(&server)
+        .ask(
+            free_text_question("Deploy?"),
+            Some(slow_options(Duration::from_secs(60))),
+        )
+        .await
+        .expect("answered before the timeout");
+
+    let polls = server.requests_to(&poll_path());
+    assert_gaps(&polls, &[250, 250]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_on_maintenance_that_never_ends_without_a_timeout() {
+    let server = LocalServer::start(|_| (503, json!({ "retry_after": 0 }).to_string())).await;
+
+    let result = local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(AskOptions::new()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::ServiceUnavailable { .. })),
+        "{:?}",
+        result
+    );
+    let creates = server.requests_to("/confirmations/create");
+    assert_gaps(&creates, &[1000; 5]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_waiting_when_the_human_runs_out_of_time() {
+    let server = server(|_| pending()).await;
+    let options = AskOptions::new()
+        .with_due_in(Duration::from_secs(10))
+        .with_poll_interval(Duration::from_secs(3))
+        .with_poll_jitter(0.0);
+
+    let result = local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::Timeout { .. })),
+        "{:?}",
+        result
+    );
+    assert_eq!(server.requests_to(&poll_path()).len(), 4);
+}
+
+/// Whether a poll asked the server to hold it open
+fn is_long_poll(request: &Received) -> bool {
+    request.query.contains("long_poll=true")
+}
+
+fn adaptive_options() -> AskOptions {
+    AskOptions::new()
+        .with_adaptive_long_poll()
+        .with_poll_interval(Duration::from_secs(3))
+        .with_poll_jitter(0.0)
+}
+
+#[tokio::test(start_paused = true)]
+async fn keeps_long_polling_when_the_server_holds_polls() {
+    let polls = std::sync::atomic::AtomicUsize::new(0);
+    let server = LocalServer::start_delayed(move |request| {
+        if request.path == "/confirmations/create" {
+            let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+            return (200, body.to_string(), Duration::ZERO);
+        }
+        match polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            0 | 1 => (200, pending().to_string(), Duration::from_secs(30)),
+            _ => (200, answered(free_text("ok")).to_string(), Duration::ZERO),
+        }
+    })
+    .await;
+
+    local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(adaptive_options()))
+        .await
+        .unwrap();
+
+    let polls = server.requests_to(&poll_path());
+    assert!(polls.iter().all(is_long_poll), "{:?}", polls);
+    let times: Vec<u64> = polls.iter().map(|poll| poll.at.as_secs()).collect();
+    assert_eq!(times, [0, 30, 60]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn sends_a_long_poll_dropped_during_its_hold_again() {
+    let polls = std::sync::atomic::AtomicUsize::new(0);
+    let server = LocalServer::start_delayed(move |_| {
+        match polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            // Dropped by an intermediary halfway through the hold
+            0 => (DROP, String::new(), Duration::from_secs(5)),
+            _ => (200, answered(free_text("ok")).to_string(), Duration::ZERO),
+        }
+    })
+    .await;
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .max_poll_hold(Duration::from_secs(10))
+        .build()
+        .expect("valid test configuration");
+    let confirmation_id = CONFIRMATION_ID.parse().expect("valid id");
+
+    let answer = client
+        .wait_for_answer(&confirmation_id, Some(AskOptions::new().with_long_poll()))
+        .await
+        .unwrap();
+
+    assert_eq!(answer.as_free_text(), Some("ok"));
+    let polls = server.requests_to(&poll_path());
+    assert!(
+        polls
+            .iter()
+            .all(|poll| poll.query.contains("long_poll_timeout=10")),
+        "{:?}",
+        polls
+    );
+    let times: Vec<u64> = polls.iter().map(|poll| poll.at.as_secs()).collect();
+    assert_eq!(times, [0, 5]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn falls_back_to_short_polls_when_the_server_returns_right_away() {
+    let server = server(|poll| match poll {
+        0..=3 => pending(),
+        _ => answered(free_text("ok")),
+    })
+    .await;
+
+    local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(adaptive_options()))
+        .await
+        .unwrap();
+
+    let polls = server.requests_to(&poll_path());
+    let long: Vec<bool> = polls.iter().map(is_long_poll).collect();
+    assert_eq!(long, [true, true, false, false, false]);
+    // A poll returning early is followed by the poll interval, as a short poll is
+    let times: Vec<u64> = polls.iter().map(|poll| poll.at.as_secs()).collect();
+    assert_eq!(times, [0, 3, 6, 9, 12]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_the_poll_interval_when_a_long_poll_returns_right_away() {
+    let server = server(|poll| match poll {
+        0..=2 => pending(),
+        _ => answered(free_text("ok")),
+    })
+    .await;
+    let options = AskOptions::new()
+        .with_long_poll()
+        .with_poll_interval(Duration::from_secs(3))
+        .with_poll_jitter(0.0);
+
+    local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(options))
+        .await
+        .unwrap();
+
+    let polls = server.requests_to(&poll_path());
+    assert!(polls.iter().all(is_long_poll), "{:?}", polls);
+    let times: Vec<u64> = polls.iter().map(|poll| poll.at.as_secs()).collect();
+    assert_eq!(times, [0, 3, 6, 9]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ask_stream_waits_the_poll_interval_when_a_long_poll_returns_right_away() {
+    let server = server(|poll| match poll {
+        0..=2 => pending(),
+        _ => answered(free_text("ok")),
+    })
+    .await;
+    let options = AskOptions::new()
+        .with_long_poll()
+        .with_poll_interval(Duration::from_secs(3))
+        .with_poll_jitter(0.0);
+    let client = local_client(&server);
+
+    let statuses: Vec<_> = client
+        .ask_stream(free_text_question("Deploy?"), Some(options))
+        .collect()
+        .await;
+
+    assert_eq!(statuses.len(), 4);
+    let times: Vec<u64> = server
+        .requests_to(&poll_path())
+        .iter()
+        .map(|poll| poll.at.as_secs())
+        .collect();
+    assert_eq!(times, [0, 3, 6, 9]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn falls_back_to_short_polls_when_the_server_rejects_long_polls() {
+    let server = LocalServer::start(|request| {
+        if request.path == "/confirmations/create" {
+            let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+            return (200, body.to_string());
+        }
+        if is_long_poll(request) {
+            return (400, json!({ "error": "unknown parameter" }).to_string());
+        }
+        (200, answered(free_text("ok")).to_string())
+    })
+    .await;
+
+    let answer = local_client(&server)
+        .ask(free_text_question("Deploy?"), Some(adaptive_options()))
+        .await
+        .unwrap();
+
+    assert_eq!(answer.as_free_text(), Some("ok"));
+    let long: Vec<bool> = server
+        .requests_to(&poll_path())
+        .iter()
+        .map(is_long_poll)
+        .collect();
+    assert_eq!(long, [true, false]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limit_retries_and_slow_requests_share_the_timeout() {
+    // Every create takes 4s and is rate limited, with a 3s backoff before retrying
+    let server =
+        LocalServer::start_delayed(|_| (429, "{}".to_string(), Duration::from_secs(4))).await;
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .rate_limit_backoff(Duration::from_secs(3))
+        .build()
+        .expect("valid test configuration");
+
+    let result = client
+        .ask(
+            free_text_question("Deploy?"),
+            Some(slow_options(Duration::from_secs(12))),
+        )
+        .await;
+
+    // The second retry would start at 14s, past the timeout, so the ask gives up as
+    // soon as the second create is rate limited, at 11s
+    match result {
+        Err(WaitHumanError::Timeout {
+            elapsed_seconds, ..
+        }) => assert!(
+            (11.0..11.1).contains(&elapsed_seconds),
+            "{}",
+            elapsed_seconds
+        ),
+        other => panic!("expected Timeout, got {:?}", other),
+    }
+    let creates = server.requests_to("/confirmations/create");
+    let times: Vec<u64> = creates.iter().map(|create| create.at.as_secs()).collect();
+    assert_eq!(times, [0, 7]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn idempotent_create_retries_within_the_timeout() {
+    // The create stays in flight on the server, and the key is never found
+    let server = LocalServer::start(|request| match request.path.as_str() {
+        "/confirmations/create" => (409, "{}".to_string()),
+        _ => (404, "{}".to_string()),
+    })
+    .await;
+
+    let started = tokio::time::Instant::now();
+    let result = local_client(&server)
+        .create_idempotent(
+            free_text_question("Deploy?"),
+            Some(AskOptions::new().with_timeout(Duration::from_millis(1500))),
+        )
+        .await;
+
+    // One retry fits in the timeout; the wait before a third attempt doesn't
+    assert!(
+        matches!(result, Err(WaitHumanError::Timeout { .. })),
+        "{:?}",
+        result
+    );
+    assert!(started.elapsed() < Duration::from_millis(1500));
+    assert_eq!(server.requests_to("/confirmations/create").len(), 2);
+}
@@ -0,0 +1,482 @@
+//! Transport hardening: rejecting plain-HTTP endpoints, restricting redirects,
+//! bounding how long connections are held, failing over to fallback endpoints and
+//! short-circuiting a failing server
+
+mod common;
+
+use common::local::LocalServer;
+use common::*;
+use serde_json::json;
+use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use wait_human::{CircuitBreakerConfig, RedirectPolicy, WaitHuman, WaitHumanError};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client_for(endpoint: &str, redirect_policy: RedirectPolicy) -> WaitHuman {
+    WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(endpoint)
+        .redirect_policy(redirect_policy)
+        .build()
+        .expect("valid test configuration")
+}
+
+#[test]
+fn rejects_a_plain_http_endpoint_when_https_is_required() {
+    for endpoint in ["http://api.example.com", "http://10.0.0.1:8080/waithuman"] {
+        let result = WaitHuman::builder()
+            .api_key(API_KEY)
+            .endpoint(endpoint)
+            .require_https()
+            .build();
+
+        assert!(
+            matches!(result, Err(WaitHumanError::InvalidConfig { .. })),
+            "{}",
+            endpoint
+        );
+    }
+}
+
+#[test]
+fn rejects_a_long_poll_hold_under_a_second() {
+    let result = WaitHuman::builder()
+        .api_key(API_KEY)
+        .max_poll_hold(Duration::from_millis(500))
+        .build();
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidConfig { .. })));
+}
+
+#[test]
+fn allows_https_and_loopback_endpoints_when_https_is_required() {
+    for endpoint in [
+        "https://api.example.com",
+        "http://localhost:8080",
+        "http://127.0.0.1:8080",
+        "http://[::1]:8080",
+    ] {
+        let result = WaitHuman::builder()
+            .api_key(API_KEY)
+            .endpoint(endpoint)
+            .require_https()
+            .build();
+
+        assert!(result.is_ok(), "{}", endpoint);
+    }
+}
+
+#[tokio::test]
+async fn rejects_a_plain_http_endpoint_override_when_https_is_required() {
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint("https://api.example.com")
+        .require_https()
+        .build()
+        .unwrap();
+
+    let result = client
+        .ask(
+            free_text_question("Deploy?"),
+            Some(fast_options().with_endpoint_override("http://eu.example.com")),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidConfig { .. })));
+}
+
+/// Makes creates on `server` redirect to `location`
+async fn mount_create_redirect(server: &MockServer, location: String) {
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(307).insert_header("Location", location))
+        .mount(server)
+        .await;
+}
+
+/// Makes `server` accept creates under `/moved`
+async fn mount_moved_create(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/moved/confirmations/create"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "confirmation_request_id": CONFIRMATION_ID })),
+        )
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn does_not_follow_redirects_when_disabled() {
+    let server = MockServer::start().await;
+    mount_create_redirect(
+        &server,
+        format!("{}/moved/confirmations/create", server.uri()),
+    )
+    .await;
+    mount_moved_create(&server).await;
+
+    let result = client_for(&server.uri(), RedirectPolicy::None)
+        .create(free_text_question("Deploy?"), None)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::CreateFailed { status: 307, .. })
+    ));
+    assert!(requests_to(&server, "/moved/confirmations/create")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn follows_redirects_on_the_same_host_only() {
+    let server = MockServer::start().await;
+    mount_create_redirect(
+        &server,
+        format!("{}/moved/confirmations/create", server.uri()),
+    )
+    .await;
+    mount_moved_create(&server).await;
+
+    let created = client_for(&server.uri(), RedirectPolicy::SameHost)
+        .create(free_text_question("Deploy?"), None)
+        .await
+        .unwrap();
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+
+    let elsewhere = MockServer::start().await;
+    mount_moved_create(&elsewhere).await;
+    let server = MockServer::start().await;
+    mount_create_redirect(
+        &server,
+        format!("{}/moved/confirmations/create", elsewhere.uri()),
+    )
+    .await;
+
+    let result = client_for(&server.uri(), RedirectPolicy::SameHost)
+        .create(free_text_question("Deploy?"), None)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::CreateFailed { status: 307, .. })
+    ));
+    assert!(elsewhere.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn does_not_follow_redirects_to_plain_http_when_https_is_required() {
+    let server = MockServer::start().await;
+    mount_create_redirect(
+        &server,
+        "http://api.example.com/moved/confirmations/create".to_string(),
+    )
+    .await;
+    let client = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .require_https()
+        .build()
+        .unwrap();
+
+    let result = client.create(free_text_question("Deploy?"), None).await;
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::CreateFailed { status: 307, .. })
+        ),
+        "{:?}",
+        result
+    );
+}
+
+fn client_with_fallback(primary: &str, fallback: &MockServer, replicated: bool) -> WaitHuman {
+    let builder = WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(primary)
+        .fallback_endpoint(fallback.uri());
+    let builder = if replicated {
+        builder.replicated_endpoints()
+    } else {
+        builder
+    };
+    builder.build().expect("valid test configuration")
+}
+
+#[tokio::test]
+async fn fails_over_to_a_replicated_fallback_on_a_server_error() {
+    let primary = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(502))
+        .mount(&primary)
+        .await;
+    let fallback = MockServer::start().await;
+    mount_create(&fallback).await;
+    mount_poll(&fallback, answered(free_text("ship it"))).await;
+
+    let answer = client_with_fallback(&primary.uri(), &fallback, true)
+        .ask(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("answered through the fallback");
+
+    assert_eq!(answer.as_free_text(), Some("ship it"));
+    assert_eq!(
+        requests_to(&primary, "/confirmations/create").await.len(),
+        1
+    );
+    assert_eq!(
+        requests_to(&fallback, "/confirmations/create").await.len(),
+        1
+    );
+    // The primary is skipped while it cools down
+    assert!(requests_to(&primary, &poll_path()).await.is_empty());
+}
+
+#[tokio::test]
+async fn fails_over_from_an_unreachable_endpoint() {
+    let fallback = MockServer::start().await;
+    mount_create(&fallback).await;
+
+    // Nothing listens on port 1
+    let created = client_with_fallback("http://127.0.0.1:1", &fallback, false)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .expect("created on the fallback");
+
+    assert_eq!(created.id.as_str(), CONFIRMATION_ID);
+}
+
+#[tokio::test]
+async fn returns_the_last_endpoints_answer_when_all_fail() {
+    let primary = MockServer::start().await;
+    let fallback = MockServer::start().await;
+    for server in [&primary, &fallback] {
+        Mock::given(method("POST"))
+            .and(path("/confirmations/create"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(server)
+            .await;
+    }
+
+    let result = client_with_fallback(&primary.uri(), &fallback, true)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(WaitHumanError::CreateFailed { status: 500, .. })
+    ));
+    assert_eq!(
+        requests_to(&primary, "/confirmations/create").await.len(),
+        1
+    );
+    assert_eq!(
+        requests_to(&fallback, "/confirmations/create").await.len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn does_not_fail_over_server_errors_without_replication() {
+    let primary = MockServer::start().await;
+    Mock::given(path(poll_path()))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&primary)
+        .await;
+    let fallback = MockServer::start().await;
+
+    let result = client_with_fallback(&primary.uri(), &fallback, false)
+        .wait_for_answer(
+            &CONFIRMATION_ID.parse().expect("valid id"),
+            Some(fast_options()),
+        )
+        .await;
+
+    assert_eq!(result.unwrap_err().status(), Some(500));
+    assert!(fallback.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn waits_out_maintenance_instead_of_failing_over() {
+    let primary = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/confirmations/create"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&primary)
+        .await;
+    let fallback = MockServer::start().await;
+    mount_create(&fallback).await;
+
+    let result = client_with_fallback(&primary.uri(), &fallback, true)
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::ServiceUnavailable { .. })),
+        "{:?}",
+        result
+    );
+    assert!(fallback.received_requests().await.unwrap().is_empty());
+}
+
+/// Returns a client whose circuit opens after `failure_threshold` failures within a
+/// minute, for 30 seconds
+fn breaker_client(server: &LocalServer, failure_threshold: u32) -> WaitHuman {
+    WaitHuman::builder()
+        .api_key(API_KEY)
+        .endpoint(server.uri())
+        .circuit_breaker(CircuitBreakerConfig {
+            failure_threshold,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        })
+        .build()
+        .expect("valid test configuration")
+}
+
+/// Starts a server answering creates with the status in `status`, and a client whose
+/// circuit opens after 3 failures
+async fn breaker_server(status: Arc<AtomicU16>) -> (LocalServer, WaitHuman) {
+    let server = LocalServer::start(move |_| {
+        let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+        (status.load(Ordering::SeqCst), body.to_string())
+    })
+    .await;
+    let client = breaker_client(&server, 3);
+    (server, client)
+}
+
+async fn create(client: &WaitHuman) -> wait_human::Result<()> {
+    client
+        .create(free_text_question("Deploy?"), Some(fast_options()))
+        .await
+        .map(|_| ())
+}
+
+fn assert_circuit_open(result: wait_human::Result<()>, retry_after: Duration) {
+    match result {
+        Err(WaitHumanError::CircuitOpen { retry_after: wait }) => {
+            assert!(wait <= retry_after && wait + Duration::from_secs(1) > retry_after)
+        }
+        other => panic!("expected CircuitOpen, got {:?}", other),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_circuit_opens_after_the_failure_threshold_and_short_circuits() {
+    let (server, client) = breaker_server(Arc::new(AtomicU16::new(500))).await;
+
+    for _ in 0..3 {
+        assert_eq!(create(&client).await.unwrap_err().status(), Some(500));
+    }
+    assert_circuit_open(create(&client).await, Duration::from_secs(30));
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_circuit_open(create(&client).await, Duration::from_secs(20));
+    assert_eq!(server.requests_to("/confirmations/create").len(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn failures_spread_beyond_the_window_keep_the_circuit_closed() {
+    let (server, client) = breaker_server(Arc::new(AtomicU16::new(500))).await;
+
+    for _ in 0..2 {
+        assert_eq!(create(&client).await.unwrap_err().status(), Some(500));
+    }
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    for _ in 0..2 {
+        assert_eq!(create(&client).await.unwrap_err().status(), Some(500));
+    }
+
+    assert_eq!(server.requests_to("/confirmations/create").len(), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_circuit_lets_a_single_probe_through_after_the_cooldown() {
+    let held = LocalServer::start_delayed(|_| {
+        let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+        (500, body.to_string(), Duration::from_secs(1))
+    })
+    .await;
+    let client = breaker_client(&held, 1);
+    assert!(create(&client).await.is_err());
+    tokio::time::sleep(Duration::from_secs(30)).await;
+
+    let (probe, concurrent) = tokio::join!(create(&client), async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        create(&client).await
+    });
+
+    assert_eq!(probe.unwrap_err().status(), Some(500));
+    assert!(matches!(
+        concurrent,
+        Err(WaitHumanError::CircuitOpen { .. })
+    ));
+    assert_eq!(held.requests_to("/confirmations/create").len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_failed_probe_reopens_the_circuit_and_a_successful_one_closes_it() {
+    let status = Arc::new(AtomicU16::new(500));
+    let (server, client) = breaker_server(status.clone()).await;
+    for _ in 0..3 {
+        assert!(create(&client).await.is_err());
+    }
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(create(&client).await.unwrap_err().status(), Some(500));
+    assert_circuit_open(create(&client).await, Duration::from_secs(30));
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    status.store(200, Ordering::SeqCst);
+    create(&client).await.expect("probe succeeded");
+    create(&client).await.expect("circuit closed");
+
+    assert_eq!(server.requests_to("/confirmations/create").len(), 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limiting_does_not_open_the_circuit() {
+    let requests = AtomicUsize::new(0);
+    let server = LocalServer::start(move |_| {
+        let status = match requests.fetch_add(1, Ordering::SeqCst) {
+            0..5 => 429,
+            _ => 200,
+        };
+        let body = json!({ "confirmation_request_id": CONFIRMATION_ID });
+        (status, body.to_string())
+    })
+    .await;
+    let client = breaker_client(&server, 3);
+
+    create(&client)
+        .await
+        .expect("created once no longer rate limited");
+
+    assert_eq!(server.requests_to("/confirmations/create").len(), 6);
+}
+
+#[test]
+fn rejects_sharing_the_http_client_combined_with_other_client_settings() {
+    let builders = [
+        WaitHuman::builder().connect_timeout(Duration::from_secs(5)),
+        WaitHuman::builder().require_https(),
+    ];
+
+    for builder in builders {
+        let result = builder.api_key(API_KEY).shared_http_client().build();
+
+        assert!(matches!(
+            result,
+            Err(WaitHumanError::InvalidConfig {
+                field: "shared_http_client",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+//! Updating the question of a pending confirmation, and racing with its answer
+
+mod common;
+
+use common::*;
+use serde_json::json;
+use wait_human::{ConfirmationId, WaitHumanError};
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn update_path() -> String {
+    format!("/confirmations/update/{}", CONFIRMATION_ID)
+}
+
+fn confirmation_id() -> ConfirmationId {
+    CONFIRMATION_ID.parse().unwrap()
+}
+
+async fn mount_update(server: &MockServer, response: ResponseTemplate) {
+    Mock::given(method("PATCH"))
+        .and(path(update_path()))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn sends_the_new_question() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path(update_path()))
+        .and(body_json(json!({
+            "question": {
+                "method": { "type": "push" },
+                "subject": "Buy at $12.40?",
+                "body": null,
+                "answer_format": { "type": "free_text" },
+            },
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "previously_seen": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let update = client(&server)
+        .update_confirmation(&confirmation_id(), free_text_question("Buy at $12.40?"))
+        .await
+        .unwrap();
+
+    assert_eq!(update.previously_seen, Some(true));
+}
+
+#[tokio::test]
+async fn accepts_an_update_without_a_body() {
+    let server = MockServer::start().await;
+    mount_update(&server, ResponseTemplate::new(204)).await;
+
+    let update = client(&server)
+        .update_confirmation(&confirmation_id(), free_text_question("Buy at $12.40?"))
+        .await
+        .unwrap();
+
+    assert_eq!(update.previously_seen, None);
+}
+
+#[tokio::test]
+async fn returns_the_answer_that_won_the_race() {
+    let server = MockServer::start().await;
+    mount_update(&server, ResponseTemplate::new(409)).await;
+    mount_poll(&server, answered(free_text("bought"))).await;
+
+    let result = client(&server)
+        .update_confirmation(&confirmation_id(), free_text_question("Buy at $12.40?"))
+        .await;
+
+    assert!(
+        matches!(&result, Err(WaitHumanError::AlreadyAnswered { answer, .. }) if answer.as_free_text() == Some("bought")),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn reports_a_confirmation_that_ended_without_an_answer() {
+    let server = MockServer::start().await;
+    mount_update(&server, ResponseTemplate::new(409)).await;
+    mount_poll(
+        &server,
+        json!({ "maybe_answer": null, "status": "cancelled" }),
+    )
+    .await;
+
+    let result = client(&server)
+        .update_confirmation(&confirmation_id(), free_text_question("Buy at $12.40?"))
+        .await;
+
+    assert!(
+        matches!(result, Err(WaitHumanError::Cancelled { .. })),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn rejects_an_invalid_question_before_sending() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .update_confirmation(&confirmation_id(), options_question("Buy?", &[], false))
+        .await;
+
+    assert!(matches!(result, Err(WaitHumanError::InvalidQuestion(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
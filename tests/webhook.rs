@@ -0,0 +1,116 @@
+//! Webhook verification: the signature, the replay window, and the parsed answer
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use wait_human::{WaitHuman, WaitHumanConfig, WaitHumanError, WebhookAnswer, WEBHOOK_TOLERANCE};
+
+const SECRET: &str = "whsec_test";
+
+const BODY: &[u8] = br#"{
+    "confirmation_request_id": "conf_123",
+    "answer": {
+        "answer": { "answer_content": { "type": "free_text", "text": "yes" } },
+        "answered_at": "2024-05-01T12:00:00Z"
+    }
+}"#;
+
+fn client_with_secret() -> WaitHuman {
+    WaitHuman::new(WaitHumanConfig::new("test-api-key").with_webhook_secret(SECRET))
+        .expect("valid test configuration")
+}
+
+/// Signs `body` at `timestamp` as the server does
+fn sign(timestamp: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Unix time `offset` seconds from now, as sent in the timestamp header
+fn timestamp(offset: i64) -> String {
+    (chrono::Utc::now().timestamp() + offset).to_string()
+}
+
+fn assert_invalid(result: Result<WebhookAnswer, WaitHumanError>) {
+    assert!(
+        matches!(result, Err(WaitHumanError::InvalidWebhook(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn accepts_a_valid_signature() {
+    let now = timestamp(0);
+
+    let webhook = client_with_secret()
+        .verify_webhook(BODY, &sign(&now, BODY), &now)
+        .expect("valid webhook");
+
+    assert_eq!(webhook.confirmation_id.as_str(), "conf_123");
+    assert_eq!(webhook.answer.as_free_text(), Some("yes"));
+}
+
+#[test]
+fn rejects_a_tampered_body() {
+    let now = timestamp(0);
+    let tampered = String::from_utf8_lossy(BODY).replace("yes", "no");
+
+    assert_invalid(client_with_secret().verify_webhook(
+        tampered.as_bytes(),
+        &sign(&now, BODY),
+        &now,
+    ));
+}
+
+#[test]
+fn rejects_a_tampered_timestamp() {
+    let signed_at = timestamp(0);
+    // Derived from the signed timestamp, as a second may pass between two clock reads
+    let tampered = (signed_at.parse::<i64>().unwrap() - 1).to_string();
+
+    assert_invalid(client_with_secret().verify_webhook(BODY, &sign(&signed_at, BODY), &tampered));
+}
+
+#[test]
+fn rejects_a_timestamp_outside_the_tolerance() {
+    let tolerance = WEBHOOK_TOLERANCE.as_secs() as i64;
+    let client = client_with_secret();
+
+    for signed_at in [timestamp(-tolerance - 10), timestamp(tolerance + 10)] {
+        assert_invalid(client.verify_webhook(BODY, &sign(&signed_at, BODY), &signed_at));
+    }
+}
+
+#[test]
+fn rejects_a_malformed_signature() {
+    let now = timestamp(0);
+    let signature = sign(&now, BODY);
+    let client = client_with_secret();
+
+    let unprefixed = signature.trim_start_matches("v1=");
+    assert_invalid(client.verify_webhook(BODY, unprefixed, &now));
+    assert_invalid(client.verify_webhook(BODY, "v1=not-hex", &now));
+    assert_invalid(client.verify_webhook(BODY, &signature, "yesterday"));
+}
+
+#[test]
+fn requires_a_webhook_secret() {
+    let client = WaitHuman::new(WaitHumanConfig::new("test-api-key")).unwrap();
+    let now = timestamp(0);
+
+    let result = client.verify_webhook(BODY, &sign(&now, BODY), &now);
+
+    assert!(
+        matches!(
+            result,
+            Err(WaitHumanError::InvalidConfig {
+                field: "webhook_secret",
+                ..
+            })
+        ),
+        "{:?}",
+        result
+    );
+}